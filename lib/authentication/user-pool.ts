@@ -4,6 +4,7 @@ import { Construct } from "constructs";
 
 export class UserPool extends cognito.UserPool {
   public static readonly CONTENT_CREATORS_GROUP_NAME = "ContentCreators";
+  public static readonly SITE_ADMINS_GROUP_NAME = "SiteAdmins";
 
   constructor(scope: Construct) {
     super(scope, "UserPool", {
@@ -31,5 +32,11 @@ export class UserPool extends cognito.UserPool {
       groupName: UserPool.CONTENT_CREATORS_GROUP_NAME,
       description: "Users who can create content",
     });
+
+    new cognito.UserPoolGroup(this, "SiteAdmins", {
+      userPool: this,
+      groupName: UserPool.SITE_ADMINS_GROUP_NAME,
+      description: "Users who can manage any event and the site",
+    });
   }
 }
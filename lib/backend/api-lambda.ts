@@ -1,17 +1,30 @@
 import { AssetHashType, Duration } from "aws-cdk-lib";
-import { Architecture } from "aws-cdk-lib/aws-lambda";
+import {
+  Architecture,
+  ParamsAndSecretsLayerVersion,
+  ParamsAndSecretsVersions,
+} from "aws-cdk-lib/aws-lambda";
+import * as secretsmanager from "aws-cdk-lib/aws-secretsmanager";
 import { RustFunction } from "cargo-lambda-cdk";
 import { Construct } from "constructs";
 import { Sentry } from "../sentry";
 import { EventTable } from "./event-table";
 import { EventImageStorage } from "./event-image-storage";
 import { UserPool } from "../authentication/user-pool";
+import { Authentication } from "../authentication/authentication";
+import { NotificationSender } from "./notification-sender";
 
 export interface ApiLambdaProps {
   sentry: Sentry;
   eventTable: EventTable;
   images: EventImageStorage;
+  tokenSigningSecret: secretsmanager.ISecret;
+  authentication: Authentication;
+  /** Lets the function email participants */
+  notificationSender?: NotificationSender;
   memory?: number;
+  /** Handler to run instead of the API, e.g. `image-processing` */
+  handler?: string;
 }
 
 export class ApiLambda extends RustFunction {
@@ -24,17 +37,42 @@ export class ApiLambda extends RustFunction {
       bundling: {
         assetHashType: AssetHashType.SOURCE,
       },
+      // Serves the token signing secret to the function, which reads it at cold start
+      paramsAndSecrets: ParamsAndSecretsLayerVersion.fromVersion(
+        ParamsAndSecretsVersions.V1_0_103,
+      ),
       environment: {
         CONTENT_CREATORS_GROUP_NAME: UserPool.CONTENT_CREATORS_GROUP_NAME,
+        GROUP_PERMISSIONS: JSON.stringify({
+          [UserPool.CONTENT_CREATORS_GROUP_NAME]: ["createEvents"],
+          [UserPool.SITE_ADMINS_GROUP_NAME]: [
+            "createEvents",
+            "moderateEvents",
+            "viewAuditLogs",
+            "manageUsers",
+          ],
+        }),
+        USER_POOL_ID: props.authentication.userPool.userPoolId,
+        USER_POOL_CLIENT_ID: props.authentication.client.userPoolClientId,
         SENTRY_DSN: props.sentry.backendDsn.stringValue,
         EVENT_TABLE_ARN: props.eventTable.tableArn,
         EVENT_IMAGES_BUCKET_NAME: props.images.bucketName,
         EVENT_IMAGES_BUCKET_PREFIX: "static/events",
+        EVENT_IMAGE_UPLOADS_PREFIX: EventImageStorage.UPLOADS_PREFIX,
+        TOKEN_SIGNING_SECRET_ARN: props.tokenSigningSecret.secretArn,
         RUST_LOG: "events_api=debug",
+        ...(props.notificationSender
+          ? { NOTIFICATION_SENDER: NotificationSender.SENDER }
+          : {}),
+        ...(props.handler ? { EVENTS_API_HANDLER: props.handler } : {}),
       },
     });
 
     props.images.grantWrite(this);
+    // Listing is needed to delete every image of an event
+    props.images.grantRead(this);
     props.eventTable.grantQuery(this.role!);
+    props.tokenSigningSecret.grantRead(this);
+    props.notificationSender?.grantSendEmail(this);
   }
 }
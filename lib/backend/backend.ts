@@ -1,6 +1,11 @@
+import { Duration } from "aws-cdk-lib";
 import { Construct } from "constructs";
 import { ApiLambda } from "./api-lambda";
+import * as lambda from "aws-cdk-lib/aws-lambda";
+import { DynamoEventSource } from "aws-cdk-lib/aws-lambda-event-sources";
 import * as agw from "aws-cdk-lib/aws-apigatewayv2";
+import * as s3 from "aws-cdk-lib/aws-s3";
+import * as s3n from "aws-cdk-lib/aws-s3-notifications";
 import * as integrations from "aws-cdk-lib/aws-apigatewayv2-integrations";
 import { Sentry } from "../sentry";
 import { EventTable } from "./event-table";
@@ -8,12 +13,17 @@ import { ApiGateway } from "../gateway/api-gateway";
 import { EventImageStorage } from "./event-image-storage";
 import { Authentication } from "../authentication/authentication";
 import { HttpUserPoolAuthorizer } from "aws-cdk-lib/aws-apigatewayv2-authorizers";
+import * as secretsmanager from "aws-cdk-lib/aws-secretsmanager";
+import { Domain } from "../domain";
+import { NotificationSender } from "./notification-sender";
+import * as db from "./events-api/database-structure.json";
 
 export interface BackendProps {
   gateway: ApiGateway;
   sentry: Sentry;
   authentication: Authentication;
   database: EventTable;
+  domain: Domain;
 }
 
 export class Backend extends Construct {
@@ -21,17 +31,78 @@ export class Backend extends Construct {
     super(scope, "Backend");
     const images = new EventImageStorage(this);
     props.gateway.cloudFront.addS3Origin("/static/*", images);
+    // Signs links given to participants, who have no accounts
+    const tokenSigningSecret = new secretsmanager.Secret(
+      this,
+      "TokenSigningSecret",
+      {
+        generateSecretString: {
+          passwordLength: 64,
+          excludePunctuation: true,
+        },
+      },
+    );
+    const notificationSender = new NotificationSender(this, props.domain);
     const apiLambda = new ApiLambda(this, "ApiLambda", {
       sentry: props.sentry,
       eventTable: props.database,
       images,
+      tokenSigningSecret,
+      authentication: props.authentication,
     });
     const imageUploadLambda = new ApiLambda(this, "ImageUploadLambda", {
       sentry: props.sentry,
       eventTable: props.database,
       images,
+      tokenSigningSecret,
+      authentication: props.authentication,
       memory: 2048,
     });
+    const imageProcessingLambda = new ApiLambda(this, "ImageProcessingLambda", {
+      sentry: props.sentry,
+      eventTable: props.database,
+      images,
+      tokenSigningSecret,
+      authentication: props.authentication,
+      memory: 2048,
+      handler: "image-processing",
+    });
+    images.addEventNotification(
+      s3.EventType.OBJECT_CREATED,
+      new s3n.LambdaDestination(imageProcessingLambda),
+      { prefix: `${EventImageStorage.UPLOADS_PREFIX}/` },
+    );
+    // Emails participants about cancelled and deleted events. Failed batches are retried, and
+    // participants already emailed are not emailed again.
+    const notificationLambda = new ApiLambda(this, "NotificationLambda", {
+      sentry: props.sentry,
+      eventTable: props.database,
+      images,
+      tokenSigningSecret,
+      authentication: props.authentication,
+      notificationSender,
+      handler: "notifications",
+    });
+    notificationLambda.addEventSource(
+      new DynamoEventSource(props.database, {
+        startingPosition: lambda.StartingPosition.TRIM_HORIZON,
+        batchSize: 1,
+        retryAttempts: 10,
+        maxRecordAge: Duration.days(1),
+        filters: [
+          lambda.FilterCriteria.filter({
+            eventName: lambda.FilterRule.isEqual("INSERT"),
+            dynamodb: {
+              Keys: {
+                [db.sorting_key_column]: {
+                  S: lambda.FilterRule.beginsWith(db.notice_sort_key_prefix),
+                },
+              },
+            },
+          }),
+        ],
+      }),
+    );
 
     const adminAuthorizer = new HttpUserPoolAuthorizer(
       "EventCreatorAuthorizer",
@@ -58,13 +129,21 @@ export class Backend extends Construct {
       authorizer: adminAuthorizer,
     });
 
+    // Images are decoded where they are uploaded, which needs more memory than the API has
+    const imageUploadIntegration = new integrations.HttpLambdaIntegration(
+      "ImageUploadIntegration",
+      imageUploadLambda,
+    );
     props.gateway.httpApi.addRoutes({
       path: "/api/admin/event/{eventId}/image",
       methods: [agw.HttpMethod.PUT],
-      integration: new integrations.HttpLambdaIntegration(
-        "ImageUploadIntegration",
-        imageUploadLambda,
-      ),
+      integration: imageUploadIntegration,
+      authorizer: adminAuthorizer,
+    });
+    props.gateway.httpApi.addRoutes({
+      path: "/api/admin/event/{eventId}/gallery",
+      methods: [agw.HttpMethod.POST],
+      integration: imageUploadIntegration,
       authorizer: adminAuthorizer,
     });
   }
@@ -1,7 +1,27 @@
+import { Duration } from "aws-cdk-lib";
 import * as s3 from "aws-cdk-lib/aws-s3";
 import { Construct } from "constructs";
 export class EventImageStorage extends s3.Bucket {
+  /** Where images uploaded with presigned links wait to be processed */
+  static readonly UPLOADS_PREFIX = "uploads";
+
   constructor(scope: Construct) {
-    super(scope, "EventImageStorage", {});
+    super(scope, "EventImageStorage", {
+      // Browsers upload images straight to the bucket with presigned links
+      cors: [
+        {
+          allowedMethods: [s3.HttpMethods.PUT],
+          allowedOrigins: ["*"],
+          allowedHeaders: ["*"],
+        },
+      ],
+      lifecycleRules: [
+        {
+          // Uploads are deleted once processed, so these are never processed at all
+          prefix: `${EventImageStorage.UPLOADS_PREFIX}/`,
+          expiration: Duration.days(1),
+        },
+      ],
+    });
   }
 }
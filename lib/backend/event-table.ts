@@ -18,8 +18,38 @@ export class EventTable extends dynamodb.TableV2 {
         name: db.sorting_key_column,
         type: dynamodb.AttributeType.STRING,
       },
+      // Removes the status of image uploads once clients no longer ask for it, and notices to
+      // participants once sent
+      timeToLiveAttribute: db.expires_at_column,
+      // Tells the notification Lambda about queued notices
+      dynamoStream: dynamodb.StreamViewType.KEYS_ONLY,
     });
 
+    // Indexes are not changed in place, which CloudFormation can't do. A changed index is
+    // added under a new name, and the old one is removed once nothing reads it. DynamoDB
+    // creates or deletes one index per deploy, so each of these changes is deployed on its own.
+
+    // Replaced by the creator events index below, and removed in a later deploy
+    this.addGlobalSecondaryIndex({
+      partitionKey: {
+        name: db.creator_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      sortKey: {
+        name: db.partition_key_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      projectionType: dynamodb.ProjectionType.INCLUDE,
+      nonKeyAttributes: [
+        db.title_column,
+        db.location_name_column,
+        db.event_date_column,
+      ],
+      indexName: db.legacy_events_by_creator_index,
+    });
+
+    // Projects what the dashboard shows of each event, and the role of co-organizers, whose
+    // organizer items are listed along with the events they created
     this.addGlobalSecondaryIndex({
       partitionKey: {
         name: db.creator_column,
@@ -34,10 +64,16 @@ export class EventTable extends dynamodb.TableV2 {
         db.title_column,
         db.location_name_column,
         db.event_date_column,
+        db.event_visible_column,
+        db.participants_count_column,
+        db.waitlist_count_column,
+        db.organizer_role_column,
       ],
       indexName: db.events_by_creator_index,
     });
 
+    // Replaced by the listing index below, and removed in a later deploy once
+    // `events-api normalize-dates` has run
     this.addGlobalSecondaryIndex({
       partitionKey: {
         name: db.sorting_key_column,
@@ -53,8 +89,44 @@ export class EventTable extends dynamodb.TableV2 {
         db.location_name_column,
         db.event_date_column,
       ],
+      indexName: db.events_by_type_index,
+    });
+
+    // Sorted by event date, so the public listing can page through events in date order. Dates
+    // stored before they were kept in UTC are rewritten by `events-api normalize-dates`.
+    this.addGlobalSecondaryIndex({
+      partitionKey: {
+        name: db.sorting_key_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      sortKey: {
+        name: db.event_date_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      projectionType: dynamodb.ProjectionType.INCLUDE,
+      nonKeyAttributes: [
+        db.title_column,
+        db.location_name_column,
+        db.event_visible_column,
+        db.image_column,
+      ],
       indexName: db.events_listing_index,
     });
+
+    // Finds the signups in a participant calendar feed. Only signups have a calendar secret.
+    this.addGlobalSecondaryIndex({
+      partitionKey: {
+        name: db.calendar_secret_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      sortKey: {
+        name: db.sorting_key_column,
+        type: dynamodb.AttributeType.STRING,
+      },
+      projectionType: dynamodb.ProjectionType.INCLUDE,
+      nonKeyAttributes: [db.signup_status_column],
+      indexName: db.signups_by_calendar_index,
+    });
   }
 
   grantQuery(principal: iam.IPrincipal) {
@@ -0,0 +1,137 @@
+//! Measures how long the image pipeline takes and how much memory it needs, to size the image
+//! Lambda and pick encoder settings. Run with the settings to compare, e.g.
+//! `AVIF_SPEED=6 cargo bench --bench images`. Photos to measure can be listed, separated by
+//! commas, in `BENCHMARK_PHOTOS`. Without any, a generated photo the size of a typical 12
+//! megapixel phone photo is used.
+
+use std::{env, io::Cursor, time::Duration};
+
+use bytes::Bytes;
+use criterion::{criterion_group, Criterion};
+use events_api::images::{
+    encode_image, formats::UploadFormat, read_image, resize_to_variants, settings::ImageSettings,
+    variants::ImageFormat,
+};
+use image::{codecs::jpeg::JpegEncoder, DynamicImage, RgbImage};
+
+/// A 12 megapixel photo in 4:3, as most phones take
+const PHOTO_WIDTH: u32 = 4032;
+const PHOTO_HEIGHT: u32 = 3024;
+
+/// Noise over a gradient, which compresses about as badly as a detailed photo
+fn generated_photo() -> Bytes {
+    let mut state: u32 = 0x9e37_79b9;
+    let image = RgbImage::from_fn(PHOTO_WIDTH, PHOTO_HEIGHT, |x, y| {
+        state ^= state << 13;
+        state ^= state >> 17;
+        state ^= state << 5;
+        let [r, g, b, _] = state.to_le_bytes();
+        let base = ((x + y) * 255 / (PHOTO_WIDTH + PHOTO_HEIGHT)) as u8;
+        let pixel = |noise: u8| base - base / 8 + noise / 8;
+        image::Rgb([pixel(r), pixel(g), pixel(b)])
+    });
+    let mut photo = Vec::new();
+    DynamicImage::ImageRgb8(image)
+        .write_with_encoder(JpegEncoder::new_with_quality(
+            &mut Cursor::new(&mut photo),
+            95,
+        ))
+        .expect("Generated photo should encode");
+    Bytes::from(photo)
+}
+
+fn photos() -> Vec<(String, Bytes)> {
+    match env::var("BENCHMARK_PHOTOS") {
+        Ok(files) => files
+            .split(',')
+            .map(|file| {
+                let photo = std::fs::read(file).expect("Photo should be readable");
+                (file.to_owned(), Bytes::from(photo))
+            })
+            .collect(),
+        Err(_) => vec![("generated photo".to_owned(), generated_photo())],
+    }
+}
+
+/// Forgets the most memory the process has had resident so far. Only Linux keeps track of it.
+fn reset_peak_memory() {
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// The most memory the process has had resident, in kB
+fn peak_memory() -> Option<u64> {
+    std::fs::read_to_string("/proc/self/status")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()
+}
+
+fn decode(photo: &Bytes) -> DynamicImage {
+    let format = UploadFormat::sniff(photo).expect("Photo should be in a supported format");
+    read_image(photo.clone(), format).expect("Photo should decode")
+}
+
+/// Runs the whole pipeline once, on its own, to see how much memory one upload needs
+fn report_memory(name: &str, photo: &Bytes, settings: &ImageSettings) {
+    reset_peak_memory();
+    let image = decode(photo);
+    let mut output_bytes = 0;
+    let (resized, _) = resize_to_variants(&image, None, settings.resize_filter);
+    for (_, image) in resized {
+        for format in ImageFormat::ALL {
+            output_bytes += encode_image(&image, format, settings)
+                .expect("Variant should encode")
+                .len();
+        }
+    }
+    let peak = peak_memory().map_or("-".to_owned(), |memory| (memory / 1024).to_string());
+    println!(
+        "{name}: {} kB in, {} kB out, peak memory {peak} MB",
+        photo.len() / 1024,
+        output_bytes / 1024,
+    );
+}
+
+fn image_pipeline(c: &mut Criterion) {
+    let settings = ImageSettings::configured();
+    println!("{settings:?}");
+    for (name, photo) in photos() {
+        report_memory(&name, &photo, &settings);
+
+        let mut group = c.benchmark_group(&name);
+        // A run takes seconds, so only a few fit in a reasonable time
+        group
+            .sample_size(10)
+            .measurement_time(Duration::from_secs(30));
+        group.bench_function("decode", |b| b.iter(|| decode(&photo)));
+
+        let image = decode(&photo);
+        group.bench_function("resize", |b| {
+            b.iter(|| resize_to_variants(&image, None, settings.resize_filter))
+        });
+
+        let (resized, _) = resize_to_variants(&image, None, settings.resize_filter);
+        for format in ImageFormat::ALL {
+            group.bench_function(format!("encode {format:?}"), |b| {
+                b.iter(|| {
+                    for (_, image) in &resized {
+                        encode_image(image, format, &settings).expect("Variant should encode");
+                    }
+                })
+            });
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, image_pipeline);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
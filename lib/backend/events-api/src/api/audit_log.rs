@@ -0,0 +1,51 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    audit::models::{AuditAction, AuditEntry},
+    events::queries::DynamodbQueries,
+};
+
+use super::error::RestError;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntryResponse {
+    pub actor: String,
+    pub action: AuditAction,
+    pub target: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub audited_at: time::OffsetDateTime,
+}
+
+impl From<AuditEntry> for AuditEntryResponse {
+    fn from(value: AuditEntry) -> Self {
+        Self {
+            actor: value.actor,
+            action: value.action,
+            target: value.target,
+            audited_at: value.audited_at,
+        }
+    }
+}
+
+/// Lists the changes organizers made to any event, oldest first. The log of a deleted event is
+/// kept, and ends with its deletion.
+pub async fn get_audit_log(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+) -> Result<Json<Vec<AuditEntryResponse>>, RestError> {
+    let entries = dynamodb.get_audit_log(event_id).await?;
+    // Events nobody has changed have no log
+    if entries.is_empty() {
+        dynamodb.get_event(event_id).await?;
+    }
+
+    Ok(Json(
+        entries.into_iter().map(AuditEntryResponse::from).collect(),
+    ))
+}
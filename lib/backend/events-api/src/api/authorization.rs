@@ -0,0 +1,38 @@
+use uuid::Uuid;
+
+use crate::{
+    authentication::{permissions::Permission, Claims},
+    events::{models::Event, queries::DynamodbQueries},
+    organizers::{errors::InsufficientRoleError, models::EventAccess},
+};
+
+use super::error::{NotEventOwnerError, RestError};
+
+/// Gets the event, provided the caller organizes it with a role that allows `access` or may
+/// moderate any event. Events the caller cannot access are reported as not found, as if they
+/// did not exist.
+pub async fn authorize(
+    dynamodb: &DynamodbQueries,
+    event_id: Uuid,
+    claims: &Claims,
+    access: EventAccess,
+) -> Result<Event, RestError> {
+    let event = dynamodb.get_event(event_id).await?;
+
+    if claims.has_permission(Permission::ModerateEvents) {
+        tracing::debug!("{} moderates event {event_id}", claims.username);
+        return Ok(event);
+    }
+
+    match dynamodb.organizer_role(&event, &claims.username).await? {
+        Some(role) if role.allows(access) => Ok(event),
+        Some(role) => {
+            tracing::debug!(
+                "{} is {role} of event {event_id}, which does not allow {access:?}",
+                claims.username
+            );
+            Err(InsufficientRoleError.into())
+        }
+        None => Err(NotEventOwnerError.into()),
+    }
+}
@@ -0,0 +1,150 @@
+//! iCalendar exports. Calendar clients subscribe to feeds without logging in, so feeds are reached
+//! through signed tokens.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    calendar::{
+        errors::CalendarError,
+        ics::{self, Calendar},
+        tokens::{OrganizerFeedToken, ParticipantFeedToken},
+    },
+    events::{
+        errors::GetEventError,
+        listing::{CreatorEventSummary, CreatorEventsFilter},
+        models::Event,
+        queries::DynamodbQueries,
+    },
+    localization::LanguagePreference,
+};
+
+use super::{error::RestError, language::Language};
+
+/// Events read per page when collecting the events of an organizer
+const FEED_PAGE_SIZE: usize = 100;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedToken {
+    pub token: String,
+}
+
+/// Name of a feed, as shown by calendar clients
+fn feed_name(
+    preference: &LanguagePreference,
+    english: &'static str,
+    swedish: &'static str,
+) -> &'static str {
+    match preference.resolve(["en", "sv"].iter()) {
+        Some("sv") => swedish,
+        _ => english,
+    }
+}
+
+fn calendar_response(calendar: Calendar) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, ics::CONTENT_TYPE)],
+        calendar.finish(),
+    )
+}
+
+pub async fn get_event_calendar(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    Language(preference): Language,
+) -> Result<impl IntoResponse, RestError> {
+    let event = dynamodb.get_event(event_id).await?;
+    if !event.event_visible {
+        return Err(GetEventError::NotFound.into());
+    }
+
+    let mut calendar = Calendar::new(None);
+    calendar.add_event(&event, &preference, None, time::OffsetDateTime::now_utc());
+    Ok(calendar_response(calendar))
+}
+
+/// Token for the feed of the events the caller organizes
+pub async fn get_organizer_feed_token(claims: Claims) -> Json<FeedToken> {
+    Json(FeedToken {
+        token: OrganizerFeedToken {
+            username: claims.username,
+        }
+        .encode(),
+    })
+}
+
+pub async fn get_organizer_calendar(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(token): Path<String>,
+    Language(preference): Language,
+) -> Result<impl IntoResponse, RestError> {
+    let token = OrganizerFeedToken::decode(&token).map_err(|_| CalendarError::InvalidToken)?;
+    let now = time::OffsetDateTime::now_utc();
+
+    let mut event_ids = Vec::new();
+    let mut start_key = None;
+    loop {
+        let filter = CreatorEventsFilter {
+            period: None,
+            visible: Some(true),
+            now,
+        };
+        let page = dynamodb
+            .list_creator_events(&token.username, filter, FEED_PAGE_SIZE, start_key)
+            .await?;
+        event_ids.extend(
+            page.events
+                .iter()
+                .map(|event: &CreatorEventSummary| event.id),
+        );
+        start_key = page.last_evaluated_key;
+        if start_key.is_none() {
+            break;
+        }
+    }
+
+    let mut events: Vec<Event> = dynamodb.get_events(&event_ids).await?;
+    events.sort_by_key(|event| event.event_date);
+
+    let mut calendar = Calendar::new(Some(feed_name(&preference, "Events", "Evenemang")));
+    for event in &events {
+        calendar.add_event(event, &preference, None, now);
+    }
+    Ok(calendar_response(calendar))
+}
+
+pub async fn get_participant_calendar(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(token): Path<String>,
+    Language(preference): Language,
+) -> Result<impl IntoResponse, RestError> {
+    let token = ParticipantFeedToken::decode(&token).map_err(|_| CalendarError::InvalidToken)?;
+    let now = time::OffsetDateTime::now_utc();
+
+    let statuses: HashMap<Uuid, _> = dynamodb
+        .get_signups_by_calendar(token.calendar_secret)
+        .await?
+        .into_iter()
+        .map(|signup| (signup.event_id, signup.status))
+        .collect();
+    let event_ids: Vec<Uuid> = statuses.keys().copied().collect();
+    let mut events: Vec<Event> = dynamodb.get_events(&event_ids).await?;
+    events.retain(|event| event.event_visible);
+    events.sort_by_key(|event| event.event_date);
+
+    let mut calendar = Calendar::new(Some(feed_name(&preference, "Signups", "Anmälningar")));
+    for event in &events {
+        calendar.add_event(event, &preference, statuses.get(&event.id).copied(), now);
+    }
+    Ok(calendar_response(calendar))
+}
@@ -0,0 +1,77 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::{
+    audit::models::AuditAction,
+    authentication::Claims,
+    events::{errors::DeleteEventError, queries::DynamodbQueries},
+    images::delete_event_images,
+    notifications::models::{Notice, PendingNotice},
+    organizers::models::EventAccess,
+};
+
+use super::{authorization::authorize, error::RestError};
+
+/// Marks the event as cancelled and queues an email to the participants. Participants also see
+/// this on the event and through their signup links, and can no longer sign up or change their
+/// signups.
+pub async fn cancel_event(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::Manage).await?;
+
+    // Cancelling again keeps the first cancellation, which participants were told about
+    let notice = if event.cancelled_at.is_none() {
+        let signups = dynamodb.get_signups(event_id).await?;
+        PendingNotice::new(&event, &signups, Notice::Cancelled)
+    } else {
+        None
+    };
+    dynamodb.cancel_event(event_id, notice.as_ref()).await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::EventCancelled,
+            None,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Permanently deletes the event with its signups and images. Participants are emailed unless
+/// they were already told the event was cancelled.
+pub async fn delete_event(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::Manage).await?;
+
+    // Queued before anything is deleted, as the signups go with the event, and the event can't
+    // be found to retry the request once it is gone
+    if event.cancelled_at.is_none() {
+        let signups = dynamodb.get_signups(event_id).await?;
+        if let Some(notice) = PendingNotice::new(&event, &signups, Notice::Deleted) {
+            dynamodb.queue_notice(&notice).await?;
+        }
+    }
+
+    // Images go first, since the event item is needed to retry the deletion
+    delete_event_images(&s3, event_id)
+        .await
+        .map_err(DeleteEventError::from)?;
+    dynamodb.delete_event(event_id).await?;
+    dynamodb
+        .record_audit(event_id, &claims.username, AuditAction::EventDeleted, None)
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
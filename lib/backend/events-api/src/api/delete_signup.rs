@@ -0,0 +1,31 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::organizers::models::EventAccess;
+use crate::{audit::models::AuditAction, authentication::Claims, events::queries::DynamodbQueries};
+
+use super::{authorization::authorize, error::RestError};
+
+pub async fn delete_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, signup_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    tracing::debug!("Removing {} from event {}", signup_id, event_id);
+    dynamodb.cancel_signup(event_id, signup_id).await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::SignupRemoved,
+            Some(signup_id.to_string()),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
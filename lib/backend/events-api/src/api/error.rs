@@ -3,9 +3,20 @@ use std::collections::HashMap;
 use axum::{http::StatusCode, response::IntoResponse, Json};
 
 use crate::{
-    authentication::AuthError,
+    audit::errors::AuditError,
+    authentication::{permissions::PermissionError, AuthError},
+    calendar::errors::CalendarError,
     database::errors::{DatabaseQueryFailed, UnknownSdkError},
-    events::errors::{AddImageError, GetEventError},
+    events::errors::{
+        AddImageError, CreateEventError, DeleteEventError, EventValidationError, GetEventError,
+        ListEventsError, UpdateEventError,
+    },
+    gallery::errors::{GalleryError, GalleryUploadError},
+    organizers::errors::{InsufficientRoleError, OrganizerError},
+    signups::errors::{
+        CreateSignupError, ExportError, GetSignupError, SignupValidationError, UpdateSignupError,
+    },
+    uploads::errors::UploadError,
 };
 
 pub struct RestError {
@@ -38,13 +49,13 @@ impl IntoResponse for RestError {
     }
 }
 
-impl Into<(StatusCode, RestErrorBody)> for RestError {
-    fn into(self) -> (StatusCode, RestErrorBody) {
+impl From<RestError> for (StatusCode, RestErrorBody) {
+    fn from(val: RestError) -> Self {
         (
             StatusCode::INTERNAL_SERVER_ERROR,
             RestErrorBody {
-                error_code: self.error_code,
-                error_params: self.error_params,
+                error_code: val.error_code,
+                error_params: val.error_params,
             },
         )
     }
@@ -60,6 +71,156 @@ impl From<NotEventOwnerError> for RestError {
     }
 }
 
+impl From<CalendarError> for RestError {
+    fn from(val: CalendarError) -> Self {
+        match val {
+            CalendarError::InvalidToken => RestError {
+                status_code: StatusCode::NOT_FOUND,
+                error_code: error_codes::INVALID_CALENDAR_TOKEN.to_string(),
+                error_params: None,
+            },
+        }
+    }
+}
+
+impl From<PermissionError> for RestError {
+    fn from(val: PermissionError) -> Self {
+        match val {
+            PermissionError::Unauthenticated(e) => e.into(),
+            PermissionError::Missing(permission) => RestError {
+                status_code: StatusCode::FORBIDDEN,
+                error_code: error_codes::MISSING_PERMISSION.to_string(),
+                error_params: Some(HashMap::from([(
+                    "permission".to_string(),
+                    permission.as_str().to_string(),
+                )])),
+            },
+        }
+    }
+}
+
+impl IntoResponse for PermissionError {
+    fn into_response(self) -> axum::response::Response {
+        RestError::from(self).into_response()
+    }
+}
+
+impl From<InsufficientRoleError> for RestError {
+    fn from(_val: InsufficientRoleError) -> Self {
+        RestError {
+            status_code: StatusCode::FORBIDDEN,
+            error_code: error_codes::INSUFFICIENT_ORGANIZER_ROLE.to_string(),
+            error_params: None,
+        }
+    }
+}
+
+impl From<OrganizerError> for RestError {
+    fn from(val: OrganizerError) -> Self {
+        let (status_code, error_code) = match val {
+            OrganizerError::NotFound => (StatusCode::NOT_FOUND, error_codes::ORGANIZER_NOT_FOUND),
+            OrganizerError::InvalidRole => {
+                (StatusCode::BAD_REQUEST, error_codes::INVALID_ORGANIZER_ROLE)
+            }
+            OrganizerError::AlreadyOwner => {
+                (StatusCode::BAD_REQUEST, error_codes::ALREADY_EVENT_OWNER)
+            }
+            OrganizerError::OwnershipConflict => {
+                (StatusCode::CONFLICT, error_codes::EVENT_OWNER_CONFLICT)
+            }
+            OrganizerError::InvalidStoredOrganizer(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::INVALID_STORED_ORGANIZER,
+            ),
+            OrganizerError::DatabaseQueryFailed(e) => return e.into(),
+        };
+        RestError {
+            status_code,
+            error_code: error_code.to_string(),
+            error_params: None,
+        }
+    }
+}
+
+impl From<AuditError> for RestError {
+    fn from(val: AuditError) -> Self {
+        match val {
+            AuditError::InvalidStoredEntry(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::INVALID_STORED_AUDIT_ENTRY.to_string(),
+                error_params: None,
+            },
+            AuditError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
+impl From<GalleryError> for RestError {
+    fn from(val: GalleryError) -> Self {
+        let (status_code, error_code) = match val {
+            GalleryError::NotFound => (StatusCode::NOT_FOUND, error_codes::GALLERY_IMAGE_NOT_FOUND),
+            GalleryError::InvalidOrder => {
+                (StatusCode::BAD_REQUEST, error_codes::INVALID_GALLERY_ORDER)
+            }
+            GalleryError::InvalidStoredImage(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::INVALID_STORED_GALLERY_IMAGE,
+            ),
+            GalleryError::DatabaseQueryFailed(e) => return e.into(),
+        };
+        RestError {
+            status_code,
+            error_code: error_code.to_string(),
+            error_params: None,
+        }
+    }
+}
+
+impl From<UploadError> for RestError {
+    fn from(val: UploadError) -> Self {
+        let (status_code, error_code) = match val {
+            UploadError::NotFound => (StatusCode::NOT_FOUND, error_codes::IMAGE_UPLOAD_NOT_FOUND),
+            UploadError::PresigningFailed => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::IMAGE_STORAGE_ERROR,
+            ),
+            UploadError::InvalidStoredUpload(_) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                error_codes::INVALID_STORED_IMAGE_UPLOAD,
+            ),
+            UploadError::DatabaseQueryFailed(e) => return e.into(),
+        };
+        RestError {
+            status_code,
+            error_code: error_code.to_string(),
+            error_params: None,
+        }
+    }
+}
+
+impl From<GalleryUploadError> for RestError {
+    fn from(val: GalleryUploadError) -> Self {
+        let (error_code, error_params) = match val {
+            GalleryUploadError::InvalidMultipart => (error_codes::INVALID_MULTIPART, None),
+            GalleryUploadError::NoImages => (error_codes::NO_IMAGES, None),
+            GalleryUploadError::TooManyImages(limit) => (
+                error_codes::TOO_MANY_IMAGES,
+                Some(HashMap::from([("limit".to_string(), limit.to_string())])),
+            ),
+            GalleryUploadError::InvalidAltText => (error_codes::INVALID_ALT_TEXT, None),
+            GalleryUploadError::AltTextMismatch(images) => (
+                error_codes::ALT_TEXT_MISMATCH,
+                Some(HashMap::from([("images".to_string(), images.to_string())])),
+            ),
+        };
+        RestError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_code: error_code.to_string(),
+            error_params,
+        }
+    }
+}
+
 impl From<DatabaseQueryFailed> for RestError {
     fn from(_val: DatabaseQueryFailed) -> Self {
         RestError {
@@ -99,33 +260,324 @@ impl From<GetEventError> for RestError {
     }
 }
 
+impl From<ListEventsError> for RestError {
+    fn from(val: ListEventsError) -> Self {
+        match val {
+            ListEventsError::InvalidCursor => RestError {
+                status_code: StatusCode::BAD_REQUEST,
+                error_code: error_codes::INVALID_CURSOR.to_string(),
+                error_params: None,
+            },
+            ListEventsError::InvalidStoredEvent => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::INVALID_STORED_EVENT.to_string(),
+                error_params: None,
+            },
+            ListEventsError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
+impl From<DeleteEventError> for RestError {
+    fn from(val: DeleteEventError) -> Self {
+        match val {
+            DeleteEventError::ImageDeletionFailed(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::IMAGE_STORAGE_ERROR.to_string(),
+                error_params: None,
+            },
+            DeleteEventError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
 impl From<AddImageError> for RestError {
     fn from(val: AddImageError) -> Self {
         match val {
             AddImageError::DatabaseQueryFailed(e) => e.into(),
             AddImageError::UnexpectedSdkError(e) => e.into(),
             AddImageError::GetEventError(e) => e.into(),
+            AddImageError::InvalidEventItem(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::UNEXPECTED_SERVER_ERROR.to_string(),
+                error_params: None,
+            },
+        }
+    }
+}
+
+impl From<CreateEventError> for RestError {
+    fn from(val: CreateEventError) -> Self {
+        match val {
+            CreateEventError::InvalidEventItem(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::UNEXPECTED_SERVER_ERROR.to_string(),
+                error_params: None,
+            },
+            CreateEventError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
+impl From<UpdateEventError> for RestError {
+    fn from(val: UpdateEventError) -> Self {
+        match val {
+            UpdateEventError::VersionConflict => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::EVENT_VERSION_CONFLICT.to_string(),
+                error_params: None,
+            },
+            UpdateEventError::InvalidEventItem(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::UNEXPECTED_SERVER_ERROR.to_string(),
+                error_params: None,
+            },
+            UpdateEventError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
+impl From<EventValidationError> for RestError {
+    fn from(val: EventValidationError) -> Self {
+        let (error_code, error_params) = match val {
+            EventValidationError::MissingTitle => (error_codes::MISSING_EVENT_TITLE, None),
+            EventValidationError::MissingTitleLanguage(language) => (
+                error_codes::MISSING_TITLE_LANGUAGE,
+                Some(HashMap::from([("language".to_string(), language)])),
+            ),
+            EventValidationError::MissingDescriptionLanguage(language) => (
+                error_codes::MISSING_DESCRIPTION_LANGUAGE,
+                Some(HashMap::from([("language".to_string(), language)])),
+            ),
+            EventValidationError::SignupDeadlineAfterEventDate => {
+                (error_codes::SIGNUP_DEADLINE_AFTER_EVENT_DATE, None)
+            }
+            EventValidationError::MissingLocation => (error_codes::MISSING_EVENT_LOCATION, None),
+            EventValidationError::InvalidEmail => (error_codes::INVALID_CONTACT_EMAIL, None),
+            EventValidationError::InvalidParticipantsLimit => {
+                (error_codes::INVALID_PARTICIPANTS_LIMIT, None)
+            }
+            EventValidationError::LimitBelowParticipants(participants) => (
+                error_codes::PARTICIPANTS_LIMIT_BELOW_SIGNUPS,
+                Some(HashMap::from([(
+                    "participants".to_string(),
+                    participants.to_string(),
+                )])),
+            ),
+            EventValidationError::InvalidQuestion(id) => (
+                error_codes::INVALID_SIGNUP_QUESTION,
+                Some(HashMap::from([("question".to_string(), id)])),
+            ),
+            EventValidationError::EventCancelled => (error_codes::EVENT_CANCELLED, None),
+            EventValidationError::InvalidTimeZone(time_zone) => (
+                error_codes::INVALID_TIME_ZONE,
+                Some(HashMap::from([("timeZone".to_string(), time_zone)])),
+            ),
+            EventValidationError::EndBeforeStart => (error_codes::END_DATE_BEFORE_EVENT_DATE, None),
+        };
+        RestError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_code: error_code.to_string(),
+            error_params,
+        }
+    }
+}
+
+impl From<CreateSignupError> for RestError {
+    fn from(val: CreateSignupError) -> Self {
+        match val {
+            CreateSignupError::EventFull => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::EVENT_FULL.to_string(),
+                error_params: None,
+            },
+            CreateSignupError::EventNotFull => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::EVENT_NOT_FULL.to_string(),
+                error_params: None,
+            },
+            CreateSignupError::InvalidSignupItem(_) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::UNEXPECTED_SERVER_ERROR.to_string(),
+                error_params: None,
+            },
+            CreateSignupError::DatabaseQueryFailed(e) => e.into(),
+        }
+    }
+}
+
+impl From<GetSignupError> for RestError {
+    fn from(val: GetSignupError) -> Self {
+        match val {
+            GetSignupError::NotFound => RestError {
+                status_code: StatusCode::NOT_FOUND,
+                error_code: error_codes::SIGNUP_NOT_FOUND.to_string(),
+                error_params: None,
+            },
+            GetSignupError::InvalidStoredSignup(id) => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::INVALID_STORED_SIGNUP.to_string(),
+                error_params: Some(HashMap::from([("id".to_string(), id.to_string())])),
+            },
+            GetSignupError::DatabaseQueryFailed(e) => e.into(),
+            GetSignupError::UnexpectedSdkError(e) => e.into(),
+        }
+    }
+}
+
+impl From<UpdateSignupError> for RestError {
+    fn from(val: UpdateSignupError) -> Self {
+        match val {
+            UpdateSignupError::Conflict => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::SIGNUP_CONFLICT.to_string(),
+                error_params: None,
+            },
+            UpdateSignupError::EventFull => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::EVENT_FULL.to_string(),
+                error_params: None,
+            },
+            UpdateSignupError::PartiallyReordered(reordered) => RestError {
+                status_code: StatusCode::CONFLICT,
+                error_code: error_codes::WAITLIST_PARTIALLY_REORDERED.to_string(),
+                error_params: Some(HashMap::from([(
+                    "reordered".to_string(),
+                    reordered.to_string(),
+                )])),
+            },
+            UpdateSignupError::DatabaseQueryFailed(e) => e.into(),
+            UpdateSignupError::GetEventError(e) => e.into(),
+            UpdateSignupError::GetSignupError(e) => e.into(),
+        }
+    }
+}
+
+impl From<SignupValidationError> for RestError {
+    fn from(val: SignupValidationError) -> Self {
+        let (error_code, error_params) = match val {
+            SignupValidationError::SignupClosed => (error_codes::SIGNUP_CLOSED, None),
+            SignupValidationError::EventCancelled => (error_codes::EVENT_CANCELLED, None),
+            SignupValidationError::MissingName => (error_codes::MISSING_PARTICIPANT_NAME, None),
+            SignupValidationError::InvalidEmail => (error_codes::INVALID_PARTICIPANT_EMAIL, None),
+            SignupValidationError::NotWaitlisted => (error_codes::NOT_WAITLISTED, None),
+            SignupValidationError::InvalidWaitlistOrder => {
+                (error_codes::INVALID_WAITLIST_ORDER, None)
+            }
+            SignupValidationError::InvalidToken => (error_codes::INVALID_SIGNUP_TOKEN, None),
+            // One entry per invalid answer, from question id to what is wrong with the answer
+            SignupValidationError::InvalidAnswers(errors) => (
+                error_codes::INVALID_ANSWERS,
+                Some(
+                    errors
+                        .into_iter()
+                        .map(|(question, error)| (question, error.as_str().to_string()))
+                        .collect(),
+                ),
+            ),
+        };
+        RestError {
+            status_code: StatusCode::BAD_REQUEST,
+            error_code: error_code.to_string(),
+            error_params,
         }
     }
 }
 
 impl From<AuthError> for RestError {
-    fn from(_val: AuthError) -> Self {
+    fn from(val: AuthError) -> Self {
+        let (status_code, error_code) = match val {
+            AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "UNAUTHORIZED"),
+            AuthError::KeysUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                error_codes::AUTHENTICATION_UNAVAILABLE,
+            ),
+        };
         RestError {
-            status_code: StatusCode::UNAUTHORIZED,
-            error_code: "UNAUTHORIZED".to_string(),
+            status_code,
+            error_code: error_code.to_string(),
             error_params: None,
         }
     }
 }
 
+impl From<ExportError> for RestError {
+    fn from(val: ExportError) -> Self {
+        match val {
+            ExportError::UnsupportedLanguage(language) => RestError {
+                status_code: StatusCode::BAD_REQUEST,
+                error_code: error_codes::UNSUPPORTED_EXPORT_LANGUAGE.to_string(),
+                error_params: Some(HashMap::from([("language".to_string(), language)])),
+            },
+            ExportError::WriteFailed => RestError {
+                status_code: StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::UNEXPECTED_SERVER_ERROR.to_string(),
+                error_params: None,
+            },
+        }
+    }
+}
+
 pub mod error_codes {
     pub const UNEXPECTED_SERVER_ERROR: &str = "UNEXPECTED_SERVER_ERROR";
     pub const EVENT_NOT_FOUND: &str = "EVENT_NOT_FOUND";
     pub const UNSUPPORTED_IMAGE_FORMAT: &str = "UNSUPPORTED_IMAGE_FORMAT";
+    pub const IMAGE_FORMAT_MISMATCH: &str = "IMAGE_FORMAT_MISMATCH";
+    pub const INVALID_IMAGE_FRAMING: &str = "INVALID_IMAGE_FRAMING";
     pub const IMAGE_CONVERSION_ERROR: &str = "IMAGE_CONVERSION_ERROR";
     pub const IMAGE_TOO_LARGE: &str = "IMAGE_TOO_LARGE";
     pub const IMAGE_TOO_SMALL: &str = "IMAGE_TOO_SMALL";
     pub const IMAGE_STORAGE_ERROR: &str = "IMAGE_STORAGE_ERROR";
     pub const INVALID_STORED_EVENT: &str = "INVALID_STORED_EVENT";
+    pub const INVALID_JSON: &str = "INVALID_JSON";
+    pub const MISSING_EVENT_TITLE: &str = "MISSING_EVENT_TITLE";
+    pub const MISSING_TITLE_LANGUAGE: &str = "MISSING_TITLE_LANGUAGE";
+    pub const MISSING_DESCRIPTION_LANGUAGE: &str = "MISSING_DESCRIPTION_LANGUAGE";
+    pub const SIGNUP_DEADLINE_AFTER_EVENT_DATE: &str = "SIGNUP_DEADLINE_AFTER_EVENT_DATE";
+    pub const MISSING_EVENT_LOCATION: &str = "MISSING_EVENT_LOCATION";
+    pub const INVALID_CONTACT_EMAIL: &str = "INVALID_CONTACT_EMAIL";
+    pub const INVALID_PARTICIPANTS_LIMIT: &str = "INVALID_PARTICIPANTS_LIMIT";
+    pub const PARTICIPANTS_LIMIT_BELOW_SIGNUPS: &str = "PARTICIPANTS_LIMIT_BELOW_SIGNUPS";
+    pub const EVENT_VERSION_CONFLICT: &str = "EVENT_VERSION_CONFLICT";
+    pub const EVENT_FULL: &str = "EVENT_FULL";
+    pub const EVENT_NOT_FULL: &str = "EVENT_NOT_FULL";
+    pub const SIGNUP_CLOSED: &str = "SIGNUP_CLOSED";
+    pub const EVENT_CANCELLED: &str = "EVENT_CANCELLED";
+    pub const INVALID_TIME_ZONE: &str = "INVALID_TIME_ZONE";
+    pub const END_DATE_BEFORE_EVENT_DATE: &str = "END_DATE_BEFORE_EVENT_DATE";
+    pub const GALLERY_IMAGE_NOT_FOUND: &str = "GALLERY_IMAGE_NOT_FOUND";
+    pub const INVALID_GALLERY_ORDER: &str = "INVALID_GALLERY_ORDER";
+    pub const INVALID_STORED_GALLERY_IMAGE: &str = "INVALID_STORED_GALLERY_IMAGE";
+    pub const INVALID_MULTIPART: &str = "INVALID_MULTIPART";
+    pub const NO_IMAGES: &str = "NO_IMAGES";
+    pub const TOO_MANY_IMAGES: &str = "TOO_MANY_IMAGES";
+    pub const INVALID_ALT_TEXT: &str = "INVALID_ALT_TEXT";
+    pub const ALT_TEXT_MISMATCH: &str = "ALT_TEXT_MISMATCH";
+    pub const IMAGE_UPLOAD_NOT_FOUND: &str = "IMAGE_UPLOAD_NOT_FOUND";
+    pub const INVALID_STORED_IMAGE_UPLOAD: &str = "INVALID_STORED_IMAGE_UPLOAD";
+    pub const IMAGE_PROCESSING_STOPPED: &str = "IMAGE_PROCESSING_STOPPED";
+    pub const INSUFFICIENT_ORGANIZER_ROLE: &str = "INSUFFICIENT_ORGANIZER_ROLE";
+    pub const ORGANIZER_NOT_FOUND: &str = "ORGANIZER_NOT_FOUND";
+    pub const INVALID_ORGANIZER_ROLE: &str = "INVALID_ORGANIZER_ROLE";
+    pub const ALREADY_EVENT_OWNER: &str = "ALREADY_EVENT_OWNER";
+    pub const EVENT_OWNER_CONFLICT: &str = "EVENT_OWNER_CONFLICT";
+    pub const INVALID_STORED_ORGANIZER: &str = "INVALID_STORED_ORGANIZER";
+    pub const INVALID_STORED_AUDIT_ENTRY: &str = "INVALID_STORED_AUDIT_ENTRY";
+    pub const MISSING_PARTICIPANT_NAME: &str = "MISSING_PARTICIPANT_NAME";
+    pub const INVALID_PARTICIPANT_EMAIL: &str = "INVALID_PARTICIPANT_EMAIL";
+    pub const SIGNUP_NOT_FOUND: &str = "SIGNUP_NOT_FOUND";
+    pub const INVALID_STORED_SIGNUP: &str = "INVALID_STORED_SIGNUP";
+    pub const SIGNUP_CONFLICT: &str = "SIGNUP_CONFLICT";
+    pub const WAITLIST_PARTIALLY_REORDERED: &str = "WAITLIST_PARTIALLY_REORDERED";
+    pub const NOT_WAITLISTED: &str = "NOT_WAITLISTED";
+    pub const INVALID_WAITLIST_ORDER: &str = "INVALID_WAITLIST_ORDER";
+    pub const INVALID_SIGNUP_TOKEN: &str = "INVALID_SIGNUP_TOKEN";
+    pub const INVALID_SIGNUP_QUESTION: &str = "INVALID_SIGNUP_QUESTION";
+    pub const INVALID_ANSWERS: &str = "INVALID_ANSWERS";
+    pub const INVALID_QUERY: &str = "INVALID_QUERY";
+    pub const INVALID_CURSOR: &str = "INVALID_CURSOR";
+    pub const UNSUPPORTED_EXPORT_LANGUAGE: &str = "UNSUPPORTED_EXPORT_LANGUAGE";
+    pub const AUTHENTICATION_UNAVAILABLE: &str = "AUTHENTICATION_UNAVAILABLE";
+    pub const MISSING_PERMISSION: &str = "MISSING_PERMISSION";
+    pub const INVALID_CALENDAR_TOKEN: &str = "INVALID_CALENDAR_TOKEN";
 }
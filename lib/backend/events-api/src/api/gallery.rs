@@ -0,0 +1,493 @@
+//! Galleries of images shown with an event. Images are uploaded several at a time as multipart
+//! data, with parts named `image`, and optionally an `altText` part listing the alt texts of the
+//! images and a `framing` part listing their crops and focal points, both in the same order.
+//! Uploads go to the image upload Lambda, which has the memory to decode them.
+
+use std::collections::{HashMap, HashSet};
+
+use axum::{
+    extract::{
+        multipart::{Field, MultipartRejection},
+        Multipart, Path, State,
+    },
+    http::StatusCode,
+    Json,
+};
+use bytes::{Bytes, BytesMut};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::{errors::GetEventError, queries::DynamodbQueries},
+    gallery::{
+        errors::{GalleryError, GalleryUploadError},
+        models::GalleryImage,
+    },
+    images::{
+        conform_image, decode_image, delete_image, discard_cover_image,
+        errors::ImageUploadError,
+        formats::UploadFormat,
+        framing::{FocalPoint, ImageFraming},
+        image_set, is_image_too_small, upload_image,
+        variants::{ImageSet, ImageVariant},
+        MAX_IMAGE_SIZE,
+    },
+    localization::localized,
+    organizers::models::EventAccess,
+};
+
+use super::{authorization::authorize, error::RestError, json, language::Language};
+
+/// Most images a gallery holds
+const MAX_GALLERY_SIZE: usize = 50;
+/// Most parts an upload may have, which is enough for a full gallery with its alt texts and
+/// framings
+const MAX_UPLOAD_PARTS: usize = MAX_GALLERY_SIZE + 2;
+/// Longest alt text or framing part
+const MAX_TEXT_PART_SIZE: usize = 64 * 1024;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryImageResponse {
+    pub id: Uuid,
+    pub alt_text: HashMap<String, String>,
+    /// Whether the image is the cover image of the event
+    pub cover: bool,
+    pub image_set: ImageSet,
+}
+
+impl GalleryImageResponse {
+    fn new(image: GalleryImage, cover: Option<Uuid>) -> Self {
+        Self {
+            id: image.image_id,
+            cover: cover == Some(image.image_id),
+            image_set: image_set(
+                image.event_id,
+                image.image_id,
+                &image.variants,
+                image.focal_point,
+            ),
+            alt_text: image.alt_text,
+        }
+    }
+}
+
+/// A gallery image as shown to participants, with its alt text in a single language
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicGalleryImage {
+    pub id: Uuid,
+    /// Language of the alt text
+    pub language: Option<String>,
+    pub alt_text: String,
+    pub image_set: ImageSet,
+}
+
+#[derive(Deserialize)]
+pub struct GalleryOrder {
+    /// Every image of the gallery, in the order to show them
+    pub images: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryImagePatch {
+    pub alt_text: HashMap<String, String>,
+}
+
+/// Leaves out languages without any alt text
+fn clean_alt_text(alt_text: HashMap<String, String>) -> HashMap<String, String> {
+    alt_text
+        .into_iter()
+        .map(|(language, text)| (language, text.trim().to_owned()))
+        .filter(|(_, text)| !text.is_empty())
+        .collect()
+}
+
+struct GalleryUpload {
+    /// The images with the formats they are declared as
+    images: Vec<(Bytes, UploadFormat)>,
+    alt_texts: Vec<HashMap<String, String>>,
+    /// How to frame the images, in the same order
+    framings: Vec<ImageFraming>,
+}
+
+impl GalleryUpload {
+    /// Alt texts and framings are matched to images by their order, so when given they must
+    /// be given for every image
+    fn validate(&self) -> Result<(), RestError> {
+        if !self.alt_texts.is_empty() && self.alt_texts.len() != self.images.len() {
+            return Err(GalleryUploadError::AltTextMismatch(self.images.len()).into());
+        }
+        if !self.framings.is_empty() && self.framings.len() != self.images.len() {
+            return Err(ImageUploadError::InvalidFraming.into());
+        }
+        Ok(())
+    }
+}
+
+/// Reads a part, stopping as soon as it is longer than `max_length`. Gives `None` for parts
+/// that are too long.
+async fn read_part(
+    mut field: Field<'_>,
+    max_length: usize,
+) -> Result<Option<Bytes>, GalleryUploadError> {
+    let mut content = BytesMut::new();
+    while let Some(chunk) = field.chunk().await.map_err(|e| {
+        tracing::debug!("Failed to read multipart field: {e:?}");
+        GalleryUploadError::InvalidMultipart
+    })? {
+        if content.len() + chunk.len() > max_length {
+            return Ok(None);
+        }
+        content.extend_from_slice(&chunk);
+    }
+    Ok(Some(content.freeze()))
+}
+
+async fn read_upload(mut multipart: Multipart) -> Result<GalleryUpload, RestError> {
+    let mut upload = GalleryUpload {
+        images: vec![],
+        alt_texts: vec![],
+        framings: vec![],
+    };
+    let mut parts = 0;
+    while let Some(field) = multipart.next_field().await.map_err(|e| {
+        tracing::debug!("Failed to read multipart field: {e:?}");
+        GalleryUploadError::InvalidMultipart
+    })? {
+        parts += 1;
+        if parts > MAX_UPLOAD_PARTS {
+            tracing::debug!("Rejected upload with more than {MAX_UPLOAD_PARTS} parts");
+            return Err(GalleryUploadError::InvalidMultipart.into());
+        }
+        match field.name() {
+            Some("image") => {
+                if upload.images.len() == MAX_GALLERY_SIZE {
+                    return Err(GalleryUploadError::TooManyImages(MAX_GALLERY_SIZE).into());
+                }
+                let format = UploadFormat::from_content_type(
+                    field
+                        .content_type()
+                        .ok_or(ImageUploadError::UnsupportedImageFormat)?,
+                )?;
+                let image = read_part(field, MAX_IMAGE_SIZE)
+                    .await?
+                    .ok_or(ImageUploadError::ImageTooLarge)?;
+                upload.images.push((image, format));
+            }
+            Some("altText") => {
+                let alt_texts = read_part(field, MAX_TEXT_PART_SIZE)
+                    .await?
+                    .ok_or(GalleryUploadError::InvalidAltText)?;
+                upload.alt_texts = serde_json::from_slice(&alt_texts).map_err(|e| {
+                    tracing::debug!("Failed to parse alt texts: {e:?}");
+                    GalleryUploadError::InvalidAltText
+                })?;
+            }
+            Some("framing") => {
+                let framings = read_part(field, MAX_TEXT_PART_SIZE)
+                    .await?
+                    .ok_or(ImageUploadError::InvalidFraming)?;
+                upload.framings = serde_json::from_slice(&framings).map_err(|e| {
+                    tracing::debug!("Failed to parse framings: {e:?}");
+                    ImageUploadError::InvalidFraming
+                })?;
+            }
+            name => tracing::debug!("Ignoring multipart field {name:?}"),
+        }
+    }
+    Ok(upload)
+}
+
+/// Lists the gallery with the alt texts in every language
+pub async fn get_gallery(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<Json<Vec<GalleryImageResponse>>, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    let gallery = dynamodb.get_gallery(event_id).await?;
+    Ok(Json(
+        gallery
+            .into_iter()
+            .map(|image| GalleryImageResponse::new(image, event.image))
+            .collect(),
+    ))
+}
+
+pub async fn get_public_gallery(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    Language(preference): Language,
+) -> Result<Json<Vec<PublicGalleryImage>>, RestError> {
+    let event = dynamodb.get_event(event_id).await?;
+    if !event.event_visible {
+        return Err(GetEventError::NotFound.into());
+    }
+
+    let gallery = dynamodb.get_gallery(event_id).await?;
+    Ok(Json(
+        gallery
+            .into_iter()
+            .map(|image| {
+                let language = preference.resolve(image.alt_text.keys()).map(str::to_owned);
+                PublicGalleryImage {
+                    id: image.image_id,
+                    alt_text: language
+                        .as_deref()
+                        .map(|language| localized(&image.alt_text, language))
+                        .unwrap_or_default(),
+                    language,
+                    image_set: image_set(
+                        image.event_id,
+                        image.image_id,
+                        &image.variants,
+                        image.focal_point,
+                    ),
+                }
+            })
+            .collect(),
+    ))
+}
+
+/// The stored files of an image not yet added to the gallery
+struct UploadedImage {
+    image_id: Uuid,
+    variants: Vec<ImageVariant>,
+    focal_point: Option<FocalPoint>,
+}
+
+/// Decodes, renders and stores one image of a gallery upload
+async fn upload_gallery_image(
+    s3: &aws_sdk_s3::Client,
+    event_id: Uuid,
+    image: Bytes,
+    format: UploadFormat,
+    framing: ImageFraming,
+) -> Result<UploadedImage, RestError> {
+    let image = decode_image(image, format, framing).await?;
+    if is_image_too_small(&image) {
+        return Err(ImageUploadError::ImageTooSmall.into());
+    }
+    let rendered = conform_image(image, framing.focal_point).await?;
+    let (image_id, variants) = upload_image(s3, event_id, rendered.variants).await?;
+    Ok(UploadedImage {
+        image_id,
+        variants,
+        focal_point: rendered.focal_point,
+    })
+}
+
+/// Removes the files of images a failed upload stored. Failures are only reported, as the
+/// image cleanup command removes whatever is left behind.
+async fn discard_uploaded_images(
+    s3: &aws_sdk_s3::Client,
+    event_id: Uuid,
+    images: &[UploadedImage],
+) {
+    for image in images {
+        if let Err(e) = delete_image(s3, event_id, image.image_id, &image.variants).await {
+            error!(
+                "Failed to delete files of uploaded image {}: {e:?}",
+                image.image_id
+            );
+            sentry::capture_error(&e);
+        }
+    }
+}
+
+/// Adds the uploaded images to the end of the gallery
+pub async fn post_gallery(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    multipart: Result<Multipart, MultipartRejection>,
+) -> Result<(StatusCode, Json<Vec<GalleryImageResponse>>), RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let multipart = multipart.map_err(|e| {
+        tracing::debug!("Rejected multipart upload: {}", e.body_text());
+        GalleryUploadError::InvalidMultipart
+    })?;
+    let upload = read_upload(multipart).await?;
+    if upload.images.is_empty() {
+        return Err(GalleryUploadError::NoImages.into());
+    }
+    upload.validate()?;
+
+    let gallery = dynamodb.get_gallery(event_id).await?;
+    if gallery.len() + upload.images.len() > MAX_GALLERY_SIZE {
+        return Err(GalleryUploadError::TooManyImages(MAX_GALLERY_SIZE).into());
+    }
+    let first_position = gallery.last().map_or(0, |image| image.position + 1);
+
+    for framing in &upload.framings {
+        framing.validate()?;
+    }
+    // Images are decoded one at a time and dropped once stored, as a full gallery of decoded
+    // photos would not fit in memory. A broken image fails the whole upload, so the images
+    // stored before it are removed.
+    let mut framings = upload.framings.into_iter();
+    let mut uploaded = Vec::with_capacity(upload.images.len());
+    for (image, format) in upload.images {
+        let framing = framings.next().unwrap_or_default();
+        match upload_gallery_image(&s3, event_id, image, format, framing).await {
+            Ok(image) => uploaded.push(image),
+            Err(e) => {
+                discard_uploaded_images(&s3, event_id, &uploaded).await;
+                return Err(e);
+            }
+        }
+    }
+
+    let mut alt_texts = upload.alt_texts.into_iter();
+    let mut added = Vec::with_capacity(uploaded.len());
+    for (index, (position, uploaded_image)) in (first_position..).zip(&uploaded).enumerate() {
+        let image = GalleryImage {
+            event_id,
+            image_id: uploaded_image.image_id,
+            position,
+            alt_text: clean_alt_text(alt_texts.next().unwrap_or_default()),
+            variants: uploaded_image.variants.clone(),
+            focal_point: uploaded_image.focal_point,
+        };
+        if let Err(e) = dynamodb.put_gallery_image(&image).await {
+            discard_uploaded_images(&s3, event_id, &uploaded[index..]).await;
+            return Err(e.into());
+        }
+        added.push(GalleryImageResponse::new(image, event.image));
+    }
+
+    Ok((StatusCode::CREATED, Json(added)))
+}
+
+pub async fn put_gallery_order(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    json::Json(order): json::Json<GalleryOrder>,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let gallery: HashSet<Uuid> = dynamodb
+        .get_gallery(event_id)
+        .await?
+        .into_iter()
+        .map(|image| image.image_id)
+        .collect();
+    let ordered: HashSet<Uuid> = order.images.iter().copied().collect();
+    if ordered.len() != order.images.len() || ordered != gallery {
+        return Err(GalleryError::InvalidOrder.into());
+    }
+
+    dynamodb.reorder_gallery(event_id, &order.images).await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn patch_gallery_image(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, image_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+    json::Json(patch): json::Json<GalleryImagePatch>,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    dynamodb
+        .set_gallery_alt_text(event_id, image_id, &clean_alt_text(patch.alt_text))
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Makes a gallery image the cover image of the event
+pub async fn put_gallery_cover(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, image_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let image = dynamodb.get_gallery_image(event_id, image_id).await?;
+    let replaced = dynamodb
+        .set_event_image(event_id, image.image_id, &image.variants, image.focal_point)
+        .await?;
+    if let Some((old_image, old_variants)) = replaced {
+        discard_cover_image(&s3, &dynamodb, event_id, old_image, &old_variants).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Removes an image from the gallery, and from the cover of the event if it is there
+pub async fn delete_gallery_image(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, image_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let image = dynamodb.remove_gallery_image(event_id, image_id).await?;
+    if dynamodb.remove_event_image(event_id, image_id).await? {
+        tracing::debug!("Removed cover image {image_id} of {event_id}");
+    }
+
+    // The image is gone from the event either way, so leftover files are only reported
+    if let Err(e) = delete_image(&s3, event_id, image_id, &image.variants).await {
+        error!("Failed to delete files of gallery image {image_id}: {e:?}");
+        sentry::capture_error(&e);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use bytes::Bytes;
+    use rstest::rstest;
+
+    use crate::images::{formats::UploadFormat, framing::ImageFraming};
+
+    use super::{clean_alt_text, GalleryUpload};
+
+    #[test]
+    fn test_clean_alt_text() {
+        let alt_text = HashMap::from([
+            ("sv".to_owned(), " Sjön i solnedgång ".to_owned()),
+            ("en".to_owned(), "  ".to_owned()),
+        ]);
+        assert_eq!(
+            clean_alt_text(alt_text),
+            HashMap::from([("sv".to_owned(), "Sjön i solnedgång".to_owned())])
+        );
+    }
+
+    #[rstest]
+    #[case(0, 0, true)]
+    #[case(2, 0, true)]
+    #[case(0, 2, true)]
+    #[case(1, 0, false)]
+    #[case(3, 0, false)]
+    #[case(0, 1, false)]
+    fn test_validate_upload(
+        #[case] alt_texts: usize,
+        #[case] framings: usize,
+        #[case] valid: bool,
+    ) {
+        let upload = GalleryUpload {
+            images: vec![(Bytes::new(), UploadFormat::Jpeg); 2],
+            alt_texts: vec![HashMap::new(); alt_texts],
+            framings: vec![ImageFraming::default(); framings],
+        };
+        assert_eq!(upload.validate().is_ok(), valid);
+    }
+}
@@ -1,26 +1,42 @@
-use crate::events::queries::DynamodbQueries;
-use axum::extract::{Path, State};
+use crate::{
+    authentication::Claims,
+    events::{
+        errors::GetEventError,
+        preview::PreviewToken,
+        queries::DynamodbQueries,
+        questions::{LocalizedQuestion, Question},
+    },
+    images::{image_set, variants::ImageSet},
+    localization::{localized, LanguagePreference},
+    organizers::models::EventAccess,
+};
+use axum::{
+    extract::{Path, State},
+    http::{header, HeaderMap, HeaderValue},
+};
+use axum_extra::{headers::ETag, TypedHeader};
+use time_tz::OffsetDateTimeExt;
 use uuid::Uuid;
 
 use std::collections::HashMap;
 
 use axum::{response::IntoResponse, Json};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-use super::error::RestError;
+use super::{authorization::authorize, error::RestError, language::Language, query::Query};
 
 #[derive(serde::Deserialize, Serialize)]
 pub struct Contact {
-    organizer: Option<String>,
-    email: Option<String>,
-    email_visible: bool,
-    phone: Option<String>,
+    pub organizer: Option<String>,
+    pub email: Option<String>,
+    pub email_visible: bool,
+    pub phone: Option<String>,
 }
 
 #[derive(serde::Deserialize, Serialize)]
 pub struct Location {
-    name: String,
-    link: String,
+    pub name: String,
+    pub link: String,
 }
 
 #[derive(serde::Serialize)]
@@ -32,12 +48,22 @@ pub struct Event {
     pub signup_end_date: time::OffsetDateTime,
     #[serde(with = "time::serde::rfc3339")]
     pub event_date: time::OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub end_date: Option<time::OffsetDateTime>,
+    pub all_day: bool,
+    pub time_zone: String,
     pub location: Location,
     pub contact: Contact,
     pub description: HashMap<String, String>,
     pub limit: Option<u16>,
+    pub spots_remaining: Option<u16>,
     pub image: Option<Uuid>,
+    /// Sources of the image, ready for a `<picture>` element
+    pub image_set: Option<ImageSet>,
     pub visible: bool,
+    pub questions: Vec<Question>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub cancelled_at: Option<time::OffsetDateTime>,
 }
 
 impl IntoResponse for Event {
@@ -46,8 +72,142 @@ impl IntoResponse for Event {
     }
 }
 
+/// An event as shown to participants, in a single language. Organizers edit the [`Event`] with
+/// every language.
+#[derive(serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicEvent {
+    pub id: Uuid,
+    /// Language of the title, description and questions
+    pub language: String,
+    /// Every language the event is available in
+    pub languages: Vec<String>,
+    pub title: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub signup_end_date: time::OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub event_date: time::OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub end_date: Option<time::OffsetDateTime>,
+    pub all_day: bool,
+    pub time_zone: String,
+    pub location: Location,
+    pub contact: Contact,
+    pub description: String,
+    pub limit: Option<u16>,
+    pub spots_remaining: Option<u16>,
+    pub image: Option<Uuid>,
+    pub image_set: Option<ImageSet>,
+    pub questions: Vec<LocalizedQuestion>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub cancelled_at: Option<time::OffsetDateTime>,
+}
+
+impl PublicEvent {
+    pub fn new(event: crate::events::models::Event, preference: &LanguagePreference) -> Self {
+        let language = preference
+            .resolve(event.title.keys())
+            .unwrap_or_default()
+            .to_owned();
+        let mut languages: Vec<String> = event.title.keys().cloned().collect();
+        languages.sort();
+        let title = localized(&event.title, &language);
+        let description = localized(&event.description, &language);
+        let event = Event::from(event);
+        Self {
+            id: event.id,
+            title,
+            signup_end_date: event.signup_end_date,
+            event_date: event.event_date,
+            end_date: event.end_date,
+            all_day: event.all_day,
+            time_zone: event.time_zone,
+            location: event.location,
+            contact: event.contact,
+            description,
+            limit: event.limit,
+            spots_remaining: event.spots_remaining,
+            image: event.image,
+            image_set: event.image_set,
+            questions: event
+                .questions
+                .into_iter()
+                .map(|question| question.localized(&language))
+                .collect(),
+            cancelled_at: event.cancelled_at,
+            language,
+            languages,
+        }
+    }
+}
+
+impl IntoResponse for PublicEvent {
+    fn into_response(self) -> axum::response::Response {
+        Json(self).into_response()
+    }
+}
+
+/// Entity tag identifying a version of an event, used for `If-Match` on updates
+pub fn event_etag(version: u64) -> TypedHeader<ETag> {
+    TypedHeader(
+        format!("\"{version}\"")
+            .parse()
+            .expect("Quoted version number is always a valid entity tag"),
+    )
+}
+
+/// Entity tag of an event in one language, as shown to participants. Signing up changes the
+/// spots remaining without changing the version, so the counts of participants and waitlisted
+/// are part of the tag. Languages are stored as given, so only the characters of language tags
+/// are kept.
+fn public_event_etag(
+    version: u64,
+    participants_count: u16,
+    waitlist_count: u16,
+    language: &str,
+) -> TypedHeader<ETag> {
+    let language: String = language
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+    TypedHeader(
+        format!("\"{version}-{participants_count}-{waitlist_count}-{language}\"")
+            .parse()
+            .expect("Quoted numbers and language tag are always a valid entity tag"),
+    )
+}
+
+/// Headers telling caches the response depends on the languages asked for
+fn language_headers(language: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(header::VARY, HeaderValue::from_static("accept-language"));
+    if let Ok(language) = HeaderValue::from_str(language) {
+        if !language.is_empty() {
+            headers.insert(header::CONTENT_LANGUAGE, language);
+        }
+    }
+    headers
+}
+
 impl From<crate::events::models::Event> for Event {
     fn from(value: crate::events::models::Event) -> Self {
+        let spots_remaining = value.spots_remaining();
+        let image_set = value.image.map(|image| {
+            image_set(
+                value.id,
+                image,
+                &value.image_variants,
+                value.image_focal_point,
+            )
+        });
+        // Dates are given at the offset of the event's time zone, so they read as local times
+        let local = |date: time::OffsetDateTime| match value.tz() {
+            Some(time_zone) => date.to_timezone(time_zone),
+            None => date,
+        };
+        let signup_end_date = local(value.signup_end_date);
+        let event_date = local(value.event_date);
+        let end_date = value.end_date.map(local);
         let location = Location {
             name: value.location_name,
             link: value.location_link,
@@ -55,8 +215,11 @@ impl From<crate::events::models::Event> for Event {
         Self {
             id: value.id,
             title: value.title,
-            signup_end_date: value.signup_end_date,
-            event_date: value.event_date,
+            signup_end_date,
+            event_date,
+            end_date,
+            all_day: value.all_day,
+            time_zone: value.time_zone,
             location,
             contact: Contact {
                 organizer: value.organizer_name,
@@ -70,24 +233,88 @@ impl From<crate::events::models::Event> for Event {
             },
             description: value.description,
             limit: value.limit,
+            spots_remaining,
             image: value.image,
+            image_set,
             visible: value.event_visible,
+            questions: value.questions,
+            cancelled_at: value.cancelled_at,
         }
     }
 }
+#[derive(Deserialize)]
+pub struct PreviewOptions {
+    /// Preview token, letting the holder see the event while it is hidden
+    pub preview: Option<String>,
+}
+
 pub async fn get_event(
     Path(event_id): Path<Uuid>,
     State(dynamodb): State<DynamodbQueries>,
-) -> Result<Event, RestError> {
+    Query(options): Query<PreviewOptions>,
+    Language(preference): Language,
+) -> Result<(TypedHeader<ETag>, HeaderMap, PublicEvent), RestError> {
     tracing::debug!("Getting event with id: {}", event_id);
-    let event = dynamodb
-        .get_event(event_id)
-        .await
-        .map_err(|e| RestError::from(e))
-        .map(|event| {
-            tracing::debug!("Found event: {}", event.id);
-            return event.into();
-        });
+    let event = dynamodb.get_event(event_id).await?;
+
+    let previewing = options
+        .preview
+        .and_then(|token| PreviewToken::decode(&token).ok())
+        .is_some_and(|token| token.grants_access(event_id, time::OffsetDateTime::now_utc()));
+    if !event.event_visible && !previewing {
+        tracing::debug!("Event {} is hidden", event.id);
+        return Err(GetEventError::NotFound.into());
+    }
 
-    return event;
+    tracing::debug!("Found event: {}", event.id);
+    let (version, participants_count, waitlist_count) = (
+        event.version,
+        event.participants_count,
+        event.waitlist_count,
+    );
+    let event = PublicEvent::new(event, &preference);
+    Ok((
+        public_event_etag(version, participants_count, waitlist_count, &event.language),
+        language_headers(&event.language),
+        event,
+    ))
+}
+
+/// Gets an event for its owner, whether it is visible or not
+pub async fn get_own_event(
+    Path(event_id): Path<Uuid>,
+    State(dynamodb): State<DynamodbQueries>,
+    claims: Claims,
+) -> Result<(TypedHeader<ETag>, Event), RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    Ok((event_etag(event.version), event.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::header;
+    use rstest::rstest;
+
+    use super::{language_headers, public_event_etag};
+
+    #[rstest]
+    #[case("sv", "\"3-10-2-sv\"")]
+    #[case("en-GB", "\"3-10-2-en-GB\"")]
+    #[case("", "\"3-10-2-\"")]
+    #[case("sv\" x", "\"3-10-2-svx\"")]
+    fn test_public_event_etag(#[case] language: &str, #[case] expected: &str) {
+        assert_eq!(
+            public_event_etag(3, 10, 2, language).0,
+            expected.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_language_headers() {
+        let headers = language_headers("sv");
+        assert_eq!(headers[header::VARY], "accept-language");
+        assert_eq!(headers[header::CONTENT_LANGUAGE], "sv");
+        assert!(!language_headers("").contains_key(header::CONTENT_LANGUAGE));
+    }
 }
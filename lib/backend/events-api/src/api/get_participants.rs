@@ -0,0 +1,106 @@
+use axum::{
+    body::Body,
+    extract::{Path, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::queries::DynamodbQueries,
+    organizers::models::EventAccess,
+    signups::{
+        errors::ExportError,
+        export::{ExportFormat, ParticipantTable},
+        models::{waitlist, SignupStatus},
+    },
+};
+
+use super::{
+    authorization::authorize, error::RestError, post_signup::SignupResponse, query::Query,
+};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Participants {
+    pub confirmed: Vec<SignupResponse>,
+    pub waitlist: Vec<SignupResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct ExportOptions {
+    pub format: ExportFormat,
+    /// One of the event's languages. Defaults to the first one alphabetically.
+    pub language: Option<String>,
+}
+
+pub async fn get_participants(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<Json<Participants>, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    let (mut confirmed, waitlisted): (Vec<_>, Vec<_>) = dynamodb
+        .get_signups(event_id)
+        .await?
+        .into_iter()
+        .partition(|s| s.status == SignupStatus::Confirmed);
+    confirmed.sort_by_key(|s| s.signup_date);
+    let confirmed = confirmed.into_iter().map(SignupResponse::from).collect();
+    let waitlist = waitlist(waitlisted)
+        .into_iter()
+        .enumerate()
+        .map(|(index, signup)| SignupResponse {
+            waitlist_position: Some(index + 1),
+            ..signup.into()
+        })
+        .collect();
+
+    Ok(Json(Participants {
+        confirmed,
+        waitlist,
+    }))
+}
+
+pub async fn export_participants(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    Query(options): Query<ExportOptions>,
+) -> Result<impl IntoResponse, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    let language = match options.language {
+        Some(language) if event.title.contains_key(&language) => language,
+        Some(language) => return Err(ExportError::UnsupportedLanguage(language).into()),
+        None => event.title.keys().min().cloned().unwrap_or_default(),
+    };
+
+    let signups = dynamodb.get_signups(event_id).await?;
+    let table = ParticipantTable::new(&event, signups, &language);
+    let body = match options.format {
+        ExportFormat::Csv => Body::from_stream(futures_util::stream::iter(table.into_csv_lines())),
+        ExportFormat::Xlsx => Body::from(table.to_xlsx()?),
+    };
+
+    Ok((
+        [
+            (
+                header::CONTENT_TYPE,
+                options.format.content_type().to_owned(),
+            ),
+            (
+                header::CONTENT_DISPOSITION,
+                format!(
+                    "attachment; filename=\"participants-{event_id}.{}\"",
+                    options.format.extension()
+                ),
+            ),
+        ],
+        body,
+    ))
+}
@@ -0,0 +1,29 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use uuid::Uuid;
+
+use crate::organizers::models::EventAccess;
+use crate::{authentication::Claims, events::queries::DynamodbQueries, signups::models::waitlist};
+
+use super::{authorization::authorize, error::RestError, post_signup::SignupResponse};
+
+pub async fn get_waitlist(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<Json<Vec<SignupResponse>>, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    let waitlist = waitlist(dynamodb.get_signups(event_id).await?)
+        .into_iter()
+        .enumerate()
+        .map(|(index, signup)| SignupResponse {
+            waitlist_position: Some(index + 1),
+            ..signup.into()
+        })
+        .collect();
+
+    Ok(Json(waitlist))
+}
@@ -0,0 +1,121 @@
+//! Cover images uploaded straight to the bucket. The client asks for an upload link, puts the
+//! image there, and polls the upload until the image is processed.
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::queries::DynamodbQueries,
+    images::{formats::UploadFormat, framing::ImageFraming},
+    organizers::models::EventAccess,
+    uploads::{
+        models::{ImageUpload, UploadStatus},
+        processing::presign_upload,
+    },
+};
+
+use super::{
+    authorization::authorize,
+    error::{error_codes, RestError},
+    json,
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewImageUpload {
+    /// Content type of the image the client is going to upload
+    pub content_type: String,
+    #[serde(flatten)]
+    pub framing: ImageFraming,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUploadLink {
+    pub id: Uuid,
+    pub url: String,
+    pub method: String,
+    /// Headers the upload must be sent with, as they are part of the signature
+    pub headers: HashMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageUploadResponse {
+    pub id: Uuid,
+    pub status: UploadStatus,
+    #[serde(with = "time::serde::rfc3339")]
+    pub requested_at: time::OffsetDateTime,
+    pub image_id: Option<Uuid>,
+    pub error_code: Option<String>,
+}
+
+/// Creates a link to upload a new cover image to
+pub async fn post_image_upload(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    json::Json(new_upload): json::Json<NewImageUpload>,
+) -> Result<(StatusCode, Json<ImageUploadLink>), RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    UploadFormat::from_content_type(&new_upload.content_type)?;
+    new_upload.framing.validate()?;
+
+    let upload = ImageUpload {
+        framing: new_upload.framing,
+        ..ImageUpload::new(event_id)
+    };
+    // The upload is recorded first, so that it is pending before the image can arrive
+    dynamodb.put_upload(&upload).await?;
+    let request = presign_upload(&s3, &upload, &new_upload.content_type).await?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(ImageUploadLink {
+            id: upload.upload_id,
+            url: request.uri().to_owned(),
+            method: request.method().to_owned(),
+            headers: request
+                .headers()
+                .map(|(name, value)| (name.to_owned(), value.to_owned()))
+                .collect(),
+        }),
+    ))
+}
+
+pub async fn get_image_upload(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, upload_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<Json<ImageUploadResponse>, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let upload = dynamodb.get_upload(event_id, upload_id).await?;
+    if upload.is_abandoned(time::OffsetDateTime::now_utc()) {
+        tracing::debug!("Upload {upload_id} of {event_id} stopped while processing");
+        return Ok(Json(ImageUploadResponse {
+            id: upload.upload_id,
+            status: UploadStatus::Failed,
+            requested_at: upload.requested_at,
+            image_id: None,
+            error_code: Some(error_codes::IMAGE_PROCESSING_STOPPED.to_owned()),
+        }));
+    }
+    Ok(Json(ImageUploadResponse {
+        id: upload.upload_id,
+        status: upload.status,
+        requested_at: upload.requested_at,
+        image_id: upload.image_id,
+        error_code: upload.error_code,
+    }))
+}
@@ -0,0 +1,19 @@
+use axum::extract::{rejection::JsonRejection, FromRequest};
+
+use super::error::{error_codes, RestError};
+
+/// JSON body extractor that rejects with a [`RestError`] instead of axum's plain text response
+#[derive(FromRequest)]
+#[from_request(via(axum::Json), rejection(RestError))]
+pub struct Json<T>(pub T);
+
+impl From<JsonRejection> for RestError {
+    fn from(value: JsonRejection) -> Self {
+        tracing::debug!("Rejected request body: {}", value.body_text());
+        Self {
+            status_code: value.status(),
+            error_code: error_codes::INVALID_JSON.to_string(),
+            error_params: None,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{header, request::Parts},
+};
+use serde::Deserialize;
+
+use crate::localization::LanguagePreference;
+
+use super::{error::RestError, query::Query};
+
+#[derive(Deserialize)]
+struct LanguageOptions {
+    lang: Option<String>,
+}
+
+/// Languages the client asks for, through the `lang` query parameter or the `Accept-Language`
+/// header
+pub struct Language(pub LanguagePreference);
+
+impl<S> FromRequestParts<S> for Language
+where
+    S: Send + Sync,
+{
+    type Rejection = RestError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(options) = Query::<LanguageOptions>::from_request_parts(parts, state).await?;
+        let accept_language = parts
+            .headers
+            .get(header::ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok());
+        Ok(Self(LanguagePreference::new(
+            options.lang.as_deref(),
+            accept_language,
+        )))
+    }
+}
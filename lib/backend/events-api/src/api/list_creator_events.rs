@@ -0,0 +1,122 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::{
+        errors::ListEventsError,
+        listing::{CreatorEventSummary, CreatorEventsFilter, EventPeriod, ListingCursor},
+        queries::DynamodbQueries,
+    },
+    organizers::models::OrganizerRole,
+};
+
+use super::{error::RestError, query::Query};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Deserialize)]
+pub struct ListCreatorEventsOptions {
+    pub period: Option<EventPeriod>,
+    /// `false` lists only hidden events, `true` only visible ones
+    pub visible: Option<bool>,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorEventListItem {
+    pub id: Uuid,
+    pub title: HashMap<String, String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub event_date: time::OffsetDateTime,
+    pub location_name: String,
+    pub visible: bool,
+    pub participants_count: u16,
+    pub waitlist_count: u16,
+    pub role: OrganizerRole,
+}
+
+impl From<CreatorEventSummary> for CreatorEventListItem {
+    fn from(value: CreatorEventSummary) -> Self {
+        Self {
+            id: value.id,
+            title: value.title,
+            event_date: value.event_date,
+            location_name: value.location_name,
+            visible: value.event_visible,
+            participants_count: value.participants_count,
+            waitlist_count: value.waitlist_count,
+            role: value.role,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorEventList {
+    pub events: Vec<CreatorEventListItem>,
+    /// Pass as `cursor` to get the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+pub async fn list_creator_events(
+    State(dynamodb): State<DynamodbQueries>,
+    claims: Claims,
+    Query(options): Query<ListCreatorEventsOptions>,
+) -> Result<Json<CreatorEventList>, RestError> {
+    list_events_of(&dynamodb, &claims.username, options).await
+}
+
+/// The events another user organizes, for user management
+pub async fn list_user_events(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(username): Path<String>,
+    Query(options): Query<ListCreatorEventsOptions>,
+) -> Result<Json<CreatorEventList>, RestError> {
+    list_events_of(&dynamodb, &username, options).await
+}
+
+async fn list_events_of(
+    dynamodb: &DynamodbQueries,
+    username: &str,
+    options: ListCreatorEventsOptions,
+) -> Result<Json<CreatorEventList>, RestError> {
+    let start_key = options
+        .cursor
+        .map(|cursor| ListingCursor::decode(&cursor).map_err(|_| ListEventsError::InvalidCursor))
+        .transpose()?
+        .map(|cursor| cursor.0);
+    let limit = options
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    let filter = CreatorEventsFilter {
+        period: options.period,
+        visible: options.visible,
+        now: time::OffsetDateTime::now_utc(),
+    };
+
+    let page = dynamodb
+        .list_creator_events(username, filter, limit, start_key)
+        .await?;
+
+    Ok(Json(CreatorEventList {
+        events: page
+            .events
+            .into_iter()
+            .map(CreatorEventListItem::from)
+            .collect(),
+        next_cursor: page
+            .last_evaluated_key
+            .and_then(|key| ListingCursor(key).encode()),
+    }))
+}
@@ -0,0 +1,113 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    events::{
+        errors::ListEventsError,
+        listing::{EventSummary, ListingCursor},
+        queries::DynamodbQueries,
+    },
+    localization::{localized, LanguagePreference},
+};
+
+use super::{error::RestError, language::Language, query::Query};
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
+
+#[derive(Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Deserialize)]
+pub struct ListEventsOptions {
+    /// Earliest event date to include. Defaults to now, listing only upcoming events.
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub from: Option<time::OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub to: Option<time::OffsetDateTime>,
+    #[serde(default)]
+    pub sort: SortOrder,
+    pub limit: Option<usize>,
+    pub cursor: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventListItem {
+    pub id: Uuid,
+    /// Language of the title
+    pub language: String,
+    pub title: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub event_date: time::OffsetDateTime,
+    pub location_name: String,
+    pub image: Option<Uuid>,
+}
+
+impl EventListItem {
+    fn new(value: EventSummary, preference: &LanguagePreference) -> Self {
+        let language = preference
+            .resolve(value.title.keys())
+            .unwrap_or_default()
+            .to_owned();
+        Self {
+            id: value.id,
+            title: localized(&value.title, &language),
+            language,
+            event_date: value.event_date,
+            location_name: value.location_name,
+            image: value.image,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventList {
+    pub events: Vec<EventListItem>,
+    /// Pass as `cursor` to get the next page. Absent on the last page.
+    pub next_cursor: Option<String>,
+}
+
+pub async fn list_events(
+    State(dynamodb): State<DynamodbQueries>,
+    Query(options): Query<ListEventsOptions>,
+    Language(preference): Language,
+) -> Result<Json<EventList>, RestError> {
+    let start_key = options
+        .cursor
+        .map(|cursor| ListingCursor::decode(&cursor).map_err(|_| ListEventsError::InvalidCursor))
+        .transpose()?
+        .map(|cursor| cursor.0);
+    let limit = options
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let page = dynamodb
+        .list_events(
+            options.from.unwrap_or_else(time::OffsetDateTime::now_utc),
+            options.to,
+            matches!(options.sort, SortOrder::Asc),
+            limit,
+            start_key,
+        )
+        .await?;
+
+    Ok(Json(EventList {
+        events: page
+            .events
+            .into_iter()
+            .map(|event| EventListItem::new(event, &preference))
+            .collect(),
+        next_cursor: page
+            .last_evaluated_key
+            .and_then(|key| ListingCursor(key).encode()),
+    }))
+}
@@ -1,3 +1,25 @@
+pub mod audit_log;
+pub mod authorization;
+pub mod calendar;
+pub mod delete_event;
+pub mod delete_signup;
 pub mod error;
+pub mod gallery;
 pub mod get_event;
+pub mod get_participants;
+pub mod get_waitlist;
+pub mod image_upload;
+pub mod json;
+pub mod language;
+pub mod list_creator_events;
+pub mod list_events;
+pub mod organizers;
+pub mod own_signup;
+pub mod patch_event;
+pub mod post_event;
+pub mod post_preview;
+pub mod post_signup;
+pub mod promote_signup;
 pub mod put_image;
+pub mod put_waitlist;
+pub mod query;
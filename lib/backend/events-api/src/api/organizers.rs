@@ -0,0 +1,132 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    audit::models::AuditAction,
+    authentication::Claims,
+    events::queries::DynamodbQueries,
+    organizers::models::{EventAccess, Organizer, OrganizerRole},
+};
+
+use super::{authorization::authorize, error::RestError, json};
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizerResponse {
+    pub username: String,
+    pub role: OrganizerRole,
+}
+
+#[derive(Deserialize)]
+pub struct OrganizerInvite {
+    pub role: OrganizerRole,
+}
+
+#[derive(Deserialize)]
+pub struct OwnershipTransfer {
+    pub username: String,
+}
+
+/// Lists everyone organizing the event, starting with the owner
+pub async fn get_organizers(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<Json<Vec<OrganizerResponse>>, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::View).await?;
+
+    let owner = OrganizerResponse {
+        username: event.creator_username,
+        role: OrganizerRole::Owner,
+    };
+    let organizers = std::iter::once(owner)
+        .chain(
+            dynamodb
+                .get_organizers(event_id)
+                .await?
+                .into_iter()
+                .map(|organizer| OrganizerResponse {
+                    username: organizer.username,
+                    role: organizer.role,
+                }),
+        )
+        .collect();
+
+    Ok(Json(organizers))
+}
+
+/// Adds a co-organizer to the event, or changes the role of one
+pub async fn put_organizer(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, username)): Path<(Uuid, String)>,
+    claims: Claims,
+    json::Json(invite): json::Json<OrganizerInvite>,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Manage).await?;
+
+    dynamodb
+        .put_organizer(&Organizer {
+            event_id,
+            username: username.clone(),
+            role: invite.role,
+        })
+        .await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::OrganizerChanged,
+            Some(username),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn delete_organizer(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, username)): Path<(Uuid, String)>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Manage).await?;
+
+    dynamodb.remove_organizer(event_id, &username).await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::OrganizerRemoved,
+            Some(username),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Hands the event over to another user. The previous owner stays on as an editor.
+pub async fn put_owner(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    json::Json(transfer): json::Json<OwnershipTransfer>,
+) -> Result<StatusCode, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::Manage).await?;
+
+    let new_owner = transfer.username.trim();
+    dynamodb.transfer_ownership(&event, new_owner).await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::OwnershipTransferred,
+            Some(new_owner.to_owned()),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,136 @@
+//! Lets participants manage their own signup through the signed token they got when signing up
+
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::{
+    calendar::tokens::ParticipantFeedToken,
+    events::queries::DynamodbQueries,
+    signups::{
+        answers::validate_answers,
+        errors::SignupValidationError,
+        models::Signup,
+        tokens::SignupToken,
+        validation::{is_signup_open, validate_participant},
+    },
+};
+
+use super::{
+    error::RestError,
+    json::Json,
+    patch_event::nullable,
+    post_signup::{waitlist_position, SignupResponse},
+};
+
+#[derive(Deserialize)]
+pub struct SignupPatch {
+    pub name: Option<String>,
+    pub email: Option<String>,
+    /// `null` removes the phone number
+    #[serde(default, deserialize_with = "nullable")]
+    pub phone: Option<Option<String>>,
+    pub answers: Option<HashMap<String, Value>>,
+}
+
+fn decode_token(token: &str) -> Result<SignupToken, SignupValidationError> {
+    SignupToken::decode(token).map_err(|_| {
+        tracing::debug!("Rejected invalid signup token");
+        SignupValidationError::InvalidToken
+    })
+}
+
+fn calendar_token(signup: &Signup) -> Option<String> {
+    signup
+        .calendar_secret
+        .map(|calendar_secret| ParticipantFeedToken { calendar_secret }.encode())
+}
+
+pub async fn get_own_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(token): Path<String>,
+) -> Result<SignupResponse, RestError> {
+    let token = decode_token(&token)?;
+    let signup = dynamodb.get_signup(token.event_id, token.signup_id).await?;
+
+    Ok(SignupResponse {
+        waitlist_position: waitlist_position(&dynamodb, &signup).await?,
+        calendar_token: calendar_token(&signup),
+        ..signup.into()
+    })
+}
+
+pub async fn patch_own_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(token): Path<String>,
+    Json(patch): Json<SignupPatch>,
+) -> Result<SignupResponse, RestError> {
+    let token = decode_token(&token)?;
+    let event = dynamodb.get_event(token.event_id).await?;
+    if event.cancelled_at.is_some() {
+        return Err(SignupValidationError::EventCancelled.into());
+    }
+    if !is_signup_open(&event, time::OffsetDateTime::now_utc()) {
+        return Err(SignupValidationError::SignupClosed.into());
+    }
+
+    let mut signup = dynamodb.get_signup(token.event_id, token.signup_id).await?;
+    if let Some(name) = patch.name {
+        signup.name = name.trim().to_owned();
+    }
+    if let Some(email) = patch.email {
+        signup.email = email.trim().to_owned();
+    }
+    if let Some(phone) = patch.phone {
+        signup.phone = phone;
+    }
+    if let Some(answers) = patch.answers {
+        signup.answers = validate_answers(&event.questions, &answers)
+            .map_err(SignupValidationError::InvalidAnswers)?;
+    }
+    validate_participant(&signup)?;
+
+    tracing::debug!("Updating signup {}", signup.id);
+    dynamodb.update_signup_details(&signup).await?;
+
+    Ok(SignupResponse {
+        waitlist_position: waitlist_position(&dynamodb, &signup).await?,
+        calendar_token: calendar_token(&signup),
+        ..signup.into()
+    })
+}
+
+pub async fn delete_own_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(token): Path<String>,
+) -> Result<StatusCode, RestError> {
+    let token = decode_token(&token)?;
+
+    tracing::debug!("Cancelling signup {}", token.signup_id);
+    dynamodb
+        .cancel_signup(token.event_id, token.signup_id)
+        .await?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::SignupPatch;
+
+    #[rstest]
+    #[case(r#"{}"#, None)]
+    #[case(r#"{"phone": null}"#, Some(None))]
+    #[case(r#"{"phone": "0701234567"}"#, Some(Some("0701234567".to_owned())))]
+    fn test_phone_patch(#[case] body: &str, #[case] expected: Option<Option<String>>) {
+        let patch: SignupPatch = serde_json::from_str(body).expect("Failed to parse patch");
+        assert_eq!(patch.phone, expected);
+    }
+}
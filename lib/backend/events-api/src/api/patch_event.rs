@@ -0,0 +1,194 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use axum_extra::{
+    headers::{ETag, IfMatch},
+    TypedHeader,
+};
+use serde::{Deserialize, Deserializer};
+use uuid::Uuid;
+
+use crate::{
+    audit::models::AuditAction,
+    authentication::Claims,
+    events::{models, queries::DynamodbQueries, questions::Question, validation::validate_event},
+    organizers::models::EventAccess,
+};
+
+use super::{
+    authorization::authorize,
+    error::{error_codes, RestError},
+    get_event::{event_etag, Contact, Event, Location},
+    json::Json,
+};
+
+/// Distinguishes a field set to `null` from a field which is left out
+pub fn nullable<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// [`nullable`] for RFC 3339 dates
+fn nullable_datetime<'de, D>(
+    deserializer: D,
+) -> Result<Option<Option<time::OffsetDateTime>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    time::serde::rfc3339::option::deserialize(deserializer).map(Some)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventPatch {
+    pub title: Option<HashMap<String, String>>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub signup_end_date: Option<time::OffsetDateTime>,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub event_date: Option<time::OffsetDateTime>,
+    #[serde(default, deserialize_with = "nullable_datetime")]
+    pub end_date: Option<Option<time::OffsetDateTime>>,
+    pub all_day: Option<bool>,
+    pub time_zone: Option<String>,
+    pub location: Option<Location>,
+    pub contact: Option<Contact>,
+    pub description: Option<HashMap<String, String>>,
+    #[serde(default, deserialize_with = "nullable")]
+    pub limit: Option<Option<u16>>,
+    pub visible: Option<bool>,
+    pub questions: Option<Vec<Question>>,
+}
+
+impl EventPatch {
+    fn apply(self, event: &mut models::Event) {
+        if let Some(title) = self.title {
+            event.title = title;
+        }
+        if let Some(signup_end_date) = self.signup_end_date {
+            event.signup_end_date = signup_end_date;
+        }
+        if let Some(event_date) = self.event_date {
+            event.event_date = event_date;
+        }
+        if let Some(end_date) = self.end_date {
+            event.end_date = end_date;
+        }
+        if let Some(all_day) = self.all_day {
+            event.all_day = all_day;
+        }
+        if let Some(time_zone) = self.time_zone {
+            event.time_zone = time_zone;
+        }
+        if let Some(location) = self.location {
+            event.location_name = location.name;
+            event.location_link = location.link;
+        }
+        if let Some(contact) = self.contact {
+            // The email is hidden from the public event, so leaving it out keeps the current one
+            if let Some(email) = contact.email {
+                event.email = email;
+            }
+            event.email_visible = contact.email_visible;
+            event.organizer_name = contact.organizer;
+            event.phone = contact.phone;
+        }
+        if let Some(description) = self.description {
+            event.description = description;
+        }
+        if let Some(limit) = self.limit {
+            event.limit = limit;
+        }
+        if let Some(visible) = self.visible {
+            event.event_visible = visible;
+        }
+        if let Some(questions) = self.questions {
+            event.questions = questions;
+        }
+    }
+}
+
+pub async fn patch_event(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    if_match: Option<TypedHeader<IfMatch>>,
+    Json(patch): Json<EventPatch>,
+) -> Result<(TypedHeader<ETag>, Event), RestError> {
+    let mut event = authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    if let Some(TypedHeader(if_match)) = if_match {
+        if !if_match.precondition_passes(&event_etag(event.version)) {
+            tracing::debug!("If-Match does not match version {}", event.version);
+            return Err(RestError {
+                status_code: StatusCode::PRECONDITION_FAILED,
+                error_code: error_codes::EVENT_VERSION_CONFLICT.to_string(),
+                error_params: None,
+            });
+        }
+    }
+
+    patch.apply(&mut event);
+    event.align_all_day_dates();
+    validate_event(&event)?;
+
+    tracing::debug!("Updating event {} from version {}", event.id, event.version);
+    event.version = dynamodb.update_event(&event).await?;
+    dynamodb
+        .record_audit(event_id, &claims.username, AuditAction::EventChanged, None)
+        .await;
+
+    if event.waitlist_count > 0 && !event.is_full() {
+        // The update itself succeeded, so a failed promotion is only reported
+        match dynamodb.fill_from_waitlist(event_id).await {
+            Ok(0) => {}
+            Ok(promoted) => {
+                tracing::debug!("Promoted {promoted} from the waitlist of {event_id}");
+                event = dynamodb.get_event(event_id).await?;
+            }
+            Err(e) => {
+                tracing::error!("Failed to promote from waitlist: {e:?}");
+                sentry::capture_error(&e);
+            }
+        }
+    }
+
+    Ok((event_etag(event.version), event.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::datetime;
+
+    use super::EventPatch;
+
+    #[rstest]
+    #[case(r#"{}"#, None)]
+    #[case(r#"{"limit": null}"#, Some(None))]
+    #[case(r#"{"limit": 10}"#, Some(Some(10)))]
+    fn test_limit_patch(#[case] body: &str, #[case] expected: Option<Option<u16>>) {
+        let patch: EventPatch = serde_json::from_str(body).expect("Failed to parse patch");
+        assert_eq!(patch.limit, expected);
+    }
+
+    #[rstest]
+    #[case(r#"{}"#, None)]
+    #[case(r#"{"endDate": null}"#, Some(None))]
+    #[case(
+        r#"{"endDate": "2025-03-08T12:00:00Z"}"#,
+        Some(Some(datetime!(2025-03-08 12:00 UTC)))
+    )]
+    fn test_end_date_patch(
+        #[case] body: &str,
+        #[case] expected: Option<Option<time::OffsetDateTime>>,
+    ) {
+        let patch: EventPatch = serde_json::from_str(body).expect("Failed to parse patch");
+        assert_eq!(patch.end_date, expected);
+    }
+}
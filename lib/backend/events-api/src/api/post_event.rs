@@ -0,0 +1,94 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::{headers::ETag, TypedHeader};
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::{
+        errors::EventValidationError, models, queries::DynamodbQueries, questions::Question,
+        validation::validate_event,
+    },
+};
+
+use super::{
+    error::RestError,
+    get_event::{event_etag, Contact, Event, Location},
+    json::Json,
+};
+
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewEvent {
+    pub title: HashMap<String, String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub signup_end_date: time::OffsetDateTime,
+    #[serde(with = "time::serde::rfc3339")]
+    pub event_date: time::OffsetDateTime,
+    #[serde(default, with = "time::serde::rfc3339::option")]
+    pub end_date: Option<time::OffsetDateTime>,
+    #[serde(default)]
+    pub all_day: bool,
+    pub time_zone: Option<String>,
+    pub location: Location,
+    pub contact: Contact,
+    pub description: HashMap<String, String>,
+    pub limit: Option<u16>,
+    pub visible: bool,
+    #[serde(default)]
+    pub questions: Vec<Question>,
+}
+
+impl NewEvent {
+    fn into_event(self, creator_username: String) -> Result<models::Event, EventValidationError> {
+        let email = self
+            .contact
+            .email
+            .ok_or(EventValidationError::InvalidEmail)?;
+        Ok(models::Event {
+            id: Uuid::new_v4(),
+            title: self.title,
+            signup_end_date: self.signup_end_date,
+            event_date: self.event_date,
+            creator_username,
+            description: self.description,
+            limit: self.limit,
+            image: None,
+            image_variants: vec![],
+            image_focal_point: None,
+            event_visible: self.visible,
+            phone: self.contact.phone,
+            email,
+            email_visible: self.contact.email_visible,
+            organizer_name: self.contact.organizer,
+            location_name: self.location.name,
+            location_link: self.location.link,
+            version: 1,
+            participants_count: 0,
+            waitlist_count: 0,
+            cancelled_at: None,
+            questions: self.questions,
+            end_date: self.end_date,
+            all_day: self.all_day,
+            time_zone: self
+                .time_zone
+                .unwrap_or_else(|| models::DEFAULT_TIME_ZONE.to_owned()),
+        })
+    }
+}
+
+pub async fn post_event(
+    State(dynamodb): State<DynamodbQueries>,
+    claims: Claims,
+    Json(new_event): Json<NewEvent>,
+) -> Result<(StatusCode, TypedHeader<ETag>, Event), RestError> {
+    let mut event = new_event.into_event(claims.username)?;
+    event.align_all_day_dates();
+    validate_event(&event)?;
+
+    tracing::debug!("Creating event with id: {}", event.id);
+    dynamodb.create_event(&event).await?;
+
+    Ok((StatusCode::CREATED, event_etag(event.version), event.into()))
+}
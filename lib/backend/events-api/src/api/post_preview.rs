@@ -0,0 +1,44 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    authentication::Claims,
+    events::{preview::PreviewToken, queries::DynamodbQueries},
+    organizers::models::EventAccess,
+};
+
+use super::{authorization::authorize, error::RestError};
+
+/// How long a preview link can be used after it is created
+const PREVIEW_LINK_LIFETIME: time::Duration = time::Duration::days(7);
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviewLink {
+    /// Passed as the `preview` query parameter when getting the event
+    pub token: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub expires_at: time::OffsetDateTime,
+}
+
+pub async fn post_preview(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<Json<PreviewLink>, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let token = PreviewToken {
+        event_id,
+        expires_at: time::OffsetDateTime::now_utc() + PREVIEW_LINK_LIFETIME,
+    };
+
+    Ok(Json(PreviewLink {
+        token: token.encode(),
+        expires_at: token.expires_at,
+    }))
+}
@@ -0,0 +1,153 @@
+use std::collections::HashMap;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    calendar::tokens::ParticipantFeedToken,
+    events::{errors::GetEventError, queries::DynamodbQueries},
+    signups::{
+        answers::{validate_answers, Answer},
+        errors::SignupValidationError,
+        models::{waitlist, Signup, SignupStatus},
+        tokens::SignupToken,
+        validation::validate_signup,
+    },
+};
+
+use super::{error::RestError, json::Json};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewSignup {
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    #[serde(default)]
+    pub answers: HashMap<String, Value>,
+    /// Adds the signup to the calendar feed of an earlier signup, instead of starting a new feed
+    pub calendar_token: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignupResponse {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    #[serde(with = "time::serde::rfc3339")]
+    pub signup_date: time::OffsetDateTime,
+    pub status: SignupStatus,
+    /// Place in line on the waitlist, starting at 1
+    pub waitlist_position: Option<usize>,
+    pub answers: HashMap<String, Answer>,
+    /// Lets the participant view, edit and cancel their signup. Only given to the participant.
+    pub token: Option<String>,
+    /// Reaches the calendar feed of the events the participant signed up for. Only given to the
+    /// participant, and absent for signups made before feeds were keyed by a secret.
+    pub calendar_token: Option<String>,
+}
+
+impl IntoResponse for SignupResponse {
+    fn into_response(self) -> axum::response::Response {
+        axum::Json(self).into_response()
+    }
+}
+
+impl From<Signup> for SignupResponse {
+    fn from(value: Signup) -> Self {
+        Self {
+            id: value.id,
+            event_id: value.event_id,
+            name: value.name,
+            email: value.email,
+            phone: value.phone,
+            signup_date: value.signup_date,
+            status: value.status,
+            waitlist_position: None,
+            answers: value.answers,
+            token: None,
+            calendar_token: None,
+        }
+    }
+}
+
+pub async fn post_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    Json(new_signup): Json<NewSignup>,
+) -> Result<(StatusCode, SignupResponse), RestError> {
+    let event = dynamodb.get_event(event_id).await?;
+    if !event.event_visible {
+        return Err(GetEventError::NotFound.into());
+    }
+
+    let answers = validate_answers(&event.questions, &new_signup.answers)
+        .map_err(SignupValidationError::InvalidAnswers)?;
+    let calendar_secret = match new_signup
+        .calendar_token
+        .map(|token| ParticipantFeedToken::decode(&token))
+    {
+        Some(Ok(token)) => token.calendar_secret,
+        Some(Err(_)) => {
+            tracing::debug!("Starting a new calendar feed in place of an invalid token");
+            Uuid::new_v4()
+        }
+        None => Uuid::new_v4(),
+    };
+    let signup = Signup {
+        id: Uuid::new_v4(),
+        event_id,
+        name: new_signup.name.trim().to_owned(),
+        email: new_signup.email.trim().to_owned(),
+        phone: new_signup.phone,
+        signup_date: time::OffsetDateTime::now_utc(),
+        status: SignupStatus::Confirmed,
+        waitlist_position: None,
+        answers,
+        calendar_secret: Some(calendar_secret),
+    };
+    validate_signup(&event, &signup)?;
+
+    tracing::debug!("Signing up {} for event {}", signup.id, event_id);
+    let signup = dynamodb.sign_up(signup).await?;
+
+    let token = SignupToken {
+        event_id,
+        signup_id: signup.id,
+    }
+    .encode();
+
+    Ok((
+        StatusCode::CREATED,
+        SignupResponse {
+            waitlist_position: waitlist_position(&dynamodb, &signup).await?,
+            token: Some(token),
+            calendar_token: Some(ParticipantFeedToken { calendar_secret }.encode()),
+            ..signup.into()
+        },
+    ))
+}
+
+/// Place in line of a signup, if it is on the waitlist
+pub async fn waitlist_position(
+    dynamodb: &DynamodbQueries,
+    signup: &Signup,
+) -> Result<Option<usize>, RestError> {
+    if signup.status != SignupStatus::Waitlisted {
+        return Ok(None);
+    }
+
+    Ok(waitlist(dynamodb.get_signups(signup.event_id).await?)
+        .iter()
+        .position(|s| s.id == signup.id)
+        .map(|index| index + 1))
+}
@@ -0,0 +1,41 @@
+use axum::extract::{Path, State};
+use uuid::Uuid;
+
+use crate::{
+    audit::models::AuditAction,
+    authentication::Claims,
+    events::queries::DynamodbQueries,
+    organizers::models::EventAccess,
+    signups::{errors::SignupValidationError, models::SignupStatus},
+};
+
+use super::{authorization::authorize, error::RestError, post_signup::SignupResponse};
+
+/// Gives a waitlisted person a confirmed spot, even if that goes past the participants limit
+pub async fn promote_signup(
+    State(dynamodb): State<DynamodbQueries>,
+    Path((event_id, signup_id)): Path<(Uuid, Uuid)>,
+    claims: Claims,
+) -> Result<SignupResponse, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let mut signup = dynamodb.get_signup(event_id, signup_id).await?;
+    if signup.status != SignupStatus::Waitlisted {
+        return Err(SignupValidationError::NotWaitlisted.into());
+    }
+
+    tracing::debug!("Promoting {} on event {}", signup_id, event_id);
+    dynamodb.promote_signup(&signup, false).await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::SignupPromoted,
+            Some(signup_id.to_string()),
+        )
+        .await;
+    signup.status = SignupStatus::Confirmed;
+    signup.waitlist_position = None;
+
+    Ok(signup.into())
+}
@@ -1,24 +1,27 @@
-use std::io::Cursor;
-
 use axum::{
     extract::{Path, State},
+    http::StatusCode,
     response::IntoResponse,
 };
 use axum_extra::{headers::ContentType, TypedHeader};
 use bytes::Bytes;
-use image::ImageReader;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::{
     authentication::Claims,
     events::queries::DynamodbQueries,
-    images::{conform_image, errors::ImageUploadError, is_image_too_small, upload_image},
+    images::{
+        conform_image, decode_image, discard_cover_image,
+        errors::ImageUploadError,
+        formats::UploadFormat,
+        framing::{CropRectangle, FocalPoint, ImageFraming},
+        is_image_too_small, upload_image, MAX_IMAGE_SIZE,
+    },
+    organizers::models::EventAccess,
 };
 
-use super::error::{NotEventOwnerError, RestError};
-
-const MAX_IMAGE_SIZE: usize = 1024 * 1024 * 10;
+use super::{authorization::authorize, error::RestError, query::Query};
 
 #[derive(Serialize)]
 pub struct PutImageResponse {
@@ -31,25 +34,46 @@ impl IntoResponse for PutImageResponse {
     }
 }
 
-fn get_image_from_body(body: Bytes) -> Result<image::DynamicImage, ImageUploadError> {
-    tracing::debug!("Reading image from body");
-    let cursor = Cursor::new(body);
-    let image = ImageReader::new(cursor)
-        .with_guessed_format()
-        .map_err(|e| {
-            sentry::capture_error(&e);
-            tracing::debug!("Failed to guess image format");
-            ImageUploadError::ImageTypeGuessError
-        })?
-        .decode()
-        .map_err(|e| {
-            sentry::capture_error(&e);
-            tracing::error!("Failed to decode image: {:?}", e);
-            ImageUploadError::ImageDecodingError
-        })?;
-    tracing::debug!("Image read from body");
+/// How to frame the image, as the body is the image itself. A crop needs all four of its
+/// parameters, and a focal point both of its.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FramingOptions {
+    crop_x: Option<f32>,
+    crop_y: Option<f32>,
+    crop_width: Option<f32>,
+    crop_height: Option<f32>,
+    focal_x: Option<f32>,
+    focal_y: Option<f32>,
+}
 
-    Ok(image)
+impl TryFrom<FramingOptions> for ImageFraming {
+    type Error = ImageUploadError;
+    fn try_from(options: FramingOptions) -> Result<Self, Self::Error> {
+        let crop = match (
+            options.crop_x,
+            options.crop_y,
+            options.crop_width,
+            options.crop_height,
+        ) {
+            (Some(x), Some(y), Some(width), Some(height)) => Some(CropRectangle {
+                x,
+                y,
+                width,
+                height,
+            }),
+            (None, None, None, None) => None,
+            _ => return Err(ImageUploadError::InvalidFraming),
+        };
+        let focal_point = match (options.focal_x, options.focal_y) {
+            (Some(x), Some(y)) => Some(FocalPoint { x, y }),
+            (None, None) => None,
+            _ => return Err(ImageUploadError::InvalidFraming),
+        };
+        let framing = ImageFraming { crop, focal_point };
+        framing.validate()?;
+        Ok(framing)
+    }
 }
 
 pub async fn put_image(
@@ -58,29 +82,50 @@ pub async fn put_image(
     Path(event_id): Path<Uuid>,
     claims: Claims,
     TypedHeader(content_type): TypedHeader<ContentType>,
+    Query(framing): Query<FramingOptions>,
     body: Bytes,
 ) -> Result<PutImageResponse, RestError> {
-    let event = dynamodb.get_event(event_id).await?;
-
-    if event.creator_username != claims.username {
-        return Err(NotEventOwnerError.into());
-    }
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
 
     if body.len() > MAX_IMAGE_SIZE {
         return Err(ImageUploadError::ImageTooLarge.into());
     }
-    if content_type != ContentType::png() && content_type != ContentType::jpeg() {
-        return Err(ImageUploadError::UnsupportedImageFormat.into());
-    }
+    let format = UploadFormat::from_content_type(&content_type.to_string())?;
+    let framing = ImageFraming::try_from(framing)?;
 
-    let image = get_image_from_body(body)?;
+    let image = decode_image(body, format, framing).await?;
     if is_image_too_small(&image) {
         return Err(ImageUploadError::ImageTooSmall.into());
     }
 
-    let conformed_image = conform_image(image).await?;
-    let image_id = upload_image(&s3, event_id, conformed_image).await?;
-    dynamodb.set_event_image(event_id, image_id).await?;
+    let rendered = conform_image(image, framing.focal_point).await?;
+    let (image_id, variants) = upload_image(&s3, event_id, rendered.variants).await?;
+    let replaced = dynamodb
+        .set_event_image(event_id, image_id, &variants, rendered.focal_point)
+        .await?;
+    if let Some((old_image, old_variants)) = replaced {
+        discard_cover_image(&s3, &dynamodb, event_id, old_image, &old_variants).await;
+    }
 
     Ok(PutImageResponse { image_id })
 }
+
+/// Removes the cover image of the event
+pub async fn delete_event_image(
+    State(s3): State<aws_sdk_s3::Client>,
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+) -> Result<StatusCode, RestError> {
+    let event = authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let Some(image_id) = event.image else {
+        return Ok(StatusCode::NO_CONTENT);
+    };
+    // Another request may have replaced the image in the meantime, and then owns its files
+    if dynamodb.remove_event_image(event_id, image_id).await? {
+        discard_cover_image(&s3, &dynamodb, event_id, image_id, &event.image_variants).await;
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,57 @@
+use std::collections::HashSet;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    audit::models::AuditAction,
+    authentication::Claims,
+    events::queries::DynamodbQueries,
+    organizers::models::EventAccess,
+    signups::{errors::SignupValidationError, models::waitlist},
+};
+
+use super::{authorization::authorize, error::RestError, json::Json};
+
+#[derive(Deserialize)]
+pub struct WaitlistOrder {
+    /// Every signup on the waitlist, first in line first
+    pub order: Vec<Uuid>,
+}
+
+pub async fn put_waitlist(
+    State(dynamodb): State<DynamodbQueries>,
+    Path(event_id): Path<Uuid>,
+    claims: Claims,
+    Json(waitlist_order): Json<WaitlistOrder>,
+) -> Result<StatusCode, RestError> {
+    authorize(&dynamodb, event_id, &claims, EventAccess::Edit).await?;
+
+    let current: HashSet<Uuid> = waitlist(dynamodb.get_signups(event_id).await?)
+        .iter()
+        .map(|signup| signup.id)
+        .collect();
+    let requested: HashSet<Uuid> = waitlist_order.order.iter().copied().collect();
+    if requested.len() != waitlist_order.order.len() || requested != current {
+        return Err(SignupValidationError::InvalidWaitlistOrder.into());
+    }
+
+    tracing::debug!("Reordering waitlist for event {}", event_id);
+    dynamodb
+        .reorder_waitlist(event_id, &waitlist_order.order)
+        .await?;
+    dynamodb
+        .record_audit(
+            event_id,
+            &claims.username,
+            AuditAction::WaitlistReordered,
+            None,
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
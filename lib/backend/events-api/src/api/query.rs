@@ -0,0 +1,19 @@
+use axum::extract::{rejection::QueryRejection, FromRequestParts};
+
+use super::error::{error_codes, RestError};
+
+/// Query string extractor that rejects with a [`RestError`] instead of axum's plain text response
+#[derive(FromRequestParts)]
+#[from_request(via(axum::extract::Query), rejection(RestError))]
+pub struct Query<T>(pub T);
+
+impl From<QueryRejection> for RestError {
+    fn from(value: QueryRejection) -> Self {
+        tracing::debug!("Rejected query string: {}", value.body_text());
+        Self {
+            status_code: value.status(),
+            error_code: error_codes::INVALID_QUERY.to_string(),
+            error_params: None,
+        }
+    }
+}
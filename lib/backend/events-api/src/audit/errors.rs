@@ -0,0 +1,9 @@
+use crate::database::errors::{DatabaseQueryFailed, ModelError};
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuditError {
+    #[error("Failed to read audit entry")]
+    InvalidStoredEntry(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
@@ -0,0 +1,163 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use serde::Serialize;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{datetime_attribute, get_datetime, get_field, get_optional_field},
+    },
+    events::models::columns,
+};
+
+/// Changes organizers make to an event and its signups
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    EventChanged,
+    EventCancelled,
+    EventDeleted,
+    OrganizerChanged,
+    OrganizerRemoved,
+    OwnershipTransferred,
+    SignupRemoved,
+    SignupPromoted,
+    WaitlistReordered,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::EventChanged => "EventChanged",
+            AuditAction::EventCancelled => "EventCancelled",
+            AuditAction::EventDeleted => "EventDeleted",
+            AuditAction::OrganizerChanged => "OrganizerChanged",
+            AuditAction::OrganizerRemoved => "OrganizerRemoved",
+            AuditAction::OwnershipTransferred => "OwnershipTransferred",
+            AuditAction::SignupRemoved => "SignupRemoved",
+            AuditAction::SignupPromoted => "SignupPromoted",
+            AuditAction::WaitlistReordered => "WaitlistReordered",
+        }
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = ModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "EventChanged" => Ok(AuditAction::EventChanged),
+            "EventCancelled" => Ok(AuditAction::EventCancelled),
+            "EventDeleted" => Ok(AuditAction::EventDeleted),
+            "OrganizerChanged" => Ok(AuditAction::OrganizerChanged),
+            "OrganizerRemoved" => Ok(AuditAction::OrganizerRemoved),
+            "OwnershipTransferred" => Ok(AuditAction::OwnershipTransferred),
+            "SignupRemoved" => Ok(AuditAction::SignupRemoved),
+            "SignupPromoted" => Ok(AuditAction::SignupPromoted),
+            "WaitlistReordered" => Ok(AuditAction::WaitlistReordered),
+            _ => Err(ModelError::InvalidData(format!("Unknown audit action {s}"))),
+        }
+    }
+}
+
+/// Who did what to an event, and when. Entries are stored with the event, and kept when the
+/// event is deleted, so that the log tells who deleted it.
+pub struct AuditEntry {
+    pub event_id: Uuid,
+    pub actor: String,
+    pub action: AuditAction,
+    /// The organizer or signup acted on, if any
+    pub target: Option<String>,
+    pub audited_at: OffsetDateTime,
+}
+
+impl AuditEntry {
+    pub const SORT_KEY_PREFIX: &str = "Audit#";
+
+    pub fn new(event_id: Uuid, actor: &str, action: AuditAction, target: Option<String>) -> Self {
+        Self {
+            event_id,
+            actor: actor.to_owned(),
+            action,
+            target,
+            audited_at: OffsetDateTime::now_utc(),
+        }
+    }
+
+    /// Sorts entries by time. The random suffix keeps entries made at the same time apart.
+    fn sort_key(&self) -> Result<String, ModelError> {
+        let audited_at = self
+            .audited_at
+            .to_offset(time::UtcOffset::UTC)
+            .format(&time::format_description::well_known::Rfc3339)
+            .map_err(|_| {
+                ModelError::InvalidType(
+                    columns::AUDITED_AT_COLUMN.to_owned(),
+                    "Rfc3339".to_owned(),
+                    self.audited_at.to_string(),
+                )
+            })?;
+        Ok(format!(
+            "{}{}#{}",
+            Self::SORT_KEY_PREFIX,
+            audited_at,
+            Uuid::new_v4()
+        ))
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for AuditEntry {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            actor: get_field(item, columns::ACTOR_COLUMN)?,
+            action: get_field(item, columns::AUDIT_ACTION_COLUMN)?,
+            target: get_optional_field(item, columns::AUDIT_TARGET_COLUMN, ScalarAttributeType::S)?,
+            audited_at: get_datetime(item, columns::AUDITED_AT_COLUMN)?,
+        })
+    }
+}
+
+impl TryFrom<&AuditEntry> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(entry: &AuditEntry) -> Result<Self, Self::Error> {
+        let mut item = HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(entry.event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(entry.sort_key()?),
+            ),
+            (
+                columns::ACTOR_COLUMN.to_owned(),
+                AttributeValue::S(entry.actor.clone()),
+            ),
+            (
+                columns::AUDIT_ACTION_COLUMN.to_owned(),
+                AttributeValue::S(entry.action.to_string()),
+            ),
+            (
+                columns::AUDITED_AT_COLUMN.to_owned(),
+                datetime_attribute(columns::AUDITED_AT_COLUMN, &entry.audited_at)?,
+            ),
+        ]);
+        if let Some(target) = &entry.target {
+            item.insert(
+                columns::AUDIT_TARGET_COLUMN.to_owned(),
+                AttributeValue::S(target.clone()),
+            );
+        }
+        Ok(item)
+    }
+}
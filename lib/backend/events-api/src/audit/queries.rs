@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{database::errors::DatabaseQueryFailed, events::queries::DynamodbQueries};
+
+use super::{
+    errors::AuditError,
+    models::{AuditAction, AuditEntry},
+};
+
+impl DynamodbQueries {
+    /// Records a change to an event. The change itself already happened, so failures are only
+    /// reported.
+    pub async fn record_audit(
+        &self,
+        event_id: Uuid,
+        actor: &str,
+        action: AuditAction,
+        target: Option<String>,
+    ) {
+        let entry = AuditEntry::new(event_id, actor, action, target);
+        let item: HashMap<String, AttributeValue> = match (&entry).try_into() {
+            Ok(item) => item,
+            Err(e) => {
+                error!("Failed to serialize audit entry: {e:?}");
+                sentry::capture_error(&e);
+                return;
+            }
+        };
+
+        if let Err(e) = self
+            .client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(item))
+            .send()
+            .await
+        {
+            error!("Failed to record {action} on event {event_id}: {e:?}");
+            sentry::capture_error(&e);
+        }
+    }
+
+    /// The audit log of an event, oldest entry first
+    pub async fn get_audit_log(&self, event_id: Uuid) -> Result<Vec<AuditEntry>, AuditError> {
+        let mut entries = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .key_condition_expression("PK = :eventId AND begins_with(SK, :prefix)")
+                .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(AuditEntry::SORT_KEY_PREFIX.to_owned()),
+                )
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    AuditError::from(DatabaseQueryFailed)
+                })?;
+
+            for item in res.items() {
+                let entry = AuditEntry::try_from(item).map_err(|e| {
+                    error!("Failed to parse audit entry: {e:?}");
+                    sentry::capture_error(&e);
+                    AuditError::from(e)
+                })?;
+                entries.push(entry);
+            }
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        audit::models::AuditAction,
+        events::queries::DynamodbQueries,
+        test_fixtures::{init_dynamodb, insert_test_event},
+    };
+
+    #[tokio::test]
+    async fn test_audit_log() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+
+        queries
+            .record_audit(event_id, "owner", AuditAction::EventChanged, None)
+            .await;
+        queries
+            .record_audit(
+                event_id,
+                "owner",
+                AuditAction::OrganizerRemoved,
+                Some("editor".to_owned()),
+            )
+            .await;
+
+        let entries = queries
+            .get_audit_log(event_id)
+            .await
+            .expect("Failed to get audit log");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, AuditAction::EventChanged);
+        assert_eq!(entries[1].action, AuditAction::OrganizerRemoved);
+        assert_eq!(entries[1].target.as_deref(), Some("editor"));
+    }
+}
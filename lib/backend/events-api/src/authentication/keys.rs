@@ -0,0 +1,138 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use jsonwebtoken::{jwk::JwkSet, DecodingKey};
+use tokio::sync::RwLock;
+use tracing::error;
+
+/// Keys are fetched again after this long, so rotated keys eventually stop being trusted
+const KEY_LIFETIME: Duration = Duration::from_secs(60 * 60);
+/// Tokens signed with unknown keys trigger a fetch at most this often
+const MIN_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(thiserror::Error, Debug)]
+pub enum KeyStoreError {
+    #[error("Failed to fetch key set")]
+    FetchFailed,
+    #[error("Key set is invalid")]
+    InvalidKeySet,
+}
+
+pub enum KeySource {
+    /// JWKS endpoint of the user pool
+    Url(String),
+    /// Local JWKS file, for running with self-minted tokens
+    File(PathBuf),
+}
+
+fn parse_key_set(body: &str) -> Result<HashMap<String, DecodingKey>, KeyStoreError> {
+    let key_set: JwkSet = serde_json::from_str(body).map_err(|e| {
+        error!("Failed to parse key set: {e:?}");
+        KeyStoreError::InvalidKeySet
+    })?;
+    key_set
+        .keys
+        .iter()
+        .filter_map(|jwk| {
+            let kid = jwk.common.key_id.clone()?;
+            Some(DecodingKey::from_jwk(jwk).map(|key| (kid, key)))
+        })
+        .collect::<Result<_, _>>()
+        .map_err(|e| {
+            error!("Failed to read key from key set: {e:?}");
+            KeyStoreError::InvalidKeySet
+        })
+}
+
+fn fetch_key_set(url: &str) -> Result<String, KeyStoreError> {
+    ureq::get(url)
+        .timeout(FETCH_TIMEOUT)
+        .call()
+        .map_err(|e| {
+            error!("Failed to fetch key set: {e:?}");
+            sentry::capture_error(&e);
+            KeyStoreError::FetchFailed
+        })?
+        .into_string()
+        .map_err(|e| {
+            error!("Failed to read key set: {e:?}");
+            sentry::capture_error(&e);
+            KeyStoreError::FetchFailed
+        })
+}
+
+impl KeySource {
+    async fn load(&self) -> Result<HashMap<String, DecodingKey>, KeyStoreError> {
+        let body = match self {
+            KeySource::Url(url) => {
+                let url = url.clone();
+                tokio::task::spawn_blocking(move || fetch_key_set(&url))
+                    .await
+                    .map_err(|e| {
+                        error!("Key set fetch did not complete: {e:?}");
+                        KeyStoreError::FetchFailed
+                    })??
+            }
+            KeySource::File(path) => std::fs::read_to_string(path).map_err(|e| {
+                error!("Failed to read key set file {path:?}: {e:?}");
+                KeyStoreError::FetchFailed
+            })?,
+        };
+        parse_key_set(&body)
+    }
+}
+
+#[derive(Default)]
+struct CachedKeys {
+    keys: HashMap<String, DecodingKey>,
+    fetched_at: Option<Instant>,
+}
+
+impl CachedKeys {
+    fn fetched_within(&self, duration: Duration) -> bool {
+        self.fetched_at
+            .is_some_and(|fetched_at| fetched_at.elapsed() < duration)
+    }
+}
+
+/// Signing keys by key id. Kept in a static, the keys survive between invocations of a warm
+/// Lambda, so the key set is only fetched on cold starts, expiry and key rotation.
+pub struct KeyStore {
+    source: KeySource,
+    cache: RwLock<CachedKeys>,
+}
+
+impl KeyStore {
+    pub fn new(source: KeySource) -> Self {
+        Self {
+            source,
+            cache: RwLock::new(CachedKeys::default()),
+        }
+    }
+
+    pub async fn key(&self, kid: &str) -> Result<Option<DecodingKey>, KeyStoreError> {
+        {
+            let cache = self.cache.read().await;
+            if cache.fetched_within(KEY_LIFETIME) {
+                if let Some(key) = cache.keys.get(kid) {
+                    return Ok(Some(key.clone()));
+                }
+            }
+            if cache.fetched_within(MIN_REFRESH_INTERVAL) {
+                return Ok(None);
+            }
+        }
+
+        let mut cache = self.cache.write().await;
+        // Another request may have fetched the keys while this one waited for the lock
+        if !cache.fetched_within(MIN_REFRESH_INTERVAL) {
+            cache.keys = self.source.load().await?;
+            cache.fetched_at = Some(Instant::now());
+        }
+        Ok(cache.keys.get(kid).cloned())
+    }
+}
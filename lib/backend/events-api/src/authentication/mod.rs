@@ -0,0 +1,241 @@
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    RequestPartsExt,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization},
+    TypedHeader,
+};
+use std::sync::LazyLock;
+
+use jsonwebtoken::{decode, decode_header, Algorithm, Validation};
+use keys::{KeySource, KeyStore};
+use serde::Deserialize;
+
+use crate::configuration::{JWKS_FILE, USER_POOL_CLIENT_ID, USER_POOL_ID};
+
+pub mod keys;
+pub mod permissions;
+
+static VERIFIER: LazyLock<TokenVerifier> = LazyLock::new(TokenVerifier::from_environment);
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthError {
+    #[error("Token is invalid")]
+    InvalidToken,
+    #[error("Signing keys are unavailable")]
+    KeysUnavailable,
+}
+
+impl IntoResponse for AuthError {
+    fn into_response(self) -> Response {
+        match self {
+            AuthError::InvalidToken => (StatusCode::UNAUTHORIZED, "Invalid token"),
+            AuthError::KeysUnavailable => {
+                (StatusCode::SERVICE_UNAVAILABLE, "Unable to verify token")
+            }
+        }
+        .into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Claims {
+    /// Missing when the user is in no groups
+    #[serde(rename = "cognito:groups", default)]
+    pub groups: Vec<String>,
+    /// Access tokens name the user `username`, ID tokens `cognito:username`
+    #[serde(alias = "cognito:username")]
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum TokenUse {
+    Access,
+    Id,
+}
+
+#[derive(Deserialize)]
+struct VerifiedToken {
+    #[serde(flatten)]
+    claims: Claims,
+    token_use: TokenUse,
+    /// Set on access tokens
+    client_id: Option<String>,
+    /// Set on ID tokens
+    aud: Option<String>,
+}
+
+/// Verifies tokens issued by the user pool to its app client
+pub struct TokenVerifier {
+    keys: KeyStore,
+    issuer: String,
+    client_id: String,
+}
+
+impl TokenVerifier {
+    pub fn new(keys: KeyStore, issuer: String, client_id: String) -> Self {
+        Self {
+            keys,
+            issuer,
+            client_id,
+        }
+    }
+
+    fn from_environment() -> Self {
+        // User pool ids are prefixed with the region of the pool
+        let (region, _) = USER_POOL_ID
+            .split_once('_')
+            .expect("USER_POOL_ID must be a user pool id");
+        let issuer = format!(
+            "https://cognito-idp.{region}.amazonaws.com/{}",
+            *USER_POOL_ID
+        );
+        let source = match JWKS_FILE.as_ref() {
+            Some(path) => KeySource::File(path.into()),
+            None => KeySource::Url(format!("{issuer}/.well-known/jwks.json")),
+        };
+        Self::new(KeyStore::new(source), issuer, USER_POOL_CLIENT_ID.clone())
+    }
+
+    pub async fn verify(&self, token: &str) -> Result<Claims, AuthError> {
+        let header = decode_header(token).map_err(|_| AuthError::InvalidToken)?;
+        let kid = header.kid.ok_or(AuthError::InvalidToken)?;
+        let key = self
+            .keys
+            .key(&kid)
+            .await
+            .map_err(|_| AuthError::KeysUnavailable)?
+            .ok_or(AuthError::InvalidToken)?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[&self.issuer]);
+        validation.set_required_spec_claims(&["exp", "iss"]);
+        // Access tokens have no audience, so the client is checked below for both token types
+        validation.validate_aud = false;
+        let token = decode::<VerifiedToken>(token, &key, &validation)
+            .map_err(|_| AuthError::InvalidToken)?
+            .claims;
+
+        let client_id = match token.token_use {
+            TokenUse::Access => token.client_id,
+            TokenUse::Id => token.aud,
+        };
+        if client_id.as_deref() != Some(self.client_id.as_str()) {
+            return Err(AuthError::InvalidToken);
+        }
+
+        Ok(token.claims)
+    }
+}
+
+impl<S> FromRequestParts<S> for Claims
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        // Extract the token from the authorization header
+        let TypedHeader(Authorization(bearer)) = parts
+            .extract::<TypedHeader<Authorization<Bearer>>>()
+            .await
+            .map_err(|_| AuthError::InvalidToken)?;
+        // Verify and decode the user data
+        VERIFIER.verify(bearer.token()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde_json::{json, Value};
+
+    use crate::test_fixtures::{sign_test_token, test_key_set_path, TEST_CLIENT_ID, TEST_ISSUER};
+
+    use super::{
+        keys::{KeySource, KeyStore},
+        AuthError, TokenVerifier,
+    };
+
+    fn verifier() -> TokenVerifier {
+        TokenVerifier::new(
+            KeyStore::new(KeySource::File(test_key_set_path())),
+            TEST_ISSUER.to_owned(),
+            TEST_CLIENT_ID.to_owned(),
+        )
+    }
+
+    fn access_token_claims() -> Value {
+        json!({
+            "iss": TEST_ISSUER,
+            "exp": time::OffsetDateTime::now_utc().unix_timestamp() + 3600,
+            "token_use": "access",
+            "client_id": TEST_CLIENT_ID,
+            "username": "creator",
+            "cognito:groups": ["ContentCreators"],
+        })
+    }
+
+    #[tokio::test]
+    async fn test_verifies_access_token() {
+        let claims = verifier()
+            .verify(&sign_test_token(&access_token_claims()))
+            .await
+            .expect("Token is valid");
+        assert_eq!(claims.username, "creator");
+        assert_eq!(claims.groups, vec!["ContentCreators"]);
+    }
+
+    #[tokio::test]
+    async fn test_verifies_id_token() {
+        let token = sign_test_token(&json!({
+            "iss": TEST_ISSUER,
+            "exp": time::OffsetDateTime::now_utc().unix_timestamp() + 3600,
+            "token_use": "id",
+            "aud": TEST_CLIENT_ID,
+            "cognito:username": "creator",
+        }));
+        let claims = verifier().verify(&token).await.expect("Token is valid");
+        assert_eq!(claims.username, "creator");
+        assert!(claims.groups.is_empty());
+    }
+
+    #[rstest]
+    #[case::wrong_issuer("iss", json!("https://cognito-idp.eu-north-1.amazonaws.com/other"))]
+    #[case::wrong_client("client_id", json!("other-client"))]
+    #[case::expired("exp", json!(time::OffsetDateTime::now_utc().unix_timestamp() - 3600))]
+    #[case::unknown_token_use("token_use", json!("refresh"))]
+    #[tokio::test]
+    async fn test_rejects_invalid_claims(#[case] claim: &str, #[case] value: Value) {
+        let mut claims = access_token_claims();
+        claims[claim] = value;
+        let result = verifier().verify(&sign_test_token(&claims)).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unsigned_token() {
+        let token = sign_test_token(&access_token_claims());
+        let (unsigned, _) = token.rsplit_once('.').unwrap();
+        let result = verifier().verify(&format!("{unsigned}.")).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_symmetric_algorithm() {
+        let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+        header.kid = Some("test-key".to_owned());
+        let token = jsonwebtoken::encode(
+            &header,
+            &access_token_claims(),
+            &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+        )
+        .unwrap();
+        let result = verifier().verify(&token).await;
+        assert!(matches!(result, Err(AuthError::InvalidToken)));
+    }
+}
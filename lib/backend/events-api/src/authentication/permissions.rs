@@ -0,0 +1,199 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::LazyLock,
+};
+
+use axum::{extract::Request, middleware::Next, response::Response};
+use serde::{Deserialize, Serialize};
+
+use crate::configuration::{CONTENT_CREATORS_GROUP_NAME, GROUP_PERMISSIONS};
+
+use super::{AuthError, Claims};
+
+static PERMISSION_MAP: LazyLock<PermissionMap> = LazyLock::new(PermissionMap::from_environment);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Permission {
+    CreateEvents,
+    /// Manage any event, regardless of its organizers
+    ModerateEvents,
+    ViewAuditLogs,
+    ManageUsers,
+}
+
+impl Permission {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Permission::CreateEvents => "createEvents",
+            Permission::ModerateEvents => "moderateEvents",
+            Permission::ViewAuditLogs => "viewAuditLogs",
+            Permission::ManageUsers => "manageUsers",
+        }
+    }
+}
+
+/// Permissions granted by each Cognito group
+#[derive(Debug, Deserialize)]
+pub struct PermissionMap(HashMap<String, HashSet<Permission>>);
+
+impl PermissionMap {
+    /// Without a configured mapping, content creators may create events, as before groups were
+    /// mapped to permissions
+    fn from_environment() -> Self {
+        match GROUP_PERMISSIONS.as_deref() {
+            Some(mapping) => serde_json::from_str(mapping)
+                .expect("GROUP_PERMISSIONS must map group names to lists of permissions"),
+            None => Self(HashMap::from([(
+                CONTENT_CREATORS_GROUP_NAME.clone(),
+                HashSet::from([Permission::CreateEvents]),
+            )])),
+        }
+    }
+
+    pub fn permissions(&self, groups: &[String]) -> HashSet<Permission> {
+        groups
+            .iter()
+            .filter_map(|group| self.0.get(group))
+            .flatten()
+            .copied()
+            .collect()
+    }
+}
+
+impl Claims {
+    pub fn permissions(&self) -> HashSet<Permission> {
+        PERMISSION_MAP.permissions(&self.groups)
+    }
+
+    pub fn has_permission(&self, permission: Permission) -> bool {
+        self.permissions().contains(&permission)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum PermissionError {
+    #[error(transparent)]
+    Unauthenticated(#[from] AuthError),
+    /// The first of the permissions which would have been enough
+    #[error("Missing the permission {0:?}")]
+    Missing(Permission),
+}
+
+/// Marks what a group of routes requires, through [`require_permission`]
+pub trait RequiredPermission {
+    /// Any one of these is enough
+    const ANY_OF: &'static [Permission];
+
+    fn allows(permissions: &HashSet<Permission>) -> bool {
+        Self::ANY_OF
+            .iter()
+            .any(|permission| permissions.contains(permission))
+    }
+
+    fn check(claims: &Claims) -> Result<(), PermissionError> {
+        if Self::allows(&claims.permissions()) {
+            return Ok(());
+        }
+        tracing::debug!(
+            "{} lacks the permissions {:?}. Rejecting request.",
+            claims.username,
+            Self::ANY_OF
+        );
+        Err(PermissionError::Missing(Self::ANY_OF[0]))
+    }
+}
+
+pub struct CanCreateEvents;
+impl RequiredPermission for CanCreateEvents {
+    const ANY_OF: &'static [Permission] = &[Permission::CreateEvents];
+}
+
+/// Organizing events they were added to, or moderating any event. Which events are
+/// accessible is checked against the organizers of each event.
+pub struct CanOrganizeEvents;
+impl RequiredPermission for CanOrganizeEvents {
+    const ANY_OF: &'static [Permission] = &[Permission::CreateEvents, Permission::ModerateEvents];
+}
+
+pub struct CanViewAuditLogs;
+impl RequiredPermission for CanViewAuditLogs {
+    const ANY_OF: &'static [Permission] = &[Permission::ViewAuditLogs];
+}
+
+pub struct CanManageUsers;
+impl RequiredPermission for CanManageUsers {
+    const ANY_OF: &'static [Permission] = &[Permission::ManageUsers];
+}
+
+/// Rejects requests to a group of routes from users without the permission `P`, e.g.
+/// `route_layer(middleware::from_fn(require_permission::<CanManageUsers>))`
+pub async fn require_permission<P: RequiredPermission>(
+    claims: Claims,
+    req: Request,
+    next: Next,
+) -> Result<Response, PermissionError> {
+    P::check(&claims)?;
+    Ok(next.run(req).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use rstest::rstest;
+
+    use super::{CanManageUsers, CanOrganizeEvents, Permission, PermissionMap, RequiredPermission};
+
+    fn permission_map() -> PermissionMap {
+        serde_json::from_str(
+            r#"{
+                "ContentCreators": ["createEvents"],
+                "SiteAdmins": ["createEvents", "moderateEvents", "viewAuditLogs", "manageUsers"]
+            }"#,
+        )
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case(&[], &[])]
+    #[case(&["Unknown"], &[])]
+    #[case(&["ContentCreators"], &[Permission::CreateEvents])]
+    #[case(
+        &["ContentCreators", "SiteAdmins"],
+        &[
+            Permission::CreateEvents,
+            Permission::ModerateEvents,
+            Permission::ViewAuditLogs,
+            Permission::ManageUsers,
+        ]
+    )]
+    fn test_permissions_of_groups(#[case] groups: &[&str], #[case] expected: &[Permission]) {
+        let groups: Vec<String> = groups.iter().map(|group| group.to_string()).collect();
+        assert_eq!(
+            permission_map().permissions(&groups),
+            expected.iter().copied().collect::<HashSet<_>>()
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_permission() {
+        let result = serde_json::from_str::<PermissionMap>(r#"{"Group": ["fly"]}"#);
+        assert!(result.is_err());
+    }
+
+    #[rstest]
+    #[case(&[], false, false)]
+    #[case(&[Permission::CreateEvents], true, false)]
+    #[case(&[Permission::ModerateEvents], true, false)]
+    #[case(&[Permission::ViewAuditLogs, Permission::ManageUsers], false, true)]
+    fn test_required_permissions(
+        #[case] granted: &[Permission],
+        #[case] organize: bool,
+        #[case] manage_users: bool,
+    ) {
+        let permissions: HashSet<Permission> = granted.iter().copied().collect();
+        assert_eq!(CanOrganizeEvents::allows(&permissions), organize);
+        assert_eq!(CanManageUsers::allows(&permissions), manage_users);
+    }
+}
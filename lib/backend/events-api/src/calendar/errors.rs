@@ -0,0 +1,5 @@
+#[derive(thiserror::Error, Debug)]
+pub enum CalendarError {
+    #[error("Calendar token is invalid")]
+    InvalidToken,
+}
@@ -0,0 +1,558 @@
+//! Writes events as iCalendar (RFC 5545) data
+
+use std::collections::BTreeMap;
+
+use time_tz::{Offset, OffsetDateTimeExt, TimeZone, Tz};
+
+use crate::{
+    events::models::Event,
+    localization::{localized, LanguagePreference},
+    signups::models::SignupStatus,
+};
+
+pub const CONTENT_TYPE: &str = "text/calendar; charset=utf-8";
+
+const PRODUCT_ID: &str = "-//events-signup//events-api//EN";
+/// Makes UIDs globally unique, as calendar clients expect
+const UID_DOMAIN: &str = "events-signup";
+/// Longest line in octets, not counting the line break
+const MAX_LINE_LENGTH: usize = 75;
+
+/// Escapes a TEXT value. Control characters other than tabs are not allowed in values, so line
+/// breaks are escaped and the rest are dropped.
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push(c),
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// The link as a URI for an ALTREP parameter, if it is a web link. Parsing percent-encodes
+/// quotes and spaces, which cannot be part of a parameter value.
+fn link_uri(link: &str) -> Option<String> {
+    url::Url::parse(link.trim())
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .map(String::from)
+}
+
+/// The LANGUAGE parameter for texts in `language`. Languages are stored as given, so those which
+/// are not language tags are left out.
+fn language_param(language: &str) -> String {
+    let is_tag = !language.is_empty()
+        && language
+            .split('-')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric()));
+    if is_tag {
+        format!(";LANGUAGE={language}")
+    } else {
+        String::new()
+    }
+}
+
+fn format_datetime(date: time::OffsetDateTime) -> String {
+    let date = date.to_offset(time::UtcOffset::UTC);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        date.year(),
+        date.month() as u8,
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+/// A date and time as read on a clock in the time zone given alongside it
+fn format_local_datetime(date: time::OffsetDateTime) -> String {
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day(),
+        date.hour(),
+        date.minute(),
+        date.second()
+    )
+}
+
+fn format_offset(offset: time::UtcOffset) -> String {
+    let (hours, minutes, seconds) = offset.as_hms();
+    let sign = if offset.is_negative() { '-' } else { '+' };
+    let (hours, minutes, seconds) = (hours.abs(), minutes.abs(), seconds.abs());
+    if seconds == 0 {
+        format!("{sign}{hours:02}{minutes:02}")
+    } else {
+        format!("{sign}{hours:02}{minutes:02}{seconds:02}")
+    }
+}
+
+fn format_date(date: time::Date) -> String {
+    format!(
+        "{:04}{:02}{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+}
+
+/// Days an all-day event spans, in its time zone. The end is exclusive, as iCalendar expects.
+fn all_day_range(event: &Event) -> (time::Date, time::Date) {
+    let local_date = |date: time::OffsetDateTime| match event.tz() {
+        Some(time_zone) => date.to_timezone(time_zone).date(),
+        None => date.date(),
+    };
+    let start = local_date(event.event_date);
+    let last = event.end_date.map_or(start, local_date).max(start);
+    (start, last.next_day().unwrap_or(last))
+}
+
+/// A period during which a time zone keeps the same offset from UTC
+struct Observance {
+    start: time::OffsetDateTime,
+    offset_from: time::UtcOffset,
+    offset_to: time::UtcOffset,
+    name: String,
+    is_dst: bool,
+}
+
+/// The offsets `time_zone` has between `from` and `to`, starting with the one in effect at `from`
+fn observances(
+    time_zone: &Tz,
+    from: time::OffsetDateTime,
+    to: time::OffsetDateTime,
+) -> Vec<Observance> {
+    let offset_at = |timestamp: i64| {
+        time_zone.get_offset_utc(
+            &time::OffsetDateTime::from_unix_timestamp(timestamp)
+                .expect("Timestamps come from valid dates"),
+        )
+    };
+    let (from, to) = (from.unix_timestamp(), to.unix_timestamp());
+
+    let first = offset_at(from);
+    let mut observances = vec![Observance {
+        start: time::OffsetDateTime::from_unix_timestamp(from)
+            .expect("Timestamps come from valid dates"),
+        offset_from: first.to_utc(),
+        offset_to: first.to_utc(),
+        name: first.name().to_owned(),
+        is_dst: first.is_dst(),
+    }];
+    let mut current = first.to_utc();
+    let mut checked = from;
+    // Offsets change at most once a day, so checking once a day finds every change
+    while checked < to {
+        let next = (checked + 86_400).min(to);
+        if offset_at(next).to_utc() != current {
+            let (mut before, mut after) = (checked, next);
+            while after - before > 1 {
+                let middle = before + (after - before) / 2;
+                if offset_at(middle).to_utc() == current {
+                    before = middle;
+                } else {
+                    after = middle;
+                }
+            }
+            let offset = offset_at(after);
+            observances.push(Observance {
+                start: time::OffsetDateTime::from_unix_timestamp(after)
+                    .expect("Timestamps come from valid dates"),
+                offset_from: current,
+                offset_to: offset.to_utc(),
+                name: offset.name().to_owned(),
+                is_dst: offset.is_dst(),
+            });
+            current = offset.to_utc();
+        }
+        checked = next;
+    }
+    observances
+}
+
+pub struct Calendar {
+    content: String,
+    /// Where the time zone definitions go, after the calendar properties
+    header_end: usize,
+    /// Time zones of timed events, with the earliest and latest time each is used at
+    time_zones: BTreeMap<&'static str, (&'static Tz, time::OffsetDateTime, time::OffsetDateTime)>,
+}
+
+impl Calendar {
+    /// Starts a calendar. Feeds are given a `name`, which clients show for the subscription.
+    pub fn new(name: Option<&str>) -> Self {
+        let mut calendar = Self {
+            content: String::new(),
+            header_end: 0,
+            time_zones: BTreeMap::new(),
+        };
+        calendar.line("BEGIN:VCALENDAR");
+        calendar.line("VERSION:2.0");
+        calendar.line(&format!("PRODID:{PRODUCT_ID}"));
+        calendar.line("CALSCALE:GREGORIAN");
+        calendar.line("METHOD:PUBLISH");
+        if let Some(name) = name {
+            calendar.line(&format!("X-WR-CALNAME:{}", escape_text(name)));
+        }
+        calendar.header_end = calendar.content.len();
+        calendar
+    }
+
+    /// Writes a line, folding it into continuation lines that start with a space when it is too
+    /// long
+    fn line(&mut self, mut line: &str) {
+        let mut max_length = MAX_LINE_LENGTH;
+        while line.len() > max_length {
+            let mut split = max_length;
+            while !line.is_char_boundary(split) {
+                split -= 1;
+            }
+            self.content.push_str(&line[..split]);
+            self.content.push_str("\r\n ");
+            line = &line[split..];
+            // The leading space counts toward the length of continuation lines
+            max_length = MAX_LINE_LENGTH - 1;
+        }
+        self.content.push_str(line);
+        self.content.push_str("\r\n");
+    }
+
+    /// Adds the event. The UID stays the same for the lifetime of the event, and the sequence
+    /// follows its version, so clients replace their copy when the event is changed.
+    /// `signup` is the status of the participant the calendar is for, if any.
+    pub fn add_event(
+        &mut self,
+        event: &Event,
+        preference: &LanguagePreference,
+        signup: Option<SignupStatus>,
+        now: time::OffsetDateTime,
+    ) {
+        let status = match (event.cancelled_at, signup) {
+            (Some(_), _) => "CANCELLED",
+            (None, Some(SignupStatus::Waitlisted)) => "TENTATIVE",
+            (None, _) => "CONFIRMED",
+        };
+        let language = preference.resolve(event.title.keys()).unwrap_or_default();
+        let language_param = language_param(language);
+
+        self.line("BEGIN:VEVENT");
+        self.line(&format!("UID:{}@{UID_DOMAIN}", event.id));
+        self.line(&format!("SEQUENCE:{}", event.version));
+        self.line(&format!("DTSTAMP:{}", format_datetime(now)));
+        if event.all_day {
+            let (start, end) = all_day_range(event);
+            self.line(&format!("DTSTART;VALUE=DATE:{}", format_date(start)));
+            self.line(&format!("DTEND;VALUE=DATE:{}", format_date(end)));
+        } else if let Some(time_zone) = event.tz() {
+            // Local times keep the event at the same time on the clock should the rules of the
+            // time zone change
+            let end = event.end_date.unwrap_or(event.event_date);
+            let name = time_zone.name();
+            let (_, first, last) =
+                self.time_zones
+                    .entry(name)
+                    .or_insert((time_zone, event.event_date, end));
+            *first = (*first).min(event.event_date);
+            *last = (*last).max(end);
+            self.line(&format!(
+                "DTSTART;TZID={name}:{}",
+                format_local_datetime(event.event_date.to_timezone(time_zone))
+            ));
+            if let Some(end_date) = event.end_date {
+                self.line(&format!(
+                    "DTEND;TZID={name}:{}",
+                    format_local_datetime(end_date.to_timezone(time_zone))
+                ));
+            }
+        } else {
+            self.line(&format!("DTSTART:{}", format_datetime(event.event_date)));
+            if let Some(end_date) = event.end_date {
+                self.line(&format!("DTEND:{}", format_datetime(end_date)));
+            }
+        }
+        self.line(&format!(
+            "SUMMARY{language_param}:{}",
+            escape_text(&localized(&event.title, language))
+        ));
+        self.line(&format!(
+            "DESCRIPTION{language_param}:{}",
+            escape_text(&localized(&event.description, language))
+        ));
+        let location = escape_text(&event.location_name);
+        match link_uri(&event.location_link) {
+            Some(uri) => self.line(&format!("LOCATION;ALTREP=\"{uri}\":{location}")),
+            None => self.line(&format!("LOCATION:{location}")),
+        }
+        self.line(&format!("STATUS:{status}"));
+        self.line("END:VEVENT");
+    }
+
+    /// Defines the time zones the events use. Clients which know the time zones by name use
+    /// their own rules, so the definitions only cover the dates of the events.
+    fn add_time_zones(&mut self) {
+        for (name, (time_zone, first, last)) in std::mem::take(&mut self.time_zones) {
+            self.line("BEGIN:VTIMEZONE");
+            self.line(&format!("TZID:{name}"));
+            for observance in observances(time_zone, first, last) {
+                let kind = if observance.is_dst {
+                    "DAYLIGHT"
+                } else {
+                    "STANDARD"
+                };
+                self.line(&format!("BEGIN:{kind}"));
+                // Observances start at the time on the clock before the change
+                self.line(&format!(
+                    "DTSTART:{}",
+                    format_local_datetime(observance.start.to_offset(observance.offset_from))
+                ));
+                self.line(&format!(
+                    "TZOFFSETFROM:{}",
+                    format_offset(observance.offset_from)
+                ));
+                self.line(&format!(
+                    "TZOFFSETTO:{}",
+                    format_offset(observance.offset_to)
+                ));
+                self.line(&format!("TZNAME:{}", escape_text(&observance.name)));
+                self.line(&format!("END:{kind}"));
+            }
+            self.line("END:VTIMEZONE");
+        }
+    }
+
+    pub fn finish(mut self) -> String {
+        // Time zones are known once every event is added, but go before the events
+        let events = self.content.split_off(self.header_end);
+        self.add_time_zones();
+        self.content.push_str(&events);
+        self.line("END:VCALENDAR");
+        self.content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::datetime;
+
+    use crate::test_fixtures::event;
+
+    use super::*;
+
+    #[rstest]
+    #[case("plain", "plain")]
+    #[case("a, b; c\\d", "a\\, b\\; c\\\\d")]
+    #[case("one\r\ntwo\nthree", "one\\ntwo\\nthree")]
+    #[case("tab\tbell\u{7}null\u{0}", "tab\tbellnull")]
+    fn test_escape_text(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(escape_text(text), expected);
+    }
+
+    #[rstest]
+    #[case("https://example.com/map", Some("https://example.com/map"))]
+    #[case(" http://example.com/a b\"c ", Some("http://example.com/a%20b%22c"))]
+    #[case("javascript:alert(1)", None)]
+    #[case("mailto:anna@example.com", None)]
+    #[case("", None)]
+    #[case("not a link", None)]
+    fn test_link_uri(#[case] link: &str, #[case] expected: Option<&str>) {
+        assert_eq!(link_uri(link).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case("sv", ";LANGUAGE=sv")]
+    #[case("en-GB", ";LANGUAGE=en-GB")]
+    #[case("", "")]
+    #[case("sv:\"x", "")]
+    #[case("en-", "")]
+    fn test_language_param(#[case] language: &str, #[case] expected: &str) {
+        assert_eq!(language_param(language), expected);
+    }
+
+    #[test]
+    fn test_folds_long_lines() {
+        let mut calendar = Calendar::new(None);
+        calendar.content.clear();
+        let line = format!("SUMMARY:{}", "å".repeat(100));
+        calendar.line(&line);
+
+        let lines: Vec<&str> = calendar
+            .content
+            .trim_end_matches("\r\n")
+            .split("\r\n")
+            .collect();
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_LENGTH));
+        assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+        let unfolded: String = lines
+            .iter()
+            .enumerate()
+            .map(|(index, line)| if index == 0 { line } else { &line[1..] })
+            .collect();
+        assert_eq!(unfolded, line);
+    }
+
+    #[rstest]
+    fn test_event(mut event: Event) {
+        event.version = 3;
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::new(Some("sv"), None),
+            None,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        let content = calendar.finish();
+
+        assert!(content.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(content.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(content.contains(&format!("\r\nUID:{}@events-signup\r\n", event.id)));
+        assert!(content.contains("\r\nSEQUENCE:3\r\n"));
+        assert!(content.contains("\r\nDTSTAMP:20250101T120000Z\r\n"));
+        assert!(content.contains("\r\nDTSTART;TZID=Europe/Stockholm:20250308T100000\r\n"));
+        assert!(content.contains("\r\nSUMMARY;LANGUAGE=sv:Utflykt\r\n"));
+        assert!(content.contains(&format!("\r\nLOCATION;ALTREP=\"{}\":", event.location_link)));
+        assert!(content.contains("\r\nSTATUS:CONFIRMED\r\n"));
+    }
+
+    #[rstest]
+    fn test_timed_event_end(mut event: Event) {
+        event.end_date = Some(datetime!(2025-03-08 15:30 UTC));
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::default(),
+            None,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        let content = calendar.finish();
+
+        assert!(content.contains("\r\nDTSTART;TZID=Europe/Stockholm:20250308T100000\r\n"));
+        assert!(content.contains("\r\nDTEND;TZID=Europe/Stockholm:20250308T163000\r\n"));
+    }
+
+    #[rstest]
+    fn test_event_without_time_zone(mut event: Event) {
+        event.time_zone = "Unknown/Zone".to_owned();
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::default(),
+            None,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        let content = calendar.finish();
+
+        assert!(content.contains("\r\nDTSTART:20250308T090000Z\r\n"));
+        assert!(!content.contains("BEGIN:VTIMEZONE"));
+    }
+
+    #[rstest]
+    fn test_time_zone_definition(mut event: Event) {
+        // Stockholm moves to summer time on 2025-03-30 at 01:00 UTC
+        event.event_date = datetime!(2025-03-29 09:00 UTC);
+        event.end_date = Some(datetime!(2025-03-31 09:00 UTC));
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::default(),
+            None,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        let content = calendar.finish();
+
+        let definition = concat!(
+            "BEGIN:VTIMEZONE\r\n",
+            "TZID:Europe/Stockholm\r\n",
+            "BEGIN:STANDARD\r\n",
+            "DTSTART:20250329T100000\r\n",
+            "TZOFFSETFROM:+0100\r\n",
+            "TZOFFSETTO:+0100\r\n",
+            "TZNAME:CET\r\n",
+            "END:STANDARD\r\n",
+            "BEGIN:DAYLIGHT\r\n",
+            "DTSTART:20250330T020000\r\n",
+            "TZOFFSETFROM:+0100\r\n",
+            "TZOFFSETTO:+0200\r\n",
+            "TZNAME:CEST\r\n",
+            "END:DAYLIGHT\r\n",
+            "END:VTIMEZONE\r\n",
+        );
+        assert!(content.contains(definition));
+        assert!(content.find("BEGIN:VTIMEZONE") < content.find("BEGIN:VEVENT"));
+        assert!(content.contains("\r\nDTSTART;TZID=Europe/Stockholm:20250329T100000\r\n"));
+        assert!(content.contains("\r\nDTEND;TZID=Europe/Stockholm:20250331T110000\r\n"));
+    }
+
+    #[rstest]
+    #[case(time::UtcOffset::from_hms(1, 0, 0).unwrap(), "+0100")]
+    #[case(time::UtcOffset::from_hms(-3, -30, 0).unwrap(), "-0330")]
+    #[case(time::UtcOffset::UTC, "+0000")]
+    fn test_format_offset(#[case] offset: time::UtcOffset, #[case] expected: &str) {
+        assert_eq!(format_offset(offset), expected);
+    }
+
+    #[rstest]
+    // Midnight in Stockholm is the evening before in UTC
+    #[case(datetime!(2025-03-07 23:00 UTC), None, "20250308", "20250309")]
+    #[case(
+        datetime!(2025-03-07 23:00 UTC),
+        Some(datetime!(2025-03-09 23:00 UTC)),
+        "20250308",
+        "20250311"
+    )]
+    fn test_all_day_event(
+        mut event: Event,
+        #[case] event_date: time::OffsetDateTime,
+        #[case] end_date: Option<time::OffsetDateTime>,
+        #[case] expected_start: &str,
+        #[case] expected_end: &str,
+    ) {
+        event.all_day = true;
+        event.event_date = event_date;
+        event.end_date = end_date;
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::default(),
+            None,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        let content = calendar.finish();
+
+        assert!(content.contains(&format!("\r\nDTSTART;VALUE=DATE:{expected_start}\r\n")));
+        assert!(content.contains(&format!("\r\nDTEND;VALUE=DATE:{expected_end}\r\n")));
+    }
+
+    #[rstest]
+    #[case(None, Some(SignupStatus::Waitlisted), "TENTATIVE")]
+    #[case(Some(datetime!(2025-01-02 12:00 UTC)), Some(SignupStatus::Confirmed), "CANCELLED")]
+    #[case(None, Some(SignupStatus::Confirmed), "CONFIRMED")]
+    fn test_event_status(
+        mut event: Event,
+        #[case] cancelled_at: Option<time::OffsetDateTime>,
+        #[case] signup: Option<SignupStatus>,
+        #[case] expected: &str,
+    ) {
+        event.cancelled_at = cancelled_at;
+        let mut calendar = Calendar::new(None);
+        calendar.add_event(
+            &event,
+            &LanguagePreference::default(),
+            signup,
+            datetime!(2025-01-01 12:00 UTC),
+        );
+        assert!(calendar
+            .finish()
+            .contains(&format!("\r\nSTATUS:{expected}\r\n")));
+    }
+}
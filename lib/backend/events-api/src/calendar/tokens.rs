@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+use crate::signing::{self, InvalidTokenError};
+
+const ORGANIZER_PURPOSE: &str = "organizer-calendar";
+const PARTICIPANT_PURPOSE: &str = "participant-calendar";
+
+/// Gives access to the calendar feed of an organizer. Calendar clients cannot log in, so the
+/// feed is reached through this token instead.
+pub struct OrganizerFeedToken {
+    pub username: String,
+}
+
+impl OrganizerFeedToken {
+    pub fn encode(&self) -> String {
+        signing::sign(ORGANIZER_PURPOSE, self.username.as_bytes())
+    }
+
+    pub fn decode(token: &str) -> Result<Self, InvalidTokenError> {
+        let payload = signing::verify(ORGANIZER_PURPOSE, token)?;
+        Ok(Self {
+            username: String::from_utf8(payload).map_err(|_| InvalidTokenError)?,
+        })
+    }
+}
+
+/// Gives access to the calendar feed of the signups with the calendar secret. The secret is
+/// random and only handed to whoever made those signups, as emails are not verified.
+pub struct ParticipantFeedToken {
+    pub calendar_secret: Uuid,
+}
+
+impl ParticipantFeedToken {
+    pub fn encode(&self) -> String {
+        signing::sign(PARTICIPANT_PURPOSE, self.calendar_secret.as_bytes())
+    }
+
+    pub fn decode(token: &str) -> Result<Self, InvalidTokenError> {
+        let payload = signing::verify(PARTICIPANT_PURPOSE, token)?;
+        Ok(Self {
+            calendar_secret: Uuid::from_slice(&payload).map_err(|_| InvalidTokenError)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signing::{sign_with_key, verify_with_key};
+
+    use super::{ORGANIZER_PURPOSE, PARTICIPANT_PURPOSE};
+
+    #[test]
+    fn test_tokens_are_not_interchangeable() {
+        let token = sign_with_key(PARTICIPANT_PURPOSE, b"anna@example.com", b"key");
+        assert!(verify_with_key(ORGANIZER_PURPOSE, &token, b"key").is_err());
+        assert!(verify_with_key(PARTICIPANT_PURPOSE, &token, b"key").is_ok());
+    }
+}
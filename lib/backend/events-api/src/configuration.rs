@@ -1,5 +1,9 @@
 use std::{env, sync::LazyLock};
 
+use image::imageops::FilterType;
+
+use crate::images::settings::parse_resize_filter;
+
 pub static EVENT_TABLE: LazyLock<String> =
     LazyLock::new(|| env::var("EVENT_TABLE_ARN").expect("EVENT_TABLE must be set"));
 
@@ -11,6 +15,82 @@ pub static EVENT_IMAGES_BUCKET_PREFIX: LazyLock<String> = LazyLock::new(|| {
     env::var("EVENT_IMAGES_BUCKET_PREFIX").expect("EVENT_IMAGES_BUCKET_PREFIX must be set")
 });
 
+/// Where images uploaded straight to the bucket wait to be processed. It must be outside of
+/// `EVENT_IMAGES_BUCKET_PREFIX`, which is served to everyone.
+pub static EVENT_IMAGE_UPLOADS_PREFIX: LazyLock<String> = LazyLock::new(|| {
+    env::var("EVENT_IMAGE_UPLOADS_PREFIX").expect("EVENT_IMAGE_UPLOADS_PREFIX must be set")
+});
+
+/// Which handler the Lambda runs: the API, or `image-processing` for images uploaded to the
+/// bucket
+pub static EVENTS_API_HANDLER: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("EVENTS_API_HANDLER").ok());
+
 pub static CONTENT_CREATORS_GROUP_NAME: LazyLock<String> = LazyLock::new(|| {
     env::var("CONTENT_CREATORS_GROUP_NAME").expect("CONTENT_CREATORS_GROUP_NAME must be set")
 });
+
+/// Secrets Manager secret holding the key participant links are signed with
+pub static TOKEN_SIGNING_SECRET_ARN: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("TOKEN_SIGNING_SECRET_ARN").ok());
+
+/// Signing key to use instead of `TOKEN_SIGNING_SECRET_ARN`, for running locally
+pub static TOKEN_SIGNING_SECRET: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("TOKEN_SIGNING_SECRET").ok());
+
+pub static USER_POOL_ID: LazyLock<String> =
+    LazyLock::new(|| env::var("USER_POOL_ID").expect("USER_POOL_ID must be set"));
+
+pub static USER_POOL_CLIENT_ID: LazyLock<String> =
+    LazyLock::new(|| env::var("USER_POOL_CLIENT_ID").expect("USER_POOL_CLIENT_ID must be set"));
+
+/// Address participants are emailed from. Participants are not notified without one.
+pub static NOTIFICATION_SENDER: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("NOTIFICATION_SENDER").ok());
+
+/// JSON object mapping Cognito group names to the permissions the group grants
+pub static GROUP_PERMISSIONS: LazyLock<Option<String>> =
+    LazyLock::new(|| env::var("GROUP_PERMISSIONS").ok());
+
+/// Reads token signing keys from this JWKS file instead of the user pool
+pub static JWKS_FILE: LazyLock<Option<String>> = LazyLock::new(|| env::var("JWKS_FILE").ok());
+
+/// Languages served, in order, when an event has none of the languages a client asks for.
+/// Comma separated. Events in none of them are served in any language they have.
+pub static FALLBACK_LANGUAGES: LazyLock<Vec<String>> = LazyLock::new(|| {
+    env::var("FALLBACK_LANGUAGES")
+        .unwrap_or_else(|_| "sv,en".to_owned())
+        .split(',')
+        .map(|language| language.trim().to_owned())
+        .filter(|language| !language.is_empty())
+        .collect()
+});
+
+/// Speed of the AVIF encoder, from 1 to 10. Defaults to that of `cavif`, like the quality.
+pub static AVIF_SPEED: LazyLock<u8> = LazyLock::new(|| {
+    env::var("AVIF_SPEED").map_or(4, |speed| {
+        speed
+            .parse()
+            .ok()
+            .filter(|speed| (1..=10).contains(speed))
+            .expect("AVIF_SPEED must be a number from 1 to 10")
+    })
+});
+
+pub static AVIF_QUALITY: LazyLock<u8> = LazyLock::new(|| {
+    env::var("AVIF_QUALITY").map_or(80, |quality| {
+        quality
+            .parse()
+            .ok()
+            .filter(|quality| (1..=100).contains(quality))
+            .expect("AVIF_QUALITY must be a number from 1 to 100")
+    })
+});
+
+pub static IMAGE_RESIZE_FILTER: LazyLock<FilterType> = LazyLock::new(|| {
+    env::var("IMAGE_RESIZE_FILTER").map_or(FilterType::CatmullRom, |filter| {
+        parse_resize_filter(&filter).expect(
+            "IMAGE_RESIZE_FILTER must be nearest, triangle, catmull-rom, gaussian or lanczos3",
+        )
+    })
+});
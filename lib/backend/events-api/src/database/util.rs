@@ -1,7 +1,10 @@
 use std::{collections::HashMap, str::FromStr};
 
-use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
-use serde::de::DeserializeOwned;
+use aws_sdk_dynamodb::{
+    operation::transact_write_items::TransactWriteItemsError,
+    types::{AttributeValue, ScalarAttributeType},
+};
+use serde::{de::DeserializeOwned, Serialize};
 use time::OffsetDateTime;
 
 use super::errors::ModelError;
@@ -24,7 +27,21 @@ pub fn get_boolean(
         .ok_or_else(|| ModelError::MissingField(field.to_owned()))?
         .as_bool()
         .map_err(|_| ModelError::InvalidData(format!("{field} is not a bool")))
-        .map(bool::clone)
+        .copied()
+}
+
+pub fn get_optional_boolean(
+    item: &HashMap<String, AttributeValue>,
+    field: &str,
+) -> Result<Option<bool>, ModelError> {
+    item.get(field)
+        .map(|value| {
+            value
+                .as_bool()
+                .map_err(|_| ModelError::InvalidData(format!("{field} is not a bool")))
+                .copied()
+        })
+        .transpose()
 }
 
 pub fn get_field<T>(item: &HashMap<String, AttributeValue>, field: &str) -> Result<T, ModelError>
@@ -80,6 +97,19 @@ where
     })
 }
 
+pub fn get_optional_nested_object<T>(
+    item: &HashMap<String, AttributeValue>,
+    field: &str,
+) -> Result<Option<T>, ModelError>
+where
+    T: DeserializeOwned,
+{
+    if !item.contains_key(field) {
+        return Ok(None);
+    }
+    get_nested_object(item, field).map(Some)
+}
+
 pub fn get_delimited<T>(
     item: &HashMap<String, AttributeValue>,
     field: &str,
@@ -113,3 +143,51 @@ pub fn get_datetime(
         },
     )
 }
+
+pub fn get_optional_datetime(
+    item: &HashMap<String, AttributeValue>,
+    field: &str,
+) -> Result<Option<OffsetDateTime>, ModelError> {
+    match item.contains_key(field) {
+        true => get_datetime(item, field).map(Some),
+        false => Ok(None),
+    }
+}
+
+/// Dates are stored in UTC, so that they sort chronologically as strings
+pub fn datetime_attribute(
+    field: &str,
+    value: &OffsetDateTime,
+) -> Result<AttributeValue, ModelError> {
+    value
+        .to_offset(time::UtcOffset::UTC)
+        .format(&time::format_description::well_known::Rfc3339)
+        .map(AttributeValue::S)
+        .map_err(|_| {
+            ModelError::InvalidType(field.to_owned(), "Rfc3339".to_owned(), value.to_string())
+        })
+}
+
+pub fn nested_object_attribute<T>(field: &str, value: &T) -> Result<AttributeValue, ModelError>
+where
+    T: Serialize,
+{
+    serde_json::to_string(value)
+        .map(AttributeValue::S)
+        .map_err(|e| {
+            sentry::capture_error(&e);
+            ModelError::InvalidData(format!("{field} could not be serialized"))
+        })
+}
+
+/// Index of the first transaction item whose condition check failed, if that is what
+/// cancelled the transaction
+pub fn failed_transaction_condition(error: Option<&TransactWriteItemsError>) -> Option<usize> {
+    match error {
+        Some(TransactWriteItemsError::TransactionCanceledException(e)) => e
+            .cancellation_reasons()
+            .iter()
+            .position(|reason| reason.code() == Some("ConditionalCheckFailed")),
+        _ => None,
+    }
+}
@@ -1,4 +1,7 @@
-use crate::database::errors::{DatabaseQueryFailed, UnknownSdkError};
+use crate::{
+    database::errors::{DatabaseQueryFailed, ModelError, UnknownSdkError},
+    images::errors::ImageDeletionError,
+};
 
 #[derive(thiserror::Error, Debug)]
 pub enum GetEventError {
@@ -20,4 +23,70 @@ pub enum AddImageError {
     UnexpectedSdkError(#[from] UnknownSdkError),
     #[error(transparent)]
     GetEventError(#[from] GetEventError),
+    #[error(transparent)]
+    InvalidEventItem(#[from] ModelError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum CreateEventError {
+    #[error("Failed to convert event to a database item")]
+    InvalidEventItem(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum EventValidationError {
+    #[error("Event has no title")]
+    MissingTitle,
+    #[error("Event is missing a title in language {0}")]
+    MissingTitleLanguage(String),
+    #[error("Event is missing a description in language {0}")]
+    MissingDescriptionLanguage(String),
+    #[error("Signup deadline is after the event date")]
+    SignupDeadlineAfterEventDate,
+    #[error("Event has no location")]
+    MissingLocation,
+    #[error("Event has no valid contact email")]
+    InvalidEmail,
+    #[error("Participants limit must be greater than zero")]
+    InvalidParticipantsLimit,
+    #[error("Participants limit is below the {0} participants already signed up")]
+    LimitBelowParticipants(u16),
+    #[error("Signup question {0} is invalid")]
+    InvalidQuestion(String),
+    #[error("Event is cancelled")]
+    EventCancelled,
+    #[error("Unknown time zone {0}")]
+    InvalidTimeZone(String),
+    #[error("Event ends before it starts")]
+    EndBeforeStart,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum UpdateEventError {
+    #[error("Event was modified by someone else")]
+    VersionConflict,
+    #[error("Failed to convert event to a database item")]
+    InvalidEventItem(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ListEventsError {
+    #[error("Pagination cursor is invalid")]
+    InvalidCursor,
+    #[error("Failed to read event summary")]
+    InvalidStoredEvent,
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DeleteEventError {
+    #[error(transparent)]
+    ImageDeletionFailed(#[from] ImageDeletionError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
 }
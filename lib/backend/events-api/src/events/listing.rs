@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{
+            get_boolean, get_datetime, get_delimited, get_field, get_nested_object,
+            get_optional_field,
+        },
+    },
+    organizers::models::{Organizer, OrganizerRole},
+    signing::{self, InvalidTokenError},
+};
+
+use super::models::{column_aliases, columns};
+
+const CURSOR_PURPOSE: &str = "events-cursor";
+
+/// The parts of an event projected into the events listing index
+pub struct EventSummary {
+    pub id: Uuid,
+    pub title: HashMap<String, String>,
+    pub event_date: time::OffsetDateTime,
+    pub location_name: String,
+    pub image: Option<Uuid>,
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for EventSummary {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: get_delimited(item, column_aliases::ID)?,
+            title: get_nested_object(item, columns::TITLE_COLUMN)?,
+            event_date: get_datetime(item, columns::EVENT_DATE_COLUMN)?,
+            location_name: get_field(item, columns::LOCATION_NAME_COLUMN)?,
+            image: get_optional_field(item, columns::IMAGE_COLUMN, ScalarAttributeType::S)?,
+        })
+    }
+}
+
+/// The parts of an event projected into the events by creator index
+pub struct CreatorEventSummary {
+    pub id: Uuid,
+    pub title: HashMap<String, String>,
+    pub event_date: time::OffsetDateTime,
+    pub location_name: String,
+    pub event_visible: bool,
+    pub participants_count: u16,
+    pub waitlist_count: u16,
+    pub role: OrganizerRole,
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for CreatorEventSummary {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: get_delimited(item, column_aliases::ID)?,
+            title: get_nested_object(item, columns::TITLE_COLUMN)?,
+            event_date: get_datetime(item, columns::EVENT_DATE_COLUMN)?,
+            location_name: get_field(item, columns::LOCATION_NAME_COLUMN)?,
+            event_visible: get_boolean(item, columns::EVENT_VISIBLE_COLUMN)?,
+            participants_count: get_optional_field(
+                item,
+                columns::PARTICIPANTS_COUNT_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .unwrap_or(0),
+            waitlist_count: get_optional_field(
+                item,
+                columns::WAITLIST_COUNT_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .unwrap_or(0),
+            // Event items are only found under their creator, who owns the event
+            role: OrganizerRole::Owner,
+        })
+    }
+}
+
+/// An item of the events by creator index. Co-organized events are found through organizer
+/// items, which only point to the event.
+pub enum CreatorListingEntry {
+    Owned(CreatorEventSummary),
+    CoOrganized(Uuid, OrganizerRole),
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for CreatorListingEntry {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        let is_organizer = item
+            .get(columns::SORTING_KEY_COLUMN)
+            .and_then(|sort_key| sort_key.as_s().ok())
+            .is_some_and(|sort_key| sort_key.starts_with(Organizer::SORT_KEY_PREFIX));
+        if is_organizer {
+            let organizer = Organizer::try_from(item)?;
+            return Ok(Self::CoOrganized(organizer.event_id, organizer.role));
+        }
+        CreatorEventSummary::try_from(item).map(Self::Owned)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum EventPeriod {
+    Upcoming,
+    Past,
+}
+
+pub struct CreatorEventsFilter {
+    pub period: Option<EventPeriod>,
+    pub visible: Option<bool>,
+    /// Boundary between upcoming and past events
+    pub now: time::OffsetDateTime,
+}
+
+impl CreatorEventsFilter {
+    pub fn matches(&self, event: &CreatorEventSummary) -> bool {
+        let period_matches = match self.period {
+            Some(EventPeriod::Upcoming) => event.event_date >= self.now,
+            Some(EventPeriod::Past) => event.event_date < self.now,
+            None => true,
+        };
+        period_matches
+            && self
+                .visible
+                .is_none_or(|visible| event.event_visible == visible)
+    }
+}
+
+/// Opaque position in the events listing, wrapping the `LastEvaluatedKey` of a page.
+/// Cursors are signed, so clients cannot make the query start at arbitrary keys.
+pub struct ListingCursor(pub HashMap<String, AttributeValue>);
+
+impl ListingCursor {
+    /// Every key attribute of the listing index is a string, so keys with anything else are
+    /// not from the listing and yield no cursor
+    fn payload(&self) -> Option<Vec<u8>> {
+        let key: HashMap<&String, &String> = self
+            .0
+            .iter()
+            .map(|(name, value)| value.as_s().ok().map(|value| (name, value)))
+            .collect::<Option<_>>()?;
+        serde_json::to_vec(&key).ok()
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, InvalidTokenError> {
+        let key: HashMap<String, String> =
+            serde_json::from_slice(payload).map_err(|_| InvalidTokenError)?;
+        Ok(Self(
+            key.into_iter()
+                .map(|(name, value)| (name, AttributeValue::S(value)))
+                .collect(),
+        ))
+    }
+
+    pub fn encode(&self) -> Option<String> {
+        self.payload()
+            .map(|payload| signing::sign(CURSOR_PURPOSE, &payload))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, InvalidTokenError> {
+        Self::from_payload(&signing::verify(CURSOR_PURPOSE, cursor)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+
+    use crate::signing::{sign_with_key, verify_with_key};
+
+    use super::{ListingCursor, CURSOR_PURPOSE};
+
+    #[test]
+    fn test_cursor_payload_roundtrip() {
+        let key = HashMap::from([
+            ("PK".to_owned(), AttributeValue::S("event".to_owned())),
+            ("SK".to_owned(), AttributeValue::S("Event".to_owned())),
+            (
+                "EventDate".to_owned(),
+                AttributeValue::S("2025-03-08T09:00:00Z".to_owned()),
+            ),
+        ]);
+        let cursor = ListingCursor(key.clone());
+        let token = sign_with_key(CURSOR_PURPOSE, &cursor.payload().unwrap(), b"key");
+        let payload = verify_with_key(CURSOR_PURPOSE, &token, b"key").expect("Cursor is valid");
+        let decoded = ListingCursor::from_payload(&payload).expect("Payload is valid");
+        assert_eq!(decoded.0, key);
+    }
+
+    #[test]
+    fn test_cursor_rejects_non_string_keys() {
+        let cursor = ListingCursor(HashMap::from([(
+            "PK".to_owned(),
+            AttributeValue::N("1".to_owned()),
+        )]));
+        assert!(cursor.payload().is_none());
+    }
+}
@@ -1,3 +1,7 @@
 pub mod errors;
+pub mod listing;
 pub mod models;
+pub mod preview;
 pub mod queries;
+pub mod questions;
+pub mod validation;
@@ -1,15 +1,22 @@
 use std::collections::HashMap;
 
 use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use time_tz::{timezones, OffsetDateTimeExt, PrimitiveDateTimeExt, Tz};
 use uuid::Uuid;
 
 use crate::database::{
     errors::ModelError,
     util::{
-        get_boolean, get_datetime, get_delimited, get_field, get_nested_object, get_optional_field,
+        datetime_attribute, get_boolean, get_datetime, get_delimited, get_field, get_nested_object,
+        get_optional_boolean, get_optional_datetime, get_optional_field,
+        get_optional_nested_object, nested_object_attribute,
     },
 };
 
+use crate::images::{framing::FocalPoint, variants::ImageVariant};
+
+use super::questions::Question;
+
 pub mod columns {
     include!(concat!(env!("OUT_DIR"), "/db_structure.rs"));
 }
@@ -20,6 +27,9 @@ pub mod column_aliases {
     pub const ID: &str = columns::PARTITION_KEY_COLUMN;
 }
 
+/// Time zone of events created before events had one
+pub const DEFAULT_TIME_ZONE: &str = "Europe/Stockholm";
+
 pub struct Event {
     pub id: Uuid,
     pub title: HashMap<String, String>,
@@ -29,6 +39,10 @@ pub struct Event {
     pub description: HashMap<String, String>,
     pub limit: Option<u16>,
     pub image: Option<Uuid>,
+    /// Sizes and formats `image` is stored in. Empty for images stored before variants were.
+    pub image_variants: Vec<ImageVariant>,
+    /// Where the subject of `image` is, if the uploader said
+    pub image_focal_point: Option<FocalPoint>,
     pub event_visible: bool,
     pub phone: Option<String>,
     pub email: String,
@@ -36,10 +50,57 @@ pub struct Event {
     pub organizer_name: Option<String>,
     pub location_name: String,
     pub location_link: String,
+    pub version: u64,
+    pub participants_count: u16,
+    pub waitlist_count: u16,
+    pub questions: Vec<Question>,
+    pub cancelled_at: Option<time::OffsetDateTime>,
+    /// When the event ends. All-day events end on the day of this date, in their time zone.
+    pub end_date: Option<time::OffsetDateTime>,
+    /// Whether the event lasts whole days rather than starting and ending at given times
+    pub all_day: bool,
+    /// IANA name of the time zone the event takes place in
+    pub time_zone: String,
+}
+
+/// Start of the day `date` falls on in `time_zone`
+fn start_of_day(date: time::OffsetDateTime, time_zone: &Tz) -> time::OffsetDateTime {
+    let local = date.to_timezone(time_zone);
+    let midnight = local.date().midnight();
+    // Midnight is skipped in zones that change to daylight saving time at midnight, in which
+    // case the day starts as the clocks are set forward, at the offset before the change
+    midnight
+        .assume_timezone(time_zone)
+        .take_first()
+        .unwrap_or_else(|| midnight.assume_offset(local.offset()))
 }
 
 impl Event {
     pub const SORT_KEY_VALUE: &str = "Event";
+
+    pub fn spots_remaining(&self) -> Option<u16> {
+        self.limit
+            .map(|limit| limit.saturating_sub(self.participants_count))
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.spots_remaining() == Some(0)
+    }
+
+    /// The time zone of the event, if it is a known IANA time zone
+    pub fn tz(&self) -> Option<&'static Tz> {
+        timezones::get_by_name(&self.time_zone)
+    }
+
+    /// Moves the dates of all-day events to the start of their days in the time zone of the
+    /// event, so deadlines are compared against when the day starts where the event is held
+    pub fn align_all_day_dates(&mut self) {
+        let Some(time_zone) = self.tz().filter(|_| self.all_day) else {
+            return;
+        };
+        self.event_date = start_of_day(self.event_date, time_zone);
+        self.end_date = self.end_date.map(|date| start_of_day(date, time_zone));
+    }
 }
 
 impl TryFrom<&HashMap<String, AttributeValue>> for Event {
@@ -65,8 +126,187 @@ impl TryFrom<&HashMap<String, AttributeValue>> for Event {
             location_name: get_field(item, columns::LOCATION_NAME_COLUMN)?,
             location_link: get_field(item, columns::LOCATION_LINK_COLUMN)?,
             image: get_optional_field(item, columns::IMAGE_COLUMN, ScalarAttributeType::S)?,
+            image_variants: get_optional_nested_object(item, columns::IMAGE_VARIANTS_COLUMN)?
+                .unwrap_or_default(),
+            image_focal_point: get_optional_nested_object(item, columns::FOCAL_POINT_COLUMN)?,
             event_visible: get_boolean(item, columns::EVENT_VISIBLE_COLUMN)?,
             organizer_name: get_optional_field(item, columns::NAME_COLUMN, ScalarAttributeType::S)?,
+            // Events created before versioning was introduced have no version
+            version: get_optional_field(item, columns::VERSION_COLUMN, ScalarAttributeType::N)?
+                .unwrap_or(0),
+            participants_count: get_optional_field(
+                item,
+                columns::PARTICIPANTS_COUNT_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .unwrap_or(0),
+            waitlist_count: get_optional_field(
+                item,
+                columns::WAITLIST_COUNT_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .unwrap_or(0),
+            questions: get_optional_nested_object(item, columns::EXTRA_INFORMATION_COLUMN)?
+                .unwrap_or_default(),
+            cancelled_at: get_optional_datetime(item, columns::CANCELLED_AT_COLUMN)?,
+            end_date: get_optional_datetime(item, columns::END_DATE_COLUMN)?,
+            all_day: get_optional_boolean(item, columns::ALL_DAY_COLUMN)?.unwrap_or(false),
+            time_zone: get_optional_field(item, columns::TIME_ZONE_COLUMN, ScalarAttributeType::S)?
+                .unwrap_or_else(|| DEFAULT_TIME_ZONE.to_owned()),
         })
     }
 }
+
+/// The participant and waitlist counts are left out, since they are only ever changed by signups.
+/// The cancellation date is left out too, since it is only set by cancelling the event.
+impl TryFrom<&Event> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(event: &Event) -> Result<Self, Self::Error> {
+        let mut item = HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(event.id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()),
+            ),
+            (
+                columns::SIGNUP_DEADLINE_COLUMN.to_owned(),
+                datetime_attribute(columns::SIGNUP_DEADLINE_COLUMN, &event.signup_end_date)?,
+            ),
+            (
+                columns::EVENT_DATE_COLUMN.to_owned(),
+                datetime_attribute(columns::EVENT_DATE_COLUMN, &event.event_date)?,
+            ),
+            (
+                columns::CREATOR_COLUMN.to_owned(),
+                AttributeValue::S(event.creator_username.clone()),
+            ),
+            (
+                columns::DESCRIPTION_COLUMN.to_owned(),
+                nested_object_attribute(columns::DESCRIPTION_COLUMN, &event.description)?,
+            ),
+            (
+                columns::TITLE_COLUMN.to_owned(),
+                nested_object_attribute(columns::TITLE_COLUMN, &event.title)?,
+            ),
+            (
+                columns::EMAIL_COLUMN.to_owned(),
+                AttributeValue::S(event.email.clone()),
+            ),
+            (
+                columns::EMAIL_VISIBLE_COLUMN.to_owned(),
+                AttributeValue::Bool(event.email_visible),
+            ),
+            (
+                columns::LOCATION_NAME_COLUMN.to_owned(),
+                AttributeValue::S(event.location_name.clone()),
+            ),
+            (
+                columns::LOCATION_LINK_COLUMN.to_owned(),
+                AttributeValue::S(event.location_link.clone()),
+            ),
+            (
+                columns::EVENT_VISIBLE_COLUMN.to_owned(),
+                AttributeValue::Bool(event.event_visible),
+            ),
+            (
+                columns::VERSION_COLUMN.to_owned(),
+                AttributeValue::N(event.version.to_string()),
+            ),
+            (
+                columns::EXTRA_INFORMATION_COLUMN.to_owned(),
+                nested_object_attribute(columns::EXTRA_INFORMATION_COLUMN, &event.questions)?,
+            ),
+            (
+                columns::ALL_DAY_COLUMN.to_owned(),
+                AttributeValue::Bool(event.all_day),
+            ),
+            (
+                columns::TIME_ZONE_COLUMN.to_owned(),
+                AttributeValue::S(event.time_zone.clone()),
+            ),
+        ]);
+
+        if let Some(limit) = event.limit {
+            item.insert(
+                columns::PARTICIPANTS_LIMIT_COLUMN.to_owned(),
+                AttributeValue::N(limit.to_string()),
+            );
+        }
+        if let Some(phone) = &event.phone {
+            item.insert(
+                columns::PHONE_COLUMN.to_owned(),
+                AttributeValue::S(phone.clone()),
+            );
+        }
+        if let Some(image) = event.image {
+            item.insert(
+                columns::IMAGE_COLUMN.to_owned(),
+                AttributeValue::S(image.to_string()),
+            );
+            item.insert(
+                columns::IMAGE_VARIANTS_COLUMN.to_owned(),
+                nested_object_attribute(columns::IMAGE_VARIANTS_COLUMN, &event.image_variants)?,
+            );
+            if let Some(focal_point) = &event.image_focal_point {
+                item.insert(
+                    columns::FOCAL_POINT_COLUMN.to_owned(),
+                    nested_object_attribute(columns::FOCAL_POINT_COLUMN, focal_point)?,
+                );
+            }
+        }
+        if let Some(end_date) = &event.end_date {
+            item.insert(
+                columns::END_DATE_COLUMN.to_owned(),
+                datetime_attribute(columns::END_DATE_COLUMN, end_date)?,
+            );
+        }
+        if let Some(name) = &event.organizer_name {
+            item.insert(
+                columns::NAME_COLUMN.to_owned(),
+                AttributeValue::S(name.clone()),
+            );
+        }
+
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::datetime;
+
+    use crate::test_fixtures::event;
+
+    use super::Event;
+
+    #[rstest]
+    // Winter time, UTC+1
+    #[case(datetime!(2025-03-08 09:00 UTC), datetime!(2025-03-07 23:00 UTC))]
+    // Summer time, UTC+2
+    #[case(datetime!(2025-06-08 09:00 UTC), datetime!(2025-06-07 22:00 UTC))]
+    // Already in the next day in Stockholm
+    #[case(datetime!(2025-03-08 23:30 UTC), datetime!(2025-03-08 23:00 UTC))]
+    fn test_align_all_day_dates(
+        mut event: Event,
+        #[case] event_date: time::OffsetDateTime,
+        #[case] expected: time::OffsetDateTime,
+    ) {
+        event.all_day = true;
+        event.event_date = event_date;
+        event.end_date = Some(event_date);
+        event.align_all_day_dates();
+        assert_eq!(event.event_date, expected);
+        assert_eq!(event.end_date, Some(expected));
+    }
+
+    #[rstest]
+    fn test_timed_dates_are_not_aligned(mut event: Event) {
+        let event_date = event.event_date;
+        event.align_all_day_dates();
+        assert_eq!(event.event_date, event_date);
+    }
+}
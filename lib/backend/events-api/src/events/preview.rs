@@ -0,0 +1,81 @@
+use uuid::Uuid;
+
+use crate::signing::{self, InvalidTokenError};
+
+const PURPOSE: &str = "event-preview";
+
+/// Lets whoever holds it see an event before it is made visible, until the token expires
+pub struct PreviewToken {
+    pub event_id: Uuid,
+    pub expires_at: time::OffsetDateTime,
+}
+
+impl PreviewToken {
+    fn payload(&self) -> Vec<u8> {
+        [
+            self.event_id.as_bytes().as_slice(),
+            &self.expires_at.unix_timestamp().to_be_bytes(),
+        ]
+        .concat()
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, InvalidTokenError> {
+        if payload.len() != 24 {
+            return Err(InvalidTokenError);
+        }
+        let (event_id, expires_at) = payload.split_at(16);
+        let expires_at = i64::from_be_bytes(expires_at.try_into().map_err(|_| InvalidTokenError)?);
+        Ok(Self {
+            event_id: Uuid::from_slice(event_id).map_err(|_| InvalidTokenError)?,
+            expires_at: time::OffsetDateTime::from_unix_timestamp(expires_at)
+                .map_err(|_| InvalidTokenError)?,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        signing::sign(PURPOSE, &self.payload())
+    }
+
+    pub fn decode(token: &str) -> Result<Self, InvalidTokenError> {
+        Self::from_payload(&signing::verify(PURPOSE, token)?)
+    }
+
+    pub fn grants_access(&self, event_id: Uuid, now: time::OffsetDateTime) -> bool {
+        self.event_id == event_id && now < self.expires_at
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+    use uuid::Uuid;
+
+    use crate::signing::{sign_with_key, verify_with_key};
+
+    use super::{PreviewToken, PURPOSE};
+
+    #[test]
+    fn test_payload_roundtrip() {
+        let token = PreviewToken {
+            event_id: Uuid::new_v4(),
+            expires_at: datetime!(2025-03-08 09:00 UTC),
+        };
+        let encoded = sign_with_key(PURPOSE, &token.payload(), b"key");
+        let payload = verify_with_key(PURPOSE, &encoded, b"key").expect("Token should be valid");
+        let decoded = PreviewToken::from_payload(&payload).expect("Payload should be valid");
+        assert_eq!(decoded.event_id, token.event_id);
+        assert_eq!(decoded.expires_at, token.expires_at);
+    }
+
+    #[test]
+    fn test_grants_access() {
+        let event_id = Uuid::new_v4();
+        let token = PreviewToken {
+            event_id,
+            expires_at: datetime!(2025-03-08 09:00 UTC),
+        };
+        assert!(token.grants_access(event_id, datetime!(2025-03-08 08:59 UTC)));
+        assert!(!token.grants_access(event_id, datetime!(2025-03-08 09:00 UTC)));
+        assert!(!token.grants_access(Uuid::new_v4(), datetime!(2025-03-08 08:59 UTC)));
+    }
+}
@@ -1,17 +1,89 @@
-use aws_sdk_dynamodb::types::AttributeValue;
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::{
+    operation::query::builders::QueryFluentBuilder,
+    types::{
+        AttributeValue, DeleteRequest, KeysAndAttributes, ReturnValue, ScalarAttributeType,
+        TransactWriteItem, Update, WriteRequest,
+    },
+};
 use tracing::error;
+use uuid::Uuid;
 
-use crate::database::errors::{DatabaseQueryFailed, UnknownSdkError};
+use crate::{
+    audit::models::AuditEntry,
+    database::{
+        errors::{DatabaseQueryFailed, ModelError, UnknownSdkError},
+        util::{
+            datetime_attribute, failed_transaction_condition, get_datetime, get_optional_field,
+            get_optional_nested_object, nested_object_attribute,
+        },
+    },
+    images::{framing::FocalPoint, variants::ImageVariant},
+    notifications::models::PendingNotice,
+    organizers::models::Organizer,
+};
 
 use super::{
-    errors::{AddImageError, GetEventError},
-    models::{columns::IMAGE_COLUMN, Event},
+    errors::{
+        AddImageError, CreateEventError, DeleteEventError, GetEventError, ListEventsError,
+        UpdateEventError,
+    },
+    listing::{
+        CreatorEventSummary, CreatorEventsFilter, CreatorListingEntry, EventPeriod, EventSummary,
+    },
+    models::{
+        columns::{self, IMAGE_COLUMN, VERSION_COLUMN},
+        Event,
+    },
 };
 
+/// Items of an event which outlive it
+const KEPT_SORT_KEY_PREFIXES: [&str; 2] =
+    [AuditEntry::SORT_KEY_PREFIX, PendingNotice::SORT_KEY_PREFIX];
+
+/// Columns which are never changed when updating the event details
+const NON_UPDATABLE_COLUMNS: [&str; 7] = [
+    columns::PARTITION_KEY_COLUMN,
+    columns::SORTING_KEY_COLUMN,
+    columns::CREATOR_COLUMN,
+    columns::IMAGE_COLUMN,
+    columns::IMAGE_VARIANTS_COLUMN,
+    columns::FOCAL_POINT_COLUMN,
+    columns::VERSION_COLUMN,
+];
+
+/// Columns which are removed from the item when the event no longer has a value for them
+const OPTIONAL_COLUMNS: [&str; 4] = [
+    columns::PARTICIPANTS_LIMIT_COLUMN,
+    columns::PHONE_COLUMN,
+    columns::NAME_COLUMN,
+    columns::END_DATE_COLUMN,
+];
+
+/// Dates of an event item, which sort chronologically once they are all stored in UTC
+const DATE_COLUMNS: [&str; 3] = [
+    columns::SIGNUP_DEADLINE_COLUMN,
+    columns::EVENT_DATE_COLUMN,
+    columns::END_DATE_COLUMN,
+];
+
+/// Most items a single batch write accepts
+const MAX_BATCH_WRITE_ITEMS: usize = 25;
+/// Most items a single batch get accepts
+const MAX_BATCH_GET_ITEMS: usize = 100;
+const MAX_BATCH_WRITE_ATTEMPTS: u32 = 5;
+
+/// A page of an events listing, with the key to continue from if there are more events
+pub struct EventsPage<T = EventSummary> {
+    pub events: Vec<T>,
+    pub last_evaluated_key: Option<HashMap<String, AttributeValue>>,
+}
+
 #[derive(Clone)]
 pub struct DynamodbQueries {
-    client: aws_sdk_dynamodb::Client,
-    table_name: &'static str,
+    pub(crate) client: aws_sdk_dynamodb::Client,
+    pub(crate) table_name: &'static str,
 }
 
 impl DynamodbQueries {
@@ -19,37 +91,391 @@ impl DynamodbQueries {
         Self { client, table_name }
     }
 
+    pub async fn create_event(&self, event: &Event) -> Result<(), CreateEventError> {
+        let item = HashMap::<String, AttributeValue>::try_from(event).map_err(|e| {
+            error!("Failed to convert event to item: {e:?}");
+            sentry::capture_error(&e);
+            CreateEventError::from(e)
+        })?;
+
+        self.client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(item))
+            .condition_expression("attribute_not_exists(PK)")
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                CreateEventError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    /// Writes the details of `event`, provided the stored event still has the version `event`
+    /// was read at. Returns the new version of the event.
+    pub async fn update_event(&self, event: &Event) -> Result<u64, UpdateEventError> {
+        let item = HashMap::<String, AttributeValue>::try_from(event).map_err(|e| {
+            error!("Failed to convert event to item: {e:?}");
+            sentry::capture_error(&e);
+            UpdateEventError::from(e)
+        })?;
+
+        let removed_columns: Vec<&str> = OPTIONAL_COLUMNS
+            .into_iter()
+            .filter(|column| !item.contains_key(*column))
+            .collect();
+
+        let mut names = HashMap::from([("#V".to_owned(), VERSION_COLUMN.to_owned())]);
+        let mut values = HashMap::from([(
+            ":version".to_owned(),
+            AttributeValue::N((event.version + 1).to_string()),
+        )]);
+        let mut set_expressions = vec!["#V = :version".to_owned()];
+        for (index, (column, value)) in item
+            .into_iter()
+            .filter(|(column, _)| !NON_UPDATABLE_COLUMNS.contains(&column.as_str()))
+            .enumerate()
+        {
+            names.insert(format!("#F{index}"), column);
+            values.insert(format!(":f{index}"), value);
+            set_expressions.push(format!("#F{index} = :f{index}"));
+        }
+        let mut update_expression = format!("SET {}", set_expressions.join(", "));
+
+        let mut remove_expressions = Vec::new();
+        for (index, column) in removed_columns.into_iter().enumerate() {
+            names.insert(format!("#R{index}"), column.to_owned());
+            remove_expressions.push(format!("#R{index}"));
+        }
+        if !remove_expressions.is_empty() {
+            update_expression.push_str(&format!(" REMOVE {}", remove_expressions.join(", ")));
+        }
+
+        // Events from before versioning have no version attribute, which is read as version 0
+        let mut condition_expression = if event.version == 0 {
+            "attribute_exists(PK) AND attribute_not_exists(#V)".to_owned()
+        } else {
+            values.insert(
+                ":expectedVersion".to_owned(),
+                AttributeValue::N(event.version.to_string()),
+            );
+            "attribute_exists(PK) AND #V = :expectedVersion".to_owned()
+        };
+        // Signups don't change the version, so one may have come in since the limit was validated
+        if let Some(limit) = event.limit {
+            names.insert(
+                "#C".to_owned(),
+                columns::PARTICIPANTS_COUNT_COLUMN.to_owned(),
+            );
+            values.insert(":limit".to_owned(), AttributeValue::N(limit.to_string()));
+            condition_expression.push_str(" AND (attribute_not_exists(#C) OR #C <= :limit)");
+        }
+
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .key("PK", AttributeValue::S(event.id.to_string()))
+            .key("SK", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
+            .update_expression(update_expression)
+            .condition_expression(condition_expression)
+            .set_expression_attribute_names(Some(names))
+            .set_expression_attribute_values(Some(values))
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    tracing::debug!("Event {} was modified concurrently", event.id);
+                    return UpdateEventError::VersionConflict;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UpdateEventError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(event.version + 1)
+    }
+
+    /// Makes `image_id` the cover image of the event. Returns the cover image it replaced, if
+    /// any, so its files can be deleted.
     pub async fn set_event_image(
         &self,
         event_id: uuid::Uuid,
         image_id: uuid::Uuid,
-    ) -> Result<(), AddImageError> {
-        self.client
+        variants: &[ImageVariant],
+        focal_point: Option<FocalPoint>,
+    ) -> Result<Option<(Uuid, Vec<ImageVariant>)>, AddImageError> {
+        let mut request = self
+            .client
             .update_item()
             .table_name(self.table_name)
             .key("PK", AttributeValue::S(event_id.to_string()))
             .key("SK", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
-            .update_expression("SET #P = :image")
+            .condition_expression("attribute_exists(PK)")
             .expression_attribute_names("#P", IMAGE_COLUMN)
+            .expression_attribute_names("#IV", columns::IMAGE_VARIANTS_COLUMN)
+            .expression_attribute_names("#FP", columns::FOCAL_POINT_COLUMN)
+            .expression_attribute_names("#V", VERSION_COLUMN)
             .expression_attribute_values(":image", AttributeValue::S(image_id.to_string()))
+            .expression_attribute_values(
+                ":variants",
+                nested_object_attribute(columns::IMAGE_VARIANTS_COLUMN, &variants)?,
+            )
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .return_values(ReturnValue::UpdatedOld);
+        // The focal point of the replaced image is no use for the new one
+        request = match focal_point {
+            Some(focal_point) => request
+                .update_expression("SET #P = :image, #IV = :variants, #FP = :focal ADD #V :one")
+                .expression_attribute_values(
+                    ":focal",
+                    nested_object_attribute(columns::FOCAL_POINT_COLUMN, &focal_point)?,
+                ),
+            None => {
+                request.update_expression("SET #P = :image, #IV = :variants REMOVE #FP ADD #V :one")
+            }
+        };
+        let res = request.send().await.map_err(|s| {
+            if s.as_service_error()
+                .is_some_and(|e| e.is_conditional_check_failed_exception())
+            {
+                return AddImageError::from(GetEventError::NotFound);
+            }
+            error!("Failed to query database: {s:?}");
+            sentry::capture_error(&s);
+            AddImageError::from(DatabaseQueryFailed)
+        })?;
+
+        let Some(old) = res.attributes else {
+            return Ok(None);
+        };
+        let old_image: Option<Uuid> =
+            get_optional_field(&old, IMAGE_COLUMN, ScalarAttributeType::S)?;
+        Ok(old_image
+            .filter(|old_image| *old_image != image_id)
+            .map(|old_image| {
+                get_optional_nested_object(&old, columns::IMAGE_VARIANTS_COLUMN)
+                    .map(|variants| (old_image, variants.unwrap_or_default()))
+            })
+            .transpose()?)
+    }
+
+    /// Removes the cover image of the event if it still is `image_id`. Returns whether it was.
+    pub async fn remove_event_image(
+        &self,
+        event_id: uuid::Uuid,
+        image_id: uuid::Uuid,
+    ) -> Result<bool, AddImageError> {
+        let res = self
+            .client
+            .update_item()
+            .table_name(self.table_name)
+            .key("PK", AttributeValue::S(event_id.to_string()))
+            .key("SK", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
+            .update_expression("REMOVE #P, #IV, #FP ADD #V :one")
+            .condition_expression("#P = :image")
+            .expression_attribute_names("#P", IMAGE_COLUMN)
+            .expression_attribute_names("#IV", columns::IMAGE_VARIANTS_COLUMN)
+            .expression_attribute_names("#FP", columns::FOCAL_POINT_COLUMN)
+            .expression_attribute_names("#V", VERSION_COLUMN)
+            .expression_attribute_values(":image", AttributeValue::S(image_id.to_string()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
             .send()
-            .await
-            .map_err(|s| {
+            .await;
+
+        match res {
+            Ok(_) => Ok(true),
+            Err(s)
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Ok(false)
+            }
+            Err(s) => {
                 error!("Failed to query database: {s:?}");
                 sentry::capture_error(&s);
-                AddImageError::from(DatabaseQueryFailed)
+                Err(AddImageError::from(DatabaseQueryFailed))
+            }
+        }
+    }
+
+    /// Marks the event as cancelled, keeping its signups. Cancelling again keeps the first date.
+    /// The `notice` to participants is queued in the same transaction, so that they are told
+    /// exactly when the event is cancelled.
+    pub async fn cancel_event(
+        &self,
+        event_id: uuid::Uuid,
+        notice: Option<&PendingNotice>,
+    ) -> Result<(), DatabaseQueryFailed> {
+        let now = time::OffsetDateTime::now_utc();
+        let cancel = Update::builder()
+            .table_name(self.table_name)
+            .key("PK", AttributeValue::S(event_id.to_string()))
+            .key("SK", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
+            .update_expression("SET #C = if_not_exists(#C, :now) ADD #V :one")
+            .condition_expression("attribute_exists(PK)")
+            .expression_attribute_names("#C", columns::CANCELLED_AT_COLUMN)
+            .expression_attribute_names("#V", VERSION_COLUMN)
+            .expression_attribute_values(":now", date_attribute(now))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .build()
+            .map_err(|e| {
+                error!("Failed to build transaction item: {e:?}");
+                sentry::capture_error(&e);
+                DatabaseQueryFailed
             })?;
 
+        let mut transaction = self
+            .client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().update(cancel).build());
+        if let Some(notice) = notice {
+            transaction = transaction.transact_items(
+                TransactWriteItem::builder()
+                    .put(self.queue_notice_request(notice)?)
+                    .build(),
+            );
+        }
+        match transaction.send().await {
+            Ok(_) => Ok(()),
+            // Someone else cancelled the event at the same time, and queued the notice
+            Err(s) if failed_transaction_condition(s.as_service_error()) == Some(1) => {
+                tracing::debug!("Notice of cancelling {event_id} already queued");
+                Ok(())
+            }
+            Err(s) => {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                Err(DatabaseQueryFailed)
+            }
+        }
+    }
+
+    /// Deletes every item of the event. The event item itself is deleted last, so that an
+    /// interrupted deletion can be retried by deleting the event again. The audit log is kept, as
+    /// are queued notices until they are sent and expire.
+    pub async fn delete_event(&self, event_id: uuid::Uuid) -> Result<(), DeleteEventError> {
+        let mut keys = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .key_condition_expression("PK = :eventId")
+                .projection_expression("PK, SK")
+                .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    DeleteEventError::from(DatabaseQueryFailed)
+                })?;
+            keys.extend(
+                res.items()
+                    .iter()
+                    .filter(|key| {
+                        !key.get(columns::SORTING_KEY_COLUMN)
+                            .and_then(|sort_key| sort_key.as_s().ok())
+                            .is_some_and(|sort_key| {
+                                KEPT_SORT_KEY_PREFIXES
+                                    .iter()
+                                    .any(|prefix| sort_key.starts_with(prefix))
+                            })
+                    })
+                    .cloned(),
+            );
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        let event_sort_key = AttributeValue::S(Event::SORT_KEY_VALUE.to_owned());
+        let (event_keys, other_keys): (Vec<_>, Vec<_>) = keys
+            .into_iter()
+            .partition(|key| key.get(columns::SORTING_KEY_COLUMN) == Some(&event_sort_key));
+
+        for chunk in other_keys.chunks(MAX_BATCH_WRITE_ITEMS) {
+            self.delete_items(chunk.to_vec()).await?;
+        }
+        self.delete_items(event_keys).await?;
+
         Ok(())
     }
 
+    /// Deletes at most [`MAX_BATCH_WRITE_ITEMS`] items, retrying any the database leaves
+    /// unprocessed
+    async fn delete_items(
+        &self,
+        keys: Vec<HashMap<String, AttributeValue>>,
+    ) -> Result<(), DeleteEventError> {
+        let mut requests = keys
+            .into_iter()
+            .map(|key| {
+                DeleteRequest::builder()
+                    .set_key(Some(key))
+                    .build()
+                    .map(|request| WriteRequest::builder().delete_request(request).build())
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| {
+                error!("Failed to build delete request: {e:?}");
+                sentry::capture_error(&e);
+                DeleteEventError::from(DatabaseQueryFailed)
+            })?;
+
+        for attempt in 0..MAX_BATCH_WRITE_ATTEMPTS {
+            if requests.is_empty() {
+                return Ok(());
+            }
+            if attempt > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(50 << attempt)).await;
+            }
+
+            let res = self
+                .client
+                .batch_write_item()
+                .request_items(self.table_name, requests)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    DeleteEventError::from(DatabaseQueryFailed)
+                })?;
+            requests = res
+                .unprocessed_items
+                .and_then(|mut items| items.remove(self.table_name))
+                .unwrap_or_default();
+        }
+
+        if requests.is_empty() {
+            return Ok(());
+        }
+        error!("{} items left unprocessed after deleting", requests.len());
+        sentry::capture_message(
+            "Items left unprocessed when deleting event",
+            sentry::Level::Error,
+        );
+        Err(DatabaseQueryFailed.into())
+    }
+
     pub async fn get_event(&self, event_id: uuid::Uuid) -> Result<Event, GetEventError> {
         let res = self
             .client
             .query()
             .table_name(self.table_name)
-            .key_condition_expression("PK = :eventId")
+            .key_condition_expression("PK = :eventId AND SK = :sk")
             .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+            .expression_attribute_values(":sk", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
             .send()
             .await
             .map_err(|s| {
@@ -71,7 +497,7 @@ impl DynamodbQueries {
 
         let event = events.first().ok_or_else(|| {
             tracing::debug!("Failed to find event with id: {event_id}");
-            return GetEventError::NotFound;
+            GetEventError::NotFound
         })?;
         tracing::debug!("Found event {:?}", event_id);
         match Event::try_from(event) {
@@ -79,144 +505,775 @@ impl DynamodbQueries {
             Err(e) => {
                 error!("Failed to parse event: {e:?}");
                 sentry::capture_error(&e);
-                Err(GetEventError::InvalidStoredEvent(event_id.clone()))
+                Err(GetEventError::InvalidStoredEvent(event_id))
+            }
+        }
+    }
+
+    /// Lists visible events taking place between `from` and `to`, ordered by event date.
+    /// Hidden events are filtered out after being read, so the index is queried until `limit`
+    /// visible events are found or the listing is exhausted.
+    pub async fn list_events(
+        &self,
+        from: time::OffsetDateTime,
+        to: Option<time::OffsetDateTime>,
+        ascending: bool,
+        limit: usize,
+        start_key: Option<HashMap<String, AttributeValue>>,
+    ) -> Result<EventsPage, ListEventsError> {
+        let mut values = HashMap::from([
+            (
+                ":type".to_owned(),
+                AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()),
+            ),
+            (":from".to_owned(), date_attribute(from)),
+            (":visible".to_owned(), AttributeValue::Bool(true)),
+        ]);
+        let key_condition = match to {
+            Some(to) => {
+                values.insert(":to".to_owned(), date_attribute(to));
+                "SK = :type AND #D BETWEEN :from AND :to"
+            }
+            None => "SK = :type AND #D >= :from",
+        };
+
+        let query = self
+            .client
+            .query()
+            .table_name(self.table_name)
+            .index_name(columns::EVENTS_LISTING_INDEX)
+            .key_condition_expression(key_condition)
+            .filter_expression("#V = :visible")
+            .expression_attribute_names("#D", columns::EVENT_DATE_COLUMN)
+            .expression_attribute_names("#V", columns::EVENT_VISIBLE_COLUMN)
+            .set_expression_attribute_values(Some(values))
+            .scan_index_forward(ascending);
+
+        fill_page(query, limit, start_key).await
+    }
+
+    /// Ids of every event, read from the listing index so the table is not scanned
+    pub async fn list_event_ids(&self) -> Result<Vec<Uuid>, ListEventsError> {
+        let mut pages = self
+            .client
+            .query()
+            .table_name(self.table_name)
+            .index_name(columns::EVENTS_LISTING_INDEX)
+            .key_condition_expression("SK = :type")
+            .expression_attribute_values(
+                ":type",
+                AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()),
+            )
+            .projection_expression("PK")
+            .into_paginator()
+            .send();
+
+        let mut ids = Vec::new();
+        while let Some(page) = pages.next().await {
+            let page = page.map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                ListEventsError::from(DatabaseQueryFailed)
+            })?;
+            for item in page.items() {
+                let id = item
+                    .get(columns::PARTITION_KEY_COLUMN)
+                    .and_then(|id| id.as_s().ok())
+                    .and_then(|id| id.parse().ok())
+                    .ok_or(ListEventsError::InvalidStoredEvent)?;
+                ids.push(id);
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Rewrites the dates of an event in UTC, which they were not always stored in. Returns
+    /// whether any date needed rewriting, which it is only when not a `dry_run`.
+    pub async fn normalize_event_dates(
+        &self,
+        event_id: Uuid,
+        dry_run: bool,
+    ) -> Result<bool, UpdateEventError> {
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(HashMap::from([
+                ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+                (
+                    "SK".to_owned(),
+                    AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()),
+                ),
+            ])))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UpdateEventError::from(DatabaseQueryFailed)
+            })?;
+        let Some(item) = res.item else {
+            return Ok(false);
+        };
+
+        let mut names = HashMap::new();
+        let mut values = HashMap::new();
+        let mut set_expressions = Vec::new();
+        let mut conditions = Vec::new();
+        for (index, column) in DATE_COLUMNS.into_iter().enumerate() {
+            let Some(stored) = item.get(column).and_then(|value| value.as_s().ok()) else {
+                continue;
+            };
+            let normalized = datetime_attribute(column, &get_datetime(&item, column)?)?;
+            if normalized
+                .as_s()
+                .is_ok_and(|normalized| normalized == stored)
+            {
+                continue;
+            }
+            names.insert(format!("#D{index}"), column.to_owned());
+            values.insert(format!(":stored{index}"), AttributeValue::S(stored.clone()));
+            values.insert(format!(":utc{index}"), normalized);
+            set_expressions.push(format!("#D{index} = :utc{index}"));
+            conditions.push(format!("#D{index} = :stored{index}"));
+        }
+        if set_expressions.is_empty() || dry_run {
+            return Ok(!set_expressions.is_empty());
+        }
+
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .key("PK", AttributeValue::S(event_id.to_string()))
+            .key("SK", AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()))
+            .update_expression(format!("SET {}", set_expressions.join(", ")))
+            .condition_expression(conditions.join(" AND "))
+            .set_expression_attribute_names(Some(names))
+            .set_expression_attribute_values(Some(values))
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    tracing::debug!("Event {event_id} was modified while normalizing its dates");
+                    return UpdateEventError::VersionConflict;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UpdateEventError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(true)
+    }
+
+    /// Lists the events created by `creator`, optionally narrowed down to past or upcoming, and
+    /// visible or hidden events
+    pub async fn list_creator_events(
+        &self,
+        creator: &str,
+        filter: CreatorEventsFilter,
+        limit: usize,
+        start_key: Option<HashMap<String, AttributeValue>>,
+    ) -> Result<EventsPage<CreatorEventSummary>, ListEventsError> {
+        let mut names = HashMap::from([("#C".to_owned(), columns::CREATOR_COLUMN.to_owned())]);
+        let mut values =
+            HashMap::from([(":creator".to_owned(), AttributeValue::S(creator.to_owned()))]);
+        let mut conditions = Vec::new();
+        match filter.period {
+            Some(EventPeriod::Upcoming) => conditions.push("#D >= :now"),
+            Some(EventPeriod::Past) => conditions.push("#D < :now"),
+            None => {}
+        }
+        if filter.period.is_some() {
+            names.insert("#D".to_owned(), columns::EVENT_DATE_COLUMN.to_owned());
+            values.insert(":now".to_owned(), date_attribute(filter.now));
+        }
+        if let Some(visible) = filter.visible {
+            conditions.push("#V = :visible");
+            names.insert("#V".to_owned(), columns::EVENT_VISIBLE_COLUMN.to_owned());
+            values.insert(":visible".to_owned(), AttributeValue::Bool(visible));
+        }
+        // Organizer items have none of the filtered attributes, so their events are filtered
+        // once they have been read
+        let filter_expression = (!conditions.is_empty()).then(|| {
+            values.insert(
+                ":organizer".to_owned(),
+                AttributeValue::S(Organizer::SORT_KEY_PREFIX.to_owned()),
+            );
+            format!(
+                "begins_with(SK, :organizer) OR ({})",
+                conditions.join(" AND ")
+            )
+        });
+
+        let query = self
+            .client
+            .query()
+            .table_name(self.table_name)
+            .index_name(columns::EVENTS_BY_CREATOR_INDEX)
+            .key_condition_expression("#C = :creator")
+            .set_filter_expression(filter_expression)
+            .set_expression_attribute_names(Some(names))
+            .set_expression_attribute_values(Some(values));
+
+        let page: EventsPage<CreatorListingEntry> = fill_page(query, limit, start_key).await?;
+
+        let co_organized: Vec<Uuid> = page
+            .events
+            .iter()
+            .filter_map(|entry| match entry {
+                CreatorListingEntry::CoOrganized(event_id, _) => Some(*event_id),
+                CreatorListingEntry::Owned(_) => None,
+            })
+            .collect();
+        let mut co_organized: HashMap<Uuid, CreatorEventSummary> = self
+            .get_events(&co_organized)
+            .await?
+            .into_iter()
+            .map(|summary: CreatorEventSummary| (summary.id, summary))
+            .collect();
+
+        let events = page
+            .events
+            .into_iter()
+            .filter_map(|entry| match entry {
+                CreatorListingEntry::Owned(summary) => Some(summary),
+                CreatorListingEntry::CoOrganized(event_id, role) => co_organized
+                    .remove(&event_id)
+                    .filter(|summary| filter.matches(summary))
+                    .map(|summary| CreatorEventSummary { role, ..summary }),
+            })
+            .collect();
+
+        Ok(EventsPage {
+            events,
+            last_evaluated_key: page.last_evaluated_key,
+        })
+    }
+
+    /// Gets the events with the given ids, in no particular order. Events that do not exist are
+    /// left out.
+    pub async fn get_events<T>(&self, event_ids: &[Uuid]) -> Result<Vec<T>, ListEventsError>
+    where
+        T: for<'a> TryFrom<&'a HashMap<String, AttributeValue>, Error = ModelError>,
+    {
+        let mut events = Vec::new();
+        for chunk in event_ids.chunks(MAX_BATCH_GET_ITEMS) {
+            let keys = chunk
+                .iter()
+                .map(|event_id| Self::event_key(*event_id))
+                .collect();
+            let mut keys_and_attributes = Some(
+                KeysAndAttributes::builder()
+                    .set_keys(Some(keys))
+                    .build()
+                    .map_err(|e| {
+                        error!("Failed to build batch get request: {e:?}");
+                        sentry::capture_error(&e);
+                        ListEventsError::from(DatabaseQueryFailed)
+                    })?,
+            );
+
+            for attempt in 0..MAX_BATCH_WRITE_ATTEMPTS {
+                let Some(request) = keys_and_attributes.take() else {
+                    break;
+                };
+                if attempt > 0 {
+                    tokio::time::sleep(std::time::Duration::from_millis(50 << attempt)).await;
+                }
+
+                let res = self
+                    .client
+                    .batch_get_item()
+                    .request_items(self.table_name, request)
+                    .send()
+                    .await
+                    .map_err(|s| {
+                        error!("Failed to query database: {s:?}");
+                        sentry::capture_error(&s);
+                        ListEventsError::from(DatabaseQueryFailed)
+                    })?;
+
+                for item in res
+                    .responses
+                    .as_ref()
+                    .and_then(|responses| responses.get(self.table_name))
+                    .into_iter()
+                    .flatten()
+                {
+                    let event = T::try_from(item).map_err(|e| {
+                        error!("Failed to parse event: {e:?}");
+                        sentry::capture_error(&e);
+                        ListEventsError::InvalidStoredEvent
+                    })?;
+                    events.push(event);
+                }
+
+                keys_and_attributes = res
+                    .unprocessed_keys
+                    .and_then(|mut keys| keys.remove(self.table_name))
+                    .filter(|keys| !keys.keys().is_empty());
             }
         }
+
+        Ok(events)
+    }
+}
+
+/// Runs `query` until `limit` items pass its filter or there are no more items to read
+async fn fill_page<T>(
+    query: QueryFluentBuilder,
+    limit: usize,
+    start_key: Option<HashMap<String, AttributeValue>>,
+) -> Result<EventsPage<T>, ListEventsError>
+where
+    T: for<'a> TryFrom<&'a HashMap<String, AttributeValue>, Error = ModelError>,
+{
+    let mut events = Vec::new();
+    let mut last_evaluated_key = start_key;
+    loop {
+        let res = query
+            .clone()
+            .limit((limit - events.len()) as i32)
+            .set_exclusive_start_key(last_evaluated_key)
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                ListEventsError::from(DatabaseQueryFailed)
+            })?;
+
+        for item in res.items() {
+            let event = T::try_from(item).map_err(|e| {
+                error!("Failed to parse event summary: {e:?}");
+                sentry::capture_error(&e);
+                ListEventsError::InvalidStoredEvent
+            })?;
+            events.push(event);
+        }
+
+        last_evaluated_key = res.last_evaluated_key;
+        if last_evaluated_key.is_none() || events.len() >= limit {
+            break;
+        }
     }
+
+    Ok(EventsPage {
+        events,
+        last_evaluated_key,
+    })
+}
+
+/// Formats a date the way [`datetime_attribute`](crate::database::util::datetime_attribute)
+/// stores it, for comparing against stored dates
+fn date_attribute(date: time::OffsetDateTime) -> AttributeValue {
+    AttributeValue::S(
+        date.to_offset(time::UtcOffset::UTC)
+            .format(&time::format_description::well_known::Rfc3339)
+            .expect("UTC dates within the year range of events can always be formatted"),
+    )
 }
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use uuid::Uuid;
 
-    use aws_config::Region;
-    use aws_sdk_dynamodb::types::{
-        builders::KeySchemaElementBuilder, AttributeDefinition, AttributeValue, BillingMode,
+    use crate::{
+        audit::models::AuditAction,
+        images::{
+            framing::FocalPoint,
+            variants::{ImageFormat, ImageSize, ImageVariant},
+        },
+        notifications::models::{Notice, PendingNotice},
+        test_fixtures::{init_dynamodb, insert_test_event},
     };
-    use serde_json::Value;
-    use testcontainers_modules::{
-        localstack::LocalStack,
-        testcontainers::{runners::AsyncRunner, ContainerAsync, ImageExt},
-    };
-    use uuid::Uuid;
 
-    use crate::events::models::columns;
-
-    fn json_to_dynamodb(json: serde_json::Value) -> HashMap<String, AttributeValue> {
-        let mut item = HashMap::new();
-        if let serde_json::Value::Object(map) = json {
-            for (key, value) in map {
-                let attr_value = match value {
-                    serde_json::Value::String(s) => AttributeValue::S(s),
-                    serde_json::Value::Number(n) => AttributeValue::N(n.to_string()),
-                    serde_json::Value::Bool(b) => AttributeValue::Bool(b),
-                    _ => continue, // Skip unsupported types
-                };
-                item.insert(key, attr_value);
-            }
-        }
-        item
+    #[tokio::test]
+    async fn test_get_event() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+
+        let queries = super::DynamodbQueries::new(client, "events");
+        let event_from_db = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        assert_eq!(event_from_db.id, event_id);
     }
 
-    async fn init_dynamodb() -> (ContainerAsync<LocalStack>, aws_sdk_dynamodb::Client) {
-        let request = LocalStack::default().with_env_var("SERVICES", "dynamodb");
-        let container = request.start().await.expect("Failed to start localstack");
+    #[tokio::test]
+    async fn test_create_event() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let mut event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
 
-        let endpoint_url = format!(
-            "http://{}:{}",
-            container
-                .get_host()
-                .await
-                .expect("Failed to get local stack host"),
-            container
-                .get_host_port_ipv4(4566)
-                .await
-                .expect("Failed to get local stack port")
-        );
-        let creds = aws_sdk_dynamodb::config::Credentials::new("fake", "fake", None, None, "test");
-        let config = aws_sdk_dynamodb::config::Builder::default()
-            .behavior_version_latest()
-            .credentials_provider(creds)
-            .region(Region::new("us-east-1"))
-            .endpoint_url(endpoint_url)
-            .build();
-
-        let client = aws_sdk_dynamodb::Client::from_conf(config);
-
-        let pk_attribute = AttributeDefinition::builder()
-            .attribute_name(columns::PARTITION_KEY_COLUMN)
-            .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
-            .build()
-            .expect("Failed to build test table attribute definition");
-        let sk_attribute = AttributeDefinition::builder()
-            .attribute_name(columns::SORTING_KEY_COLUMN)
-            .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
-            .build()
-            .expect("Failed to build test table attribute definition");
+        let new_event_id = Uuid::new_v4();
+        event.id = new_event_id;
+        event.image = None;
+        queries
+            .create_event(&event)
+            .await
+            .expect("Failed to create event");
 
-        let pk_schema = KeySchemaElementBuilder::default()
-            .attribute_name("PK")
-            .key_type(aws_sdk_dynamodb::types::KeyType::Hash)
-            .build()
-            .expect("Failed to build test table key schema");
-        let sk_schema = KeySchemaElementBuilder::default()
-            .attribute_name("SK")
-            .key_type(aws_sdk_dynamodb::types::KeyType::Range)
-            .build()
-            .expect("Failed to build test table key schema");
+        let created_event = queries
+            .get_event(new_event_id)
+            .await
+            .expect("Failed to get created event from database");
+        assert_eq!(created_event.id, new_event_id);
+        assert_eq!(created_event.title, event.title);
+        assert_eq!(created_event.event_date, event.event_date);
+        assert_eq!(created_event.creator_username, event.creator_username);
+        assert!(created_event.image.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_create_event_does_not_overwrite() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        assert!(queries.create_event(&event).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_update_event() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let mut event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        event.location_name = "Hornborgasjön".to_owned();
+        event.limit = None;
+        let new_version = queries
+            .update_event(&event)
+            .await
+            .expect("Failed to update event");
+
+        let updated_event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        assert_eq!(updated_event.version, new_version);
+        assert_eq!(updated_event.location_name, "Hornborgasjön");
+        assert!(updated_event.limit.is_none());
+        assert_eq!(updated_event.image, event.image);
+    }
+
+    #[tokio::test]
+    async fn test_update_event_version_conflict() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let first_read = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        let mut second_read = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        queries
+            .update_event(&first_read)
+            .await
+            .expect("Failed to update event");
+        second_read.location_name = "Hornborgasjön".to_owned();
+        let result = queries.update_event(&second_read).await;
 
+        assert!(matches!(
+            result,
+            Err(crate::events::errors::UpdateEventError::VersionConflict)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_normalize_event_dates() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
         client
-            .create_table()
+            .update_item()
             .table_name("events")
-            .attribute_definitions(pk_attribute)
-            .attribute_definitions(sk_attribute)
-            .billing_mode(BillingMode::PayPerRequest)
-            .key_schema(pk_schema)
-            .key_schema(sk_schema)
+            .key("PK", AttributeValue::S(event_id.to_string()))
+            .key("SK", AttributeValue::S("Event".to_owned()))
+            .update_expression("SET EventDate = :date")
+            .expression_attribute_values(
+                ":date",
+                AttributeValue::S("2025-03-08T10:48:27+01:00".to_owned()),
+            )
             .send()
             .await
-            .expect("Failed to create test table");
-
-        (container, client)
-    }
-
-    async fn insert_test_event(client: &aws_sdk_dynamodb::Client) -> Uuid {
-        let event: Value =
-            serde_json::from_str(include_str!("../test_fixtures/event.json")).unwrap();
-        let event_id = Uuid::parse_str(
-            event
-                .as_object()
-                .unwrap()
-                .get("PK")
-                .unwrap()
-                .as_str()
-                .unwrap(),
-        )
-        .unwrap();
-        let item = json_to_dynamodb(event);
-        client
-            .put_item()
+            .expect("Failed to store date with offset");
+        let queries = super::DynamodbQueries::new(client, "events");
+
+        assert_eq!(
+            queries
+                .list_event_ids()
+                .await
+                .expect("Failed to list events"),
+            vec![event_id]
+        );
+        assert!(queries
+            .normalize_event_dates(event_id, false)
+            .await
+            .expect("Failed to normalize dates"));
+        assert!(!queries
+            .normalize_event_dates(event_id, false)
+            .await
+            .expect("Failed to normalize dates"));
+
+        let item = queries
+            .client
+            .get_item()
             .table_name("events")
-            .set_item(Some(item))
+            .key("PK", AttributeValue::S(event_id.to_string()))
+            .key("SK", AttributeValue::S("Event".to_owned()))
             .send()
             .await
-            .expect("Failed to insert test event");
+            .expect("Failed to get event item")
+            .item
+            .expect("Event item is missing");
+        assert_eq!(
+            item.get("EventDate"),
+            Some(&AttributeValue::S("2025-03-08T09:48:27Z".to_owned()))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_events() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let mut event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        let later_id = Uuid::new_v4();
+        event.id = later_id;
+        event.event_date += time::Duration::days(7);
+        queries
+            .create_event(&event)
+            .await
+            .expect("Failed to create event");
+        event.id = Uuid::new_v4();
+        event.event_visible = false;
+        queries
+            .create_event(&event)
+            .await
+            .expect("Failed to create event");
+
+        let from = time::macros::datetime!(2025-01-01 00:00 UTC);
+        let first_page = queries
+            .list_events(from, None, true, 1, None)
+            .await
+            .expect("Failed to list events");
+        assert_eq!(
+            first_page.events.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![event_id]
+        );
 
-        event_id
+        let second_page = queries
+            .list_events(from, None, true, 10, first_page.last_evaluated_key)
+            .await
+            .expect("Failed to list events");
+        assert_eq!(
+            second_page.events.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![later_id]
+        );
+        assert!(second_page.last_evaluated_key.is_none());
     }
 
     #[tokio::test]
-    async fn test_get_event() {
+    async fn test_list_creator_events() {
         let (_container, client) = init_dynamodb().await;
         let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let mut event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
 
+        let hidden_id = Uuid::new_v4();
+        event.id = hidden_id;
+        event.event_visible = false;
+        queries
+            .create_event(&event)
+            .await
+            .expect("Failed to create event");
+        event.id = Uuid::new_v4();
+        event.creator_username = "someone else".to_owned();
+        queries
+            .create_event(&event)
+            .await
+            .expect("Failed to create event");
+
+        let filter = crate::events::listing::CreatorEventsFilter {
+            period: Some(crate::events::listing::EventPeriod::Past),
+            visible: Some(false),
+            now: time::OffsetDateTime::now_utc(),
+        };
+        let page = queries
+            .list_creator_events("Google_104204918422142738931", filter, 10, None)
+            .await
+            .expect("Failed to list events");
+
+        assert_eq!(
+            page.events.iter().map(|e| e.id).collect::<Vec<_>>(),
+            vec![hidden_id]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_co_organized_events() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
         let queries = super::DynamodbQueries::new(client, "events");
-        let event_from_db = queries
+        queries
+            .put_organizer(&crate::organizers::models::Organizer {
+                event_id,
+                username: "editor".to_owned(),
+                role: crate::organizers::models::OrganizerRole::Editor,
+            })
+            .await
+            .expect("Failed to add organizer");
+
+        let filter = crate::events::listing::CreatorEventsFilter {
+            period: None,
+            visible: Some(true),
+            now: time::OffsetDateTime::now_utc(),
+        };
+        let page = queries
+            .list_creator_events("editor", filter, 10, None)
+            .await
+            .expect("Failed to list events");
+
+        assert_eq!(page.events.len(), 1);
+        assert_eq!(page.events[0].id, event_id);
+        assert_eq!(
+            page.events[0].role,
+            crate::organizers::models::OrganizerRole::Editor
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cancel_event() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        let event = queries
             .get_event(event_id)
             .await
             .expect("Failed to get event from database");
+        let signups = queries
+            .get_signups(event_id)
+            .await
+            .expect("Failed to get signups");
+        let notice = PendingNotice::new(&event, &signups, Notice::Cancelled);
 
-        assert_eq!(event_from_db.id, event_id);
+        queries
+            .cancel_event(event_id, notice.as_ref())
+            .await
+            .expect("Failed to cancel event");
+        let cancelled = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        // As when two organizers cancel the event at once
+        queries
+            .cancel_event(event_id, notice.as_ref())
+            .await
+            .expect("Failed to cancel event again");
+        let cancelled_again = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+
+        assert!(cancelled.cancelled_at.is_some());
+        assert_eq!(cancelled_again.cancelled_at, cancelled.cancelled_at);
+        let queued = queries
+            .get_pending_notice(event_id, Notice::Cancelled)
+            .await
+            .expect("Failed to get notice");
+        assert_eq!(queued.is_some(), notice.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_delete_event() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = super::DynamodbQueries::new(client, "events");
+        // More signups than fit in one batch
+        for i in 0..30 {
+            queries
+                .client
+                .put_item()
+                .table_name("events")
+                .item("PK", AttributeValue::S(event_id.to_string()))
+                .item("SK", AttributeValue::S(format!("Signup#{i}")))
+                .send()
+                .await
+                .expect("Failed to insert signup");
+        }
+        let notice = PendingNotice {
+            event_id,
+            notice: Notice::Deleted,
+            subject: "Cancelled".to_owned(),
+            body: "Cancelled".to_owned(),
+            recipients: ["anna@example.com".to_owned()].into(),
+            queued_at: time::OffsetDateTime::now_utc(),
+        };
+        queries
+            .queue_notice(&notice)
+            .await
+            .expect("Failed to queue notice");
+        queries
+            .record_audit(event_id, "organizer", AuditAction::EventChanged, None)
+            .await;
+
+        queries
+            .delete_event(event_id)
+            .await
+            .expect("Failed to delete event");
+
+        let remaining = queries
+            .client
+            .query()
+            .table_name("events")
+            .key_condition_expression("PK = :eventId")
+            .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+            .send()
+            .await
+            .expect("Failed to query database");
+        // Only the audit log and the notice to be sent are left
+        assert_eq!(remaining.count(), 2);
+        assert_eq!(
+            queries
+                .get_audit_log(event_id)
+                .await
+                .expect("Failed to get audit log")
+                .len(),
+            1
+        );
+        assert!(queries
+            .get_pending_notice(event_id, Notice::Deleted)
+            .await
+            .expect("Failed to get notice")
+            .is_some());
     }
 
     #[tokio::test]
@@ -230,8 +1287,15 @@ mod tests {
             .await
             .expect("Failed to get event from database");
         assert_ne!(event_from_db.image.unwrap(), new_image_id);
+        let variants = vec![ImageVariant {
+            size: ImageSize::Full,
+            format: ImageFormat::Jpeg,
+            width: 1280,
+            height: 960,
+        }];
+        let focal_point = FocalPoint { x: 0.25, y: 0.5 };
         queries
-            .set_event_image(event_id, new_image_id)
+            .set_event_image(event_id, new_image_id, &variants, Some(focal_point))
             .await
             .expect("Failed to add image to event");
         let updated_event = queries
@@ -239,5 +1303,15 @@ mod tests {
             .await
             .expect("Failed to get event from database");
         assert_eq!(updated_event.image.unwrap(), new_image_id);
+        assert_eq!(updated_event.image_variants, variants);
+        assert_eq!(updated_event.image_focal_point, Some(focal_point));
+
+        // A new image without a focal point doesn't keep the old one
+        queries
+            .set_event_image(event_id, Uuid::new_v4(), &variants, None)
+            .await
+            .expect("Failed to replace image of event");
+        let updated_event = queries.get_event(event_id).await.unwrap();
+        assert_eq!(updated_event.image_focal_point, None);
     }
 }
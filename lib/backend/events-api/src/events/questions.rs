@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::localization::localized;
+
+/// A question participants answer when signing up, defined by the organizer per event
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Question {
+    pub id: String,
+    pub label: HashMap<String, String>,
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: QuestionKind,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum QuestionKind {
+    #[serde(rename_all = "camelCase")]
+    FreeText {
+        max_length: Option<usize>,
+    },
+    SingleChoice {
+        options: Vec<ChoiceOption>,
+    },
+    MultiChoice {
+        options: Vec<ChoiceOption>,
+    },
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Boolean,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ChoiceOption {
+    pub id: String,
+    pub label: HashMap<String, String>,
+}
+
+/// A [`Question`] with its labels in a single language
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalizedQuestion {
+    pub id: String,
+    pub label: String,
+    pub required: bool,
+    #[serde(flatten)]
+    pub kind: LocalizedQuestionKind,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum LocalizedQuestionKind {
+    #[serde(rename_all = "camelCase")]
+    FreeText {
+        max_length: Option<usize>,
+    },
+    SingleChoice {
+        options: Vec<LocalizedChoiceOption>,
+    },
+    MultiChoice {
+        options: Vec<LocalizedChoiceOption>,
+    },
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Boolean,
+}
+
+#[derive(Serialize)]
+pub struct LocalizedChoiceOption {
+    pub id: String,
+    pub label: String,
+}
+
+impl Question {
+    pub fn localized(self, language: &str) -> LocalizedQuestion {
+        let options = |options: Vec<ChoiceOption>| {
+            options
+                .into_iter()
+                .map(|option| LocalizedChoiceOption {
+                    label: localized(&option.label, language),
+                    id: option.id,
+                })
+                .collect()
+        };
+        LocalizedQuestion {
+            label: localized(&self.label, language),
+            id: self.id,
+            required: self.required,
+            kind: match self.kind {
+                QuestionKind::FreeText { max_length } => {
+                    LocalizedQuestionKind::FreeText { max_length }
+                }
+                QuestionKind::SingleChoice { options: choices } => {
+                    LocalizedQuestionKind::SingleChoice {
+                        options: options(choices),
+                    }
+                }
+                QuestionKind::MultiChoice { options: choices } => {
+                    LocalizedQuestionKind::MultiChoice {
+                        options: options(choices),
+                    }
+                }
+                QuestionKind::Number { min, max } => LocalizedQuestionKind::Number { min, max },
+                QuestionKind::Boolean => LocalizedQuestionKind::Boolean,
+            },
+        }
+    }
+}
+
+fn has_all_languages(label: &HashMap<String, String>, languages: &HashSet<&String>) -> bool {
+    languages
+        .iter()
+        .all(|language| label.get(*language).is_some_and(|l| !l.trim().is_empty()))
+}
+
+/// Checks that a question can be answered, and is labelled in every language of the event.
+/// Returns the id of the first invalid question.
+pub fn find_invalid_question<'a>(
+    questions: &'a [Question],
+    languages: &HashSet<&String>,
+) -> Option<&'a str> {
+    let mut ids = HashSet::new();
+    questions
+        .iter()
+        .find(|question| {
+            if question.id.trim().is_empty()
+                || !ids.insert(&question.id)
+                || !has_all_languages(&question.label, languages)
+            {
+                return true;
+            }
+
+            match &question.kind {
+                QuestionKind::SingleChoice { options } | QuestionKind::MultiChoice { options } => {
+                    let mut option_ids = HashSet::new();
+                    options.is_empty()
+                        || options.iter().any(|option| {
+                            option.id.trim().is_empty()
+                                || !option_ids.insert(&option.id)
+                                || !has_all_languages(&option.label, languages)
+                        })
+                }
+                QuestionKind::Number {
+                    min: Some(min),
+                    max: Some(max),
+                } => min > max,
+                _ => false,
+            }
+        })
+        .map(|question| question.id.as_str())
+}
@@ -0,0 +1,196 @@
+use super::{errors::EventValidationError, models::Event, questions::find_invalid_question};
+
+pub fn validate_event(event: &Event) -> Result<(), EventValidationError> {
+    if event.cancelled_at.is_some() {
+        return Err(EventValidationError::EventCancelled);
+    }
+
+    if event.title.is_empty() {
+        return Err(EventValidationError::MissingTitle);
+    }
+
+    if let Some((language, _)) = event
+        .title
+        .iter()
+        .find(|(_, title)| title.trim().is_empty())
+    {
+        return Err(EventValidationError::MissingTitleLanguage(language.clone()));
+    }
+
+    // The frontend renders title and description per language, so both need the same set
+    if let Some(language) = event
+        .description
+        .keys()
+        .find(|language| !event.title.contains_key(*language))
+    {
+        return Err(EventValidationError::MissingTitleLanguage(language.clone()));
+    }
+
+    if let Some(language) = event
+        .title
+        .keys()
+        .find(|language| !event.description.contains_key(*language))
+    {
+        return Err(EventValidationError::MissingDescriptionLanguage(
+            language.clone(),
+        ));
+    }
+
+    if event.tz().is_none() {
+        return Err(EventValidationError::InvalidTimeZone(
+            event.time_zone.clone(),
+        ));
+    }
+
+    if event.signup_end_date > event.event_date {
+        return Err(EventValidationError::SignupDeadlineAfterEventDate);
+    }
+
+    if event
+        .end_date
+        .is_some_and(|end_date| end_date < event.event_date)
+    {
+        return Err(EventValidationError::EndBeforeStart);
+    }
+
+    if event.location_name.trim().is_empty() {
+        return Err(EventValidationError::MissingLocation);
+    }
+
+    if event.email.trim().is_empty() || !event.email.contains('@') {
+        return Err(EventValidationError::InvalidEmail);
+    }
+
+    if event.limit == Some(0) {
+        return Err(EventValidationError::InvalidParticipantsLimit);
+    }
+
+    // Confirmed participants are never moved back to the waitlist
+    if event
+        .limit
+        .is_some_and(|limit| limit < event.participants_count)
+    {
+        return Err(EventValidationError::LimitBelowParticipants(
+            event.participants_count,
+        ));
+    }
+
+    let languages = event.title.keys().collect();
+    if let Some(id) = find_invalid_question(&event.questions, &languages) {
+        return Err(EventValidationError::InvalidQuestion(id.to_owned()));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::Duration;
+
+    use crate::{
+        events::{errors::EventValidationError, models::Event},
+        test_fixtures::event,
+    };
+
+    use super::validate_event;
+
+    #[rstest]
+    fn test_valid_event(event: Event) {
+        assert!(validate_event(&event).is_ok());
+    }
+
+    #[rstest]
+    fn test_cancelled_event(mut event: Event) {
+        event.cancelled_at = Some(event.signup_end_date);
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::EventCancelled)
+        ));
+    }
+
+    #[rstest]
+    fn test_signup_deadline_after_event_date(mut event: Event) {
+        event.signup_end_date = event.event_date + Duration::hours(1);
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::SignupDeadlineAfterEventDate)
+        ));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("Mars/Olympus_Mons")]
+    fn test_invalid_time_zone(mut event: Event, #[case] time_zone: &str) {
+        event.time_zone = time_zone.to_owned();
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::InvalidTimeZone(zone)) if zone == time_zone
+        ));
+    }
+
+    #[rstest]
+    #[case(Duration::hours(-1), false)]
+    #[case(Duration::ZERO, true)]
+    #[case(Duration::hours(3), true)]
+    fn test_end_date(mut event: Event, #[case] offset: Duration, #[case] valid: bool) {
+        event.end_date = Some(event.event_date + offset);
+        match validate_event(&event) {
+            Ok(()) => assert!(valid),
+            Err(EventValidationError::EndBeforeStart) => assert!(!valid),
+            Err(e) => panic!("Unexpected error {e:?}"),
+        }
+    }
+
+    #[rstest]
+    fn test_missing_title_language(mut event: Event) {
+        event.title.remove("en");
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::MissingTitleLanguage(language)) if language == "en"
+        ));
+    }
+
+    #[rstest]
+    fn test_missing_description_language(mut event: Event) {
+        event.description.remove("sv");
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::MissingDescriptionLanguage(language)) if language == "sv"
+        ));
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("   ")]
+    fn test_empty_location(mut event: Event, #[case] location: &str) {
+        event.location_name = location.to_owned();
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::MissingLocation)
+        ));
+    }
+
+    #[rstest]
+    fn test_zero_participants_limit(mut event: Event) {
+        event.limit = Some(0);
+        assert!(matches!(
+            validate_event(&event),
+            Err(EventValidationError::InvalidParticipantsLimit)
+        ));
+    }
+
+    #[rstest]
+    #[case(9, false)]
+    #[case(10, true)]
+    #[case(11, true)]
+    fn test_limit_below_participants(mut event: Event, #[case] limit: u16, #[case] valid: bool) {
+        event.participants_count = 10;
+        event.limit = Some(limit);
+        match validate_event(&event) {
+            Ok(()) => assert!(valid),
+            Err(EventValidationError::LimitBelowParticipants(10)) => assert!(!valid),
+            Err(e) => panic!("Unexpected error {e:?}"),
+        }
+    }
+}
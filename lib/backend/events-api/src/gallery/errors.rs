@@ -0,0 +1,27 @@
+use crate::database::errors::{DatabaseQueryFailed, ModelError};
+
+#[derive(thiserror::Error, Debug)]
+pub enum GalleryError {
+    #[error("Gallery image not found")]
+    NotFound,
+    #[error("New order does not list every gallery image exactly once")]
+    InvalidOrder,
+    #[error("Failed to read gallery image")]
+    InvalidStoredImage(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GalleryUploadError {
+    #[error("Upload is not valid multipart data")]
+    InvalidMultipart,
+    #[error("Upload has no images")]
+    NoImages,
+    #[error("Upload has more than {0} images")]
+    TooManyImages(usize),
+    #[error("Alt texts are not a list of texts by language")]
+    InvalidAltText,
+    #[error("Alt texts are not given for each of the {0} images")]
+    AltTextMismatch(usize),
+}
@@ -0,0 +1,97 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{
+            get_field, get_optional_field, get_optional_nested_object, nested_object_attribute,
+        },
+    },
+    events::models::columns,
+    images::{framing::FocalPoint, variants::ImageVariant},
+};
+
+/// An image in the gallery of an event. The cover image of the event is kept on the event item,
+/// and may be one of these.
+pub struct GalleryImage {
+    pub event_id: Uuid,
+    pub image_id: Uuid,
+    /// Images are shown in ascending position
+    pub position: u32,
+    /// Description of the image by language, for those who cannot see it
+    pub alt_text: HashMap<String, String>,
+    pub variants: Vec<ImageVariant>,
+    /// Where the subject of the image is, if the uploader said
+    pub focal_point: Option<FocalPoint>,
+}
+
+impl GalleryImage {
+    pub const SORT_KEY_PREFIX: &str = "Image#";
+
+    pub fn sort_key_for(image_id: Uuid) -> String {
+        format!("{}{}", Self::SORT_KEY_PREFIX, image_id)
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for GalleryImage {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            image_id: get_field(item, columns::IMAGE_COLUMN)?,
+            position: get_optional_field(
+                item,
+                columns::GALLERY_POSITION_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .unwrap_or(0),
+            alt_text: get_optional_nested_object(item, columns::ALT_TEXT_COLUMN)?
+                .unwrap_or_default(),
+            variants: get_optional_nested_object(item, columns::IMAGE_VARIANTS_COLUMN)?
+                .unwrap_or_default(),
+            focal_point: get_optional_nested_object(item, columns::FOCAL_POINT_COLUMN)?,
+        })
+    }
+}
+
+impl TryFrom<&GalleryImage> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(image: &GalleryImage) -> Result<Self, Self::Error> {
+        let mut item = HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(image.event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(GalleryImage::sort_key_for(image.image_id)),
+            ),
+            (
+                columns::IMAGE_COLUMN.to_owned(),
+                AttributeValue::S(image.image_id.to_string()),
+            ),
+            (
+                columns::GALLERY_POSITION_COLUMN.to_owned(),
+                AttributeValue::N(image.position.to_string()),
+            ),
+            (
+                columns::ALT_TEXT_COLUMN.to_owned(),
+                nested_object_attribute(columns::ALT_TEXT_COLUMN, &image.alt_text)?,
+            ),
+            (
+                columns::IMAGE_VARIANTS_COLUMN.to_owned(),
+                nested_object_attribute(columns::IMAGE_VARIANTS_COLUMN, &image.variants)?,
+            ),
+        ]);
+        if let Some(focal_point) = &image.focal_point {
+            item.insert(
+                columns::FOCAL_POINT_COLUMN.to_owned(),
+                nested_object_attribute(columns::FOCAL_POINT_COLUMN, focal_point)?,
+            );
+        }
+        Ok(item)
+    }
+}
@@ -0,0 +1,284 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::{AttributeValue, ReturnValue, TransactWriteItem, Update};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::DatabaseQueryFailed,
+        util::{failed_transaction_condition, nested_object_attribute},
+    },
+    events::{models::columns, queries::DynamodbQueries},
+};
+
+use super::{errors::GalleryError, models::GalleryImage};
+
+impl DynamodbQueries {
+    fn gallery_image_key(event_id: Uuid, image_id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+            (
+                "SK".to_owned(),
+                AttributeValue::S(GalleryImage::sort_key_for(image_id)),
+            ),
+        ])
+    }
+
+    /// Lists the gallery of an event in the order it is shown
+    pub async fn get_gallery(&self, event_id: Uuid) -> Result<Vec<GalleryImage>, GalleryError> {
+        let mut images = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .key_condition_expression("PK = :eventId AND begins_with(SK, :prefix)")
+                .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(GalleryImage::SORT_KEY_PREFIX.to_owned()),
+                )
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    GalleryError::from(DatabaseQueryFailed)
+                })?;
+
+            for item in res.items() {
+                let image = GalleryImage::try_from(item).map_err(|e| {
+                    error!("Failed to parse gallery image: {e:?}");
+                    sentry::capture_error(&e);
+                    GalleryError::from(e)
+                })?;
+                images.push(image);
+            }
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        images.sort_by_key(|image| image.position);
+        Ok(images)
+    }
+
+    pub async fn get_gallery_image(
+        &self,
+        event_id: Uuid,
+        image_id: Uuid,
+    ) -> Result<GalleryImage, GalleryError> {
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::gallery_image_key(event_id, image_id)))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                GalleryError::from(DatabaseQueryFailed)
+            })?;
+
+        let item = res.item.ok_or(GalleryError::NotFound)?;
+        GalleryImage::try_from(&item).map_err(|e| {
+            error!("Failed to parse gallery image: {e:?}");
+            sentry::capture_error(&e);
+            GalleryError::from(e)
+        })
+    }
+
+    pub async fn put_gallery_image(&self, image: &GalleryImage) -> Result<(), GalleryError> {
+        self.client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(image.try_into()?))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                GalleryError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    pub async fn set_gallery_alt_text(
+        &self,
+        event_id: Uuid,
+        image_id: Uuid,
+        alt_text: &HashMap<String, String>,
+    ) -> Result<(), GalleryError> {
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::gallery_image_key(event_id, image_id)))
+            .update_expression("SET #A = :alt")
+            .condition_expression("attribute_exists(PK)")
+            .expression_attribute_names("#A", columns::ALT_TEXT_COLUMN)
+            .expression_attribute_values(
+                ":alt",
+                nested_object_attribute(columns::ALT_TEXT_COLUMN, alt_text)?,
+            )
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    return GalleryError::NotFound;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                GalleryError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    /// Shows the images in the order of `image_ids`, which must list every image of the gallery
+    pub async fn reorder_gallery(
+        &self,
+        event_id: Uuid,
+        image_ids: &[Uuid],
+    ) -> Result<(), GalleryError> {
+        if image_ids.is_empty() {
+            return Ok(());
+        }
+
+        let mut transaction = self.client.transact_write_items();
+        for (position, image_id) in image_ids.iter().enumerate() {
+            let update = Update::builder()
+                .table_name(self.table_name)
+                .set_key(Some(Self::gallery_image_key(event_id, *image_id)))
+                .update_expression("SET #P = :position")
+                .condition_expression("attribute_exists(PK)")
+                .expression_attribute_names("#P", columns::GALLERY_POSITION_COLUMN)
+                .expression_attribute_values(":position", AttributeValue::N(position.to_string()))
+                .build()
+                .map_err(|e| {
+                    error!("Failed to build transaction item: {e:?}");
+                    sentry::capture_error(&e);
+                    GalleryError::from(DatabaseQueryFailed)
+                })?;
+            transaction =
+                transaction.transact_items(TransactWriteItem::builder().update(update).build());
+        }
+
+        transaction.send().await.map_err(|s| {
+            if failed_transaction_condition(s.as_service_error()).is_some() {
+                tracing::debug!("Gallery of {event_id} changed while reordering");
+                return GalleryError::NotFound;
+            }
+            error!("Failed to query database: {s:?}");
+            sentry::capture_error(&s);
+            GalleryError::from(DatabaseQueryFailed)
+        })?;
+
+        Ok(())
+    }
+
+    pub async fn remove_gallery_image(
+        &self,
+        event_id: Uuid,
+        image_id: Uuid,
+    ) -> Result<GalleryImage, GalleryError> {
+        let res = self
+            .client
+            .delete_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::gallery_image_key(event_id, image_id)))
+            .condition_expression("attribute_exists(PK)")
+            .return_values(ReturnValue::AllOld)
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    return GalleryError::NotFound;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                GalleryError::from(DatabaseQueryFailed)
+            })?;
+
+        let item = res.attributes.ok_or(GalleryError::NotFound)?;
+        GalleryImage::try_from(&item).map_err(|e| {
+            error!("Failed to parse gallery image: {e:?}");
+            sentry::capture_error(&e);
+            GalleryError::from(e)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use crate::{
+        events::queries::DynamodbQueries,
+        gallery::{errors::GalleryError, models::GalleryImage},
+        test_fixtures::{init_dynamodb, insert_test_event},
+    };
+
+    fn gallery_image(event_id: Uuid, position: u32) -> GalleryImage {
+        GalleryImage {
+            event_id,
+            image_id: Uuid::new_v4(),
+            position,
+            alt_text: HashMap::from([("en".to_owned(), format!("Image {position}"))]),
+            variants: vec![],
+            focal_point: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reorder_and_remove_gallery_images() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let images = [gallery_image(event_id, 0), gallery_image(event_id, 1)];
+        for image in &images {
+            queries
+                .put_gallery_image(image)
+                .await
+                .expect("Failed to add gallery image");
+        }
+
+        queries
+            .reorder_gallery(event_id, &[images[1].image_id, images[0].image_id])
+            .await
+            .expect("Failed to reorder gallery");
+        let gallery = queries
+            .get_gallery(event_id)
+            .await
+            .expect("Failed to get gallery");
+        let order: Vec<Uuid> = gallery.iter().map(|image| image.image_id).collect();
+        assert_eq!(order, [images[1].image_id, images[0].image_id]);
+        assert_eq!(gallery[1].alt_text, images[0].alt_text);
+
+        let removed = queries
+            .remove_gallery_image(event_id, images[0].image_id)
+            .await
+            .expect("Failed to remove gallery image");
+        assert_eq!(removed.image_id, images[0].image_id);
+        assert!(matches!(
+            queries
+                .remove_gallery_image(event_id, images[0].image_id)
+                .await,
+            Err(GalleryError::NotFound)
+        ));
+        // The event itself is not part of the gallery
+        assert_eq!(queries.get_gallery(event_id).await.unwrap().len(), 1);
+    }
+}
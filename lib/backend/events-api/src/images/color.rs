@@ -0,0 +1,177 @@
+//! Converts images with an embedded ICC profile to sRGB. Stored images carry no profile, so
+//! browsers show them as sRGB, and photos in wider color spaces such as Display P3 would
+//! otherwise look washed out.
+//!
+//! Only RGB profiles are converted, which is what cameras and phones embed. Images with other
+//! profiles, or profiles that can't be read, are kept as they are.
+
+use image::DynamicImage;
+use moxcms::{ColorProfile, DataColorSpace, Layout, Transform8BitExecutor, TransformOptions};
+
+/// Colors sampled to tell whether a profile is sRGB in all but name
+const SAMPLE_STEPS: [u8; 5] = [0, 64, 128, 192, 255];
+
+/// Converts the image from the color space of the `icc` profile to sRGB. Colors sRGB cannot
+/// show are clipped.
+pub fn convert_to_srgb(image: DynamicImage, icc: &[u8]) -> DynamicImage {
+    let profile = match ColorProfile::new_from_slice(icc) {
+        Ok(profile) if profile.color_space == DataColorSpace::Rgb => profile,
+        Ok(_) => {
+            tracing::debug!("Keeping colors of image with a color profile that is not RGB");
+            return image;
+        }
+        Err(e) => {
+            tracing::debug!("Keeping colors of image with an unsupported color profile: {e:?}");
+            return image;
+        }
+    };
+    let layout = if image.color().has_alpha() {
+        Layout::Rgba
+    } else {
+        Layout::Rgb
+    };
+    let transform = match profile.create_transform_8bit(
+        layout,
+        &ColorProfile::new_srgb(),
+        layout,
+        TransformOptions::default(),
+    ) {
+        Ok(transform) => transform,
+        Err(e) => {
+            tracing::debug!("Keeping colors of image the color profile can't convert: {e:?}");
+            return image;
+        }
+    };
+
+    // Converting would only add rounding errors to images that already are in sRGB
+    let mut samples = Vec::new();
+    for r in SAMPLE_STEPS {
+        for g in SAMPLE_STEPS {
+            for b in SAMPLE_STEPS {
+                samples.extend([r, g, b]);
+                if layout == Layout::Rgba {
+                    samples.push(u8::MAX);
+                }
+            }
+        }
+    }
+    let mut converted_samples = vec![0; samples.len()];
+    let unchanged = transform
+        .transform(&samples, &mut converted_samples)
+        .is_ok_and(|()| {
+            samples
+                .iter()
+                .zip(&converted_samples)
+                .all(|(sample, converted)| sample.abs_diff(*converted) <= 1)
+        });
+    if unchanged {
+        return image;
+    }
+    tracing::debug!("Converting image to sRGB");
+
+    let width = image.width() as usize;
+    if layout == Layout::Rgba {
+        let mut pixels = image.into_rgba8();
+        convert_rows(&mut pixels, width * 4, &*transform);
+        DynamicImage::ImageRgba8(pixels)
+    } else {
+        let mut pixels = image.into_rgb8();
+        convert_rows(&mut pixels, width * 3, &*transform);
+        DynamicImage::ImageRgb8(pixels)
+    }
+}
+
+/// Converts the pixels a row at a time, as the transform can't write over its input
+fn convert_rows(pixels: &mut [u8], row_length: usize, transform: &Transform8BitExecutor) {
+    let mut row = vec![0; row_length];
+    for converted in pixels.chunks_mut(row_length) {
+        row[..converted.len()].copy_from_slice(converted);
+        if let Err(e) = transform.transform(&row[..converted.len()], converted) {
+            tracing::error!("Failed to convert image to sRGB: {e:?}");
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use image::{DynamicImage, GenericImageView, Rgb, RgbImage, Rgba, RgbaImage};
+    use moxcms::ColorProfile;
+    use rstest::rstest;
+
+    use super::convert_to_srgb;
+
+    /// The profile phones embed in their photos
+    pub fn display_p3_profile() -> Vec<u8> {
+        ColorProfile::new_display_p3().encode().unwrap()
+    }
+
+    /// The profile cameras embed when set to shoot in Adobe RGB
+    fn adobe_rgb_profile() -> Vec<u8> {
+        ColorProfile::new_adobe_rgb().encode().unwrap()
+    }
+
+    fn convert_pixel(pixel: [u8; 3], icc: &[u8]) -> [u8; 3] {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb(pixel)));
+        let [r, g, b, _] = convert_to_srgb(image, icc).get_pixel(0, 0).0;
+        [r, g, b]
+    }
+
+    fn assert_close(actual: [u8; 3], expected: [u8; 3]) {
+        let difference = actual
+            .iter()
+            .zip(expected)
+            .map(|(value, expected)| value.abs_diff(expected))
+            .max();
+        assert!(difference <= Some(2), "{actual:?} != {expected:?}");
+    }
+
+    #[test]
+    fn test_srgb_profile_is_kept() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(16, 16, |x, y| {
+            Rgb([x as u8 * 16, y as u8 * 16, 100])
+        }));
+        let icc = ColorProfile::new_srgb().encode().unwrap();
+        assert_eq!(convert_to_srgb(image.clone(), &icc), image);
+    }
+
+    // Expected colors are worked out from the primaries of the color spaces
+    #[rstest]
+    // Gray is the same in both
+    #[case([128, 128, 128], [128, 128, 128])]
+    // The reddest red of Display P3 is beyond sRGB, and clipped
+    #[case([255, 0, 0], [255, 0, 0])]
+    #[case([200, 100, 50], [215, 93, 31])]
+    #[case([60, 160, 90], [0, 163, 82])]
+    fn test_convert_display_p3(#[case] pixel: [u8; 3], #[case] expected: [u8; 3]) {
+        assert_close(convert_pixel(pixel, &display_p3_profile()), expected);
+    }
+
+    #[rstest]
+    // Adobe RGB has a plain 2.2 gamma, a little darker than sRGB in the shadows
+    #[case([128, 128, 128], [129, 129, 129])]
+    #[case([0, 255, 0], [0, 255, 0])]
+    #[case([200, 100, 50], [227, 100, 42])]
+    #[case([60, 160, 90], [0, 162, 85])]
+    fn test_convert_adobe_rgb(#[case] pixel: [u8; 3], #[case] expected: [u8; 3]) {
+        assert_close(convert_pixel(pixel, &adobe_rgb_profile()), expected);
+    }
+
+    #[test]
+    fn test_convert_keeps_alpha() {
+        let image = DynamicImage::ImageRgba8(RgbaImage::from_pixel(4, 2, Rgba([200, 100, 50, 70])));
+        let converted = convert_to_srgb(image, &display_p3_profile());
+        assert_eq!(converted.dimensions(), (4, 2));
+        let [r, g, b, a] = converted.get_pixel(3, 1).0;
+        assert_close([r, g, b], [215, 93, 31]);
+        assert_eq!(a, 70);
+    }
+
+    #[rstest]
+    #[case(b"not a profile".to_vec())]
+    #[case(ColorProfile::new_gray_with_gamma(2.2).encode().unwrap())]
+    fn test_unsupported_profile(#[case] icc: Vec<u8>) {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(1, 1, Rgb([200, 100, 50])));
+        assert_eq!(convert_to_srgb(image.clone(), &icc), image);
+    }
+}
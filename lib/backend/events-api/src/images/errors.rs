@@ -1,44 +1,59 @@
+use std::collections::HashMap;
+
 use axum::http;
 
 use crate::api::error::{error_codes, RestError};
 
+use super::formats::UploadFormat;
+
 #[derive(thiserror::Error, Debug)]
 pub enum ImageUploadError {
     #[error("Image too large")]
     ImageTooLarge,
     #[error("Image too small")]
     ImageTooSmall,
-    #[error("Can't guess image format")]
-    ImageTypeGuessError,
     #[error("Image format not supported")]
     UnsupportedImageFormat,
+    #[error("Image is not in the format it is declared as")]
+    FormatMismatch,
     #[error("Image decoding error")]
     ImageDecodingError,
     #[error("Image encoding error")]
     ImageEncodingError,
     #[error("Storage error")]
     StorageError,
+    #[error("Image processing task failed")]
+    ProcessingFailed,
+    #[error("Invalid crop or focal point")]
+    InvalidFraming,
 }
 
+#[derive(thiserror::Error, Debug)]
+#[error("Failed to delete images")]
+pub struct ImageDeletionError;
+
 impl From<ImageUploadError> for RestError {
     fn from(val: ImageUploadError) -> Self {
         match val {
             ImageUploadError::UnsupportedImageFormat => RestError {
                 status_code: http::StatusCode::BAD_REQUEST,
                 error_code: error_codes::UNSUPPORTED_IMAGE_FORMAT.to_string(),
-                error_params: None,
+                error_params: Some(HashMap::from([(
+                    "formats".to_string(),
+                    UploadFormat::accepted_content_types(),
+                )])),
             },
-            ImageUploadError::ImageDecodingError => RestError {
+            ImageUploadError::FormatMismatch => RestError {
                 status_code: http::StatusCode::BAD_REQUEST,
-                error_code: error_codes::IMAGE_CONVERSION_ERROR.to_string(),
+                error_code: error_codes::IMAGE_FORMAT_MISMATCH.to_string(),
                 error_params: None,
             },
-            ImageUploadError::ImageEncodingError => RestError {
+            ImageUploadError::ImageDecodingError => RestError {
                 status_code: http::StatusCode::BAD_REQUEST,
                 error_code: error_codes::IMAGE_CONVERSION_ERROR.to_string(),
                 error_params: None,
             },
-            ImageUploadError::ImageTypeGuessError => RestError {
+            ImageUploadError::ImageEncodingError => RestError {
                 status_code: http::StatusCode::BAD_REQUEST,
                 error_code: error_codes::IMAGE_CONVERSION_ERROR.to_string(),
                 error_params: None,
@@ -58,6 +73,16 @@ impl From<ImageUploadError> for RestError {
                 error_code: error_codes::IMAGE_STORAGE_ERROR.to_string(),
                 error_params: None,
             },
+            ImageUploadError::ProcessingFailed => RestError {
+                status_code: http::StatusCode::INTERNAL_SERVER_ERROR,
+                error_code: error_codes::IMAGE_CONVERSION_ERROR.to_string(),
+                error_params: None,
+            },
+            ImageUploadError::InvalidFraming => RestError {
+                status_code: http::StatusCode::BAD_REQUEST,
+                error_code: error_codes::INVALID_IMAGE_FRAMING.to_string(),
+                error_params: None,
+            },
         }
     }
 }
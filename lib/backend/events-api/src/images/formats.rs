@@ -0,0 +1,130 @@
+use super::errors::ImageUploadError;
+
+/// Formats images can be uploaded in. Whatever the format, images are stored as AVIF and JPEG.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UploadFormat {
+    Png,
+    Jpeg,
+    WebP,
+    /// Only the first frame of animated GIFs is kept
+    Gif,
+    Heic,
+}
+
+/// Brands of the `ftyp` box HEIC files start with
+const HEIC_BRANDS: [&[u8; 4]; 6] = [b"heic", b"heix", b"heim", b"heis", b"hevc", b"hevx"];
+
+impl UploadFormat {
+    /// The formats uploads may be in
+    pub const ACCEPTED: &[UploadFormat] = &[
+        UploadFormat::Png,
+        UploadFormat::Jpeg,
+        UploadFormat::WebP,
+        UploadFormat::Gif,
+        UploadFormat::Heic,
+    ];
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            UploadFormat::Png => "image/png",
+            UploadFormat::Jpeg => "image/jpeg",
+            UploadFormat::WebP => "image/webp",
+            UploadFormat::Gif => "image/gif",
+            UploadFormat::Heic => "image/heic",
+        }
+    }
+
+    /// The accepted format an image declared as `content_type` is in
+    pub fn from_content_type(content_type: &str) -> Result<Self, ImageUploadError> {
+        let essence = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        match essence.as_str() {
+            "image/png" => Ok(UploadFormat::Png),
+            "image/jpeg" => Ok(UploadFormat::Jpeg),
+            "image/webp" => Ok(UploadFormat::WebP),
+            "image/gif" => Ok(UploadFormat::Gif),
+            "image/heic" | "image/heif" => Ok(UploadFormat::Heic),
+            _ => Err(ImageUploadError::UnsupportedImageFormat),
+        }
+    }
+
+    /// The format the data is actually in, judging by how it starts
+    pub fn sniff(data: &[u8]) -> Option<Self> {
+        if data.get(4..8) == Some(b"ftyp")
+            && data
+                .get(8..12)
+                .is_some_and(|brand| HEIC_BRANDS.iter().any(|heic| brand == *heic))
+        {
+            return Some(UploadFormat::Heic);
+        }
+        match image::guess_format(data).ok()? {
+            image::ImageFormat::Png => Some(UploadFormat::Png),
+            image::ImageFormat::Jpeg => Some(UploadFormat::Jpeg),
+            image::ImageFormat::WebP => Some(UploadFormat::WebP),
+            image::ImageFormat::Gif => Some(UploadFormat::Gif),
+            _ => None,
+        }
+    }
+
+    /// The format for the image crate, which decodes all but HEIC
+    pub fn image_format(self) -> Option<image::ImageFormat> {
+        match self {
+            UploadFormat::Png => Some(image::ImageFormat::Png),
+            UploadFormat::Jpeg => Some(image::ImageFormat::Jpeg),
+            UploadFormat::WebP => Some(image::ImageFormat::WebP),
+            UploadFormat::Gif => Some(image::ImageFormat::Gif),
+            UploadFormat::Heic => None,
+        }
+    }
+
+    /// Content types of the accepted formats, for error messages
+    pub fn accepted_content_types() -> String {
+        Self::ACCEPTED
+            .iter()
+            .map(|format| format.content_type())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use crate::test_fixtures::encoded_image;
+
+    use super::UploadFormat;
+
+    #[rstest]
+    #[case(image::ImageFormat::Png, UploadFormat::Png)]
+    #[case(image::ImageFormat::Jpeg, UploadFormat::Jpeg)]
+    #[case(image::ImageFormat::WebP, UploadFormat::WebP)]
+    #[case(image::ImageFormat::Gif, UploadFormat::Gif)]
+    fn test_sniff(#[case] format: image::ImageFormat, #[case] expected: UploadFormat) {
+        let data = encoded_image(&image::DynamicImage::new_rgb8(4, 4), format);
+        assert_eq!(UploadFormat::sniff(&data), Some(expected));
+    }
+
+    #[rstest]
+    #[case(b"\0\0\0\x18ftypheic\0\0\0\0mif1heic", Some(UploadFormat::Heic))]
+    // AVIF shares the container of HEIC, but is not accepted
+    #[case(b"\0\0\0\x1cftypavif\0\0\0\0avifmif1miaf", None)]
+    #[case(b"not an image", None)]
+    fn test_sniff_signature(#[case] data: &[u8], #[case] expected: Option<UploadFormat>) {
+        assert_eq!(UploadFormat::sniff(data), expected);
+    }
+
+    #[rstest]
+    #[case("image/png", Some(UploadFormat::Png))]
+    #[case("IMAGE/WEBP", Some(UploadFormat::WebP))]
+    #[case("image/gif; charset=binary", Some(UploadFormat::Gif))]
+    #[case("image/svg+xml", None)]
+    #[case("image/heif", Some(UploadFormat::Heic))]
+    fn test_from_content_type(#[case] content_type: &str, #[case] expected: Option<UploadFormat>) {
+        assert_eq!(UploadFormat::from_content_type(content_type).ok(), expected);
+    }
+}
@@ -0,0 +1,224 @@
+//! How the uploader wants an image framed. Coordinates are fractions of the width and height of
+//! the upright image, from its top left corner, so they don't depend on the size it is shown in.
+
+use std::borrow::Cow;
+
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+
+use super::errors::ImageUploadError;
+
+/// Neither side of a stored image is more than this many times the other. Pages show images in
+/// frames of a less extreme shape, so what lies beyond would never be seen.
+pub const MAX_ASPECT_RATIO: f32 = 2.0;
+
+/// The part of the image to keep
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct CropRectangle {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+/// Where the subject of the image is, which should stay in view when the image is cropped to
+/// fit a different shape
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct FocalPoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl FocalPoint {
+    const CENTER: FocalPoint = FocalPoint { x: 0.5, y: 0.5 };
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageFraming {
+    pub crop: Option<CropRectangle>,
+    /// Within the cropped image
+    pub focal_point: Option<FocalPoint>,
+}
+
+fn is_fraction(value: f32) -> bool {
+    (0.0..=1.0).contains(&value)
+}
+
+impl ImageFraming {
+    pub fn validate(&self) -> Result<(), ImageUploadError> {
+        if let Some(crop) = &self.crop {
+            let within_image = is_fraction(crop.x)
+                && is_fraction(crop.y)
+                && crop.width > 0.0
+                && crop.height > 0.0
+                // Leeway for rounding by clients
+                && crop.x + crop.width <= 1.001
+                && crop.y + crop.height <= 1.001;
+            if !within_image {
+                return Err(ImageUploadError::InvalidFraming);
+            }
+        }
+        if let Some(focal_point) = &self.focal_point {
+            if !is_fraction(focal_point.x) || !is_fraction(focal_point.y) {
+                return Err(ImageUploadError::InvalidFraming);
+            }
+        }
+        Ok(())
+    }
+
+    /// The part of the image to keep, which is all of it without a crop
+    pub fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let Some(crop) = &self.crop else {
+            return image;
+        };
+        let (width, height) = image.dimensions();
+        let scale = |fraction: f32, dimension: u32| {
+            ((fraction * dimension as f32).round() as u32).min(dimension)
+        };
+        let (left, top) = (scale(crop.x, width), scale(crop.y, height));
+        let right = scale(crop.x + crop.width, width).max(left + 1);
+        let bottom = scale(crop.y + crop.height, height).max(top + 1);
+        tracing::debug!("Cropping image to {left},{top} - {right},{bottom}");
+        image.crop_imm(left, top, right - left, bottom - top)
+    }
+}
+
+/// Along a side of `length` pixels, where the `kept` pixels start that have `focus` as close to
+/// their middle as the side allows, and where `focus` is within them
+fn keep_around(focus: f32, length: u32, kept: u32) -> (u32, f32) {
+    let start = focus * length as f32 - kept as f32 / 2.0;
+    let start = (start.round().max(0.0) as u32).min(length - kept);
+    let focus = (focus * length as f32 - start as f32) / kept as f32;
+    (start, focus.clamp(0.0, 1.0))
+}
+
+/// Crops images of a more extreme shape than `MAX_ASPECT_RATIO` to it, around their focal point,
+/// or their middle without one. Gives the focal point within the cropped image.
+pub fn limit_aspect_ratio(
+    image: &DynamicImage,
+    focal_point: Option<FocalPoint>,
+) -> (Cow<'_, DynamicImage>, Option<FocalPoint>) {
+    let (width, height) = image.dimensions();
+    let focus = focal_point.unwrap_or(FocalPoint::CENTER);
+    let kept = |short: u32, long: u32| ((short as f32 * MAX_ASPECT_RATIO).round() as u32).min(long);
+    if width as f32 > height as f32 * MAX_ASPECT_RATIO {
+        let kept = kept(height, width);
+        let (left, x) = keep_around(focus.x, width, kept);
+        tracing::debug!("Cropping image to {kept} of its {width} pixels wide");
+        let focal_point = focal_point.map(|focal_point| FocalPoint { x, ..focal_point });
+        (
+            Cow::Owned(image.crop_imm(left, 0, kept, height)),
+            focal_point,
+        )
+    } else if height as f32 > width as f32 * MAX_ASPECT_RATIO {
+        let kept = kept(width, height);
+        let (top, y) = keep_around(focus.y, height, kept);
+        tracing::debug!("Cropping image to {kept} of its {height} pixels high");
+        let focal_point = focal_point.map(|focal_point| FocalPoint { y, ..focal_point });
+        (Cow::Owned(image.crop_imm(0, top, width, kept)), focal_point)
+    } else {
+        (Cow::Borrowed(image), focal_point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::{DynamicImage, GenericImageView, Rgb, RgbImage};
+    use rstest::rstest;
+
+    use super::{limit_aspect_ratio, CropRectangle, FocalPoint, ImageFraming};
+
+    fn crop(x: f32, y: f32, width: f32, height: f32) -> Option<CropRectangle> {
+        Some(CropRectangle {
+            x,
+            y,
+            width,
+            height,
+        })
+    }
+
+    #[rstest]
+    #[case(crop(0.0, 0.0, 1.0, 1.0), None, true)]
+    #[case(crop(0.25, 0.5, 0.75, 0.5), Some(FocalPoint { x: 0.5, y: 1.0 }), true)]
+    #[case(crop(0.5, 0.0, 0.6, 1.0), None, false)]
+    #[case(crop(0.0, 0.0, 0.0, 1.0), None, false)]
+    #[case(crop(-0.1, 0.0, 0.5, 0.5), None, false)]
+    #[case(None, Some(FocalPoint { x: 1.5, y: 0.5 }), false)]
+    fn test_validate(
+        #[case] crop: Option<CropRectangle>,
+        #[case] focal_point: Option<FocalPoint>,
+        #[case] valid: bool,
+    ) {
+        let framing = ImageFraming { crop, focal_point };
+        assert_eq!(framing.validate().is_ok(), valid);
+    }
+
+    #[test]
+    fn test_apply() {
+        // Red on the right half
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(200, 100, |x, _| {
+            if x < 100 {
+                Rgb([0, 0, 255])
+            } else {
+                Rgb([255, 0, 0])
+            }
+        }));
+        let framing = ImageFraming {
+            crop: crop(0.5, 0.25, 0.5, 0.5),
+            focal_point: None,
+        };
+
+        let cropped = framing.apply(image);
+        assert_eq!(cropped.dimensions(), (100, 50));
+        assert!(cropped
+            .to_rgb8()
+            .pixels()
+            .all(|pixel| *pixel == Rgb([255, 0, 0])));
+    }
+
+    #[rstest]
+    // Within the limit
+    #[case((400, 200), None, (400, 200), None)]
+    #[case((900, 100), None, (200, 100), None)]
+    // The focal point is kept in the middle where the image allows, and moves along
+    #[case((900, 100), Some(FocalPoint { x: 0.5, y: 0.25 }), (200, 100), Some(FocalPoint { x: 0.5, y: 0.25 }))]
+    #[case((900, 100), Some(FocalPoint { x: 0.9, y: 0.5 }), (200, 100), Some(FocalPoint { x: 0.55, y: 0.5 }))]
+    #[case((100, 900), Some(FocalPoint { x: 0.5, y: 0.0 }), (100, 200), Some(FocalPoint { x: 0.5, y: 0.0 }))]
+    fn test_limit_aspect_ratio(
+        #[case] dimensions: (u32, u32),
+        #[case] focal_point: Option<FocalPoint>,
+        #[case] expected_dimensions: (u32, u32),
+        #[case] expected_focal_point: Option<FocalPoint>,
+    ) {
+        let image = DynamicImage::new_rgb8(dimensions.0, dimensions.1);
+        let (cropped, focal_point) = limit_aspect_ratio(&image, focal_point);
+        assert_eq!(cropped.dimensions(), expected_dimensions);
+        let close = |left: FocalPoint, right: FocalPoint| {
+            (left.x - right.x).abs() < 1e-4 && (left.y - right.y).abs() < 1e-4
+        };
+        match (focal_point, expected_focal_point) {
+            (Some(focal_point), Some(expected)) => assert!(
+                close(focal_point, expected),
+                "{focal_point:?} != {expected:?}"
+            ),
+            (focal_point, expected) => assert_eq!(focal_point, expected),
+        }
+    }
+
+    #[test]
+    fn test_limit_aspect_ratio_keeps_focus() {
+        // Red in the right tenth of a panorama
+        let image = DynamicImage::ImageRgb8(RgbImage::from_fn(1000, 100, |x, _| {
+            if x >= 900 {
+                Rgb([255, 0, 0])
+            } else {
+                Rgb([0, 0, 255])
+            }
+        }));
+        let (cropped, _) = limit_aspect_ratio(&image, Some(FocalPoint { x: 0.95, y: 0.5 }));
+        assert_eq!(cropped.dimensions(), (200, 100));
+        assert_eq!(cropped.get_pixel(150, 50).0, [255, 0, 0, 255]);
+        assert_eq!(cropped.get_pixel(50, 50).0, [0, 0, 255, 255]);
+    }
+}
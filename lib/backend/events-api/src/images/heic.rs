@@ -0,0 +1,54 @@
+//! Decodes HEIC, the format iPhones take photos in. The image crate has no HEVC decoder, so this
+//! uses heic-decoder, which is written in Rust and needs no system libraries to build.
+
+use heic_decoder::HeifError;
+use image::{DynamicImage, RgbImage, RgbaImage};
+use tracing::error;
+
+use super::{errors::ImageUploadError, MAX_DECODED_BYTES, MAX_DECODED_DIMENSION};
+
+/// Decodes the primary image of the file, with its ICC profile if it has one. The decoder turns
+/// the image upright itself, from the rotation and mirroring the file specifies.
+pub fn read_heic(data: &[u8]) -> Result<(DynamicImage, Option<Vec<u8>>), ImageUploadError> {
+    let decoding_error = |e: HeifError| {
+        if let HeifError::LimitExceeded(limit) = e {
+            tracing::debug!("Refused to decode HEIC image: {limit}");
+            return ImageUploadError::ImageTooLarge;
+        }
+        sentry::capture_error(&e);
+        error!("Failed to decode HEIC image: {e:?}");
+        ImageUploadError::ImageDecodingError
+    };
+    // The size is read from the headers, so huge images are refused before they are decoded
+    let info = heic_decoder::probe(data).map_err(decoding_error)?;
+    let max_dimension = MAX_DECODED_DIMENSION as usize;
+    let channels = if info.alpha_bit_depth.is_some() { 4 } else { 3 };
+    if info.width > max_dimension
+        || info.height > max_dimension
+        || (info.width * info.height * channels) as u64 > MAX_DECODED_BYTES
+    {
+        tracing::debug!(
+            "Refused to decode {}x{} HEIC image",
+            info.width,
+            info.height
+        );
+        return Err(ImageUploadError::ImageTooLarge);
+    }
+    let decoded = heic_decoder::decode(data).map_err(decoding_error)?;
+    let icc_profile = decoded.metadata.icc_profile.clone();
+
+    let image = if decoded.alpha.is_some() {
+        let pixels = decoded.to_rgba8().map_err(decoding_error)?;
+        RgbaImage::from_raw(pixels.width as u32, pixels.height as u32, pixels.data)
+            .map(DynamicImage::ImageRgba8)
+    } else {
+        let pixels = decoded.to_rgb8().map_err(decoding_error)?;
+        RgbImage::from_raw(pixels.width as u32, pixels.height as u32, pixels.data)
+            .map(DynamicImage::ImageRgb8)
+    };
+
+    Ok((
+        image.ok_or(ImageUploadError::ImageDecodingError)?,
+        icc_profile,
+    ))
+}
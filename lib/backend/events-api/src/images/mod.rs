@@ -1,21 +1,175 @@
-use std::io::Cursor;
+use std::{io::Cursor, num::NonZeroUsize, sync::LazyLock, thread::available_parallelism};
 
-use aws_sdk_s3::primitives::{ByteStream, SdkBody};
-use errors::ImageUploadError;
-use image::{DynamicImage, GenericImageView};
-use tracing::info;
+use aws_sdk_s3::{
+    primitives::{ByteStream, SdkBody},
+    types::{Delete, ObjectIdentifier},
+};
+use bytes::Bytes;
+use errors::{ImageDeletionError, ImageUploadError};
+use formats::UploadFormat;
+use framing::{FocalPoint, ImageFraming};
+use image::{
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder},
+    imageops::FilterType,
+    metadata::Orientation,
+    DynamicImage, GenericImageView, ImageDecoder, ImageReader, Limits,
+};
+use settings::ImageSettings;
+use tokio::sync::Semaphore;
+use tracing::{error, info};
 use uuid::Uuid;
+use variants::{ImageFormat, ImageSet, ImageSize, ImageVariant};
 
-use crate::configuration::{EVENT_IMAGES_BUCKET_NAME, EVENT_IMAGES_BUCKET_PREFIX};
+use crate::{
+    configuration::{EVENT_IMAGES_BUCKET_NAME, EVENT_IMAGES_BUCKET_PREFIX},
+    events::queries::DynamodbQueries,
+    gallery::errors::GalleryError,
+};
 
+mod color;
 pub mod errors;
+pub mod formats;
+pub mod framing;
+mod heic;
+pub mod settings;
+pub mod variants;
 
-const MAX_IMAGE_DIMENSION: u32 = 1280;
 const MIN_IMAGE_DIMENSION: u32 = 800;
+const JPEG_QUALITY: u8 = 80;
+pub const MAX_IMAGE_SIZE: usize = 1024 * 1024 * 10;
+/// Widest or tallest image decoded
+const MAX_DECODED_DIMENSION: u32 = 16_384;
+/// Most memory decoding an image may take, enough for a 48 megapixel photo with alpha. Small
+/// files can decode to huge images, so the file size alone does not bound this.
+const MAX_DECODED_BYTES: u64 = 200 * 1024 * 1024;
 
-pub fn is_image_within_bounds(image: &DynamicImage) -> bool {
+/// One image is worked on per vCPU at most. The blocking pool grows to hundreds of threads, and
+/// a decoded 12 megapixel photo alone takes about 50 MB, so more at once would only share the
+/// CPU and run the Lambda out of memory.
+static IMAGE_WORK_PERMITS: LazyLock<Semaphore> =
+    LazyLock::new(|| Semaphore::new(available_parallelism().map_or(1, NonZeroUsize::get)));
+
+/// Runs CPU heavy image work on the blocking thread pool, so it does not hold up the requests
+/// the async workers serve meanwhile. Work waits for a permit when every vCPU is busy.
+async fn run_blocking<T, F>(work: F) -> Result<T, ImageUploadError>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, ImageUploadError> + Send + 'static,
+{
+    let _permit = IMAGE_WORK_PERMITS
+        .acquire()
+        .await
+        .map_err(|_| ImageUploadError::ProcessingFailed)?;
+    tokio::task::spawn_blocking(work).await.map_err(|e| {
+        error!("Image processing task failed: {e:?}");
+        sentry::capture_error(&e);
+        ImageUploadError::ProcessingFailed
+    })?
+}
+
+/// Decodes the image, and crops it as the uploader chose
+pub async fn decode_image(
+    body: Bytes,
+    declared: UploadFormat,
+    framing: ImageFraming,
+) -> Result<DynamicImage, ImageUploadError> {
+    run_blocking(move || Ok(framing.apply(read_image(body, declared)?))).await
+}
+
+/// Decodes the image, turned upright as its EXIF orientation says and in sRGB colors. Nothing
+/// else of its metadata is kept, as the encoders write none. The image must be in the format it
+/// was `declared` as.
+pub fn read_image(body: Bytes, declared: UploadFormat) -> Result<DynamicImage, ImageUploadError> {
+    tracing::debug!("Reading image from body");
+    let format = UploadFormat::sniff(&body).ok_or(ImageUploadError::UnsupportedImageFormat)?;
+    if format != declared {
+        tracing::debug!("Image declared as {declared:?} is {format:?}");
+        return Err(ImageUploadError::FormatMismatch);
+    }
+    let (mut image, icc_profile) = match format.image_format() {
+        Some(format) => read_with_metadata(body, format)?,
+        None => heic::read_heic(&body)?,
+    };
+    if let Some(icc_profile) = icc_profile {
+        image = color::convert_to_srgb(image, &icc_profile);
+    }
+    tracing::debug!("Image read from body");
+
+    Ok(image)
+}
+
+/// Decodes an image the image crate supports, turned upright, and its ICC profile. Animated
+/// images give their first frame.
+fn read_with_metadata(
+    body: Bytes,
+    format: image::ImageFormat,
+) -> Result<(DynamicImage, Option<Vec<u8>>), ImageUploadError> {
+    let decoding_error = |e: image::ImageError| {
+        if let image::ImageError::Limits(e) = e {
+            tracing::debug!("Refused to decode image: {e}");
+            return ImageUploadError::ImageTooLarge;
+        }
+        sentry::capture_error(&e);
+        tracing::error!("Failed to decode image: {:?}", e);
+        ImageUploadError::ImageDecodingError
+    };
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_DECODED_DIMENSION);
+    limits.max_image_height = Some(MAX_DECODED_DIMENSION);
+    limits.max_alloc = Some(MAX_DECODED_BYTES);
+    let mut reader = ImageReader::with_format(Cursor::new(body), format);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().map_err(decoding_error)?;
+    // Broken metadata is no reason to refuse an image that decodes
+    let orientation = decoder.orientation().unwrap_or(Orientation::NoTransforms);
+    let icc_profile = decoder.icc_profile().ok().flatten();
+    let mut image = DynamicImage::from_decoder(decoder).map_err(decoding_error)?;
+    image.apply_orientation(orientation);
+
+    Ok((image, icc_profile))
+}
+
+/// Where clients load the image from
+pub fn image_set(
+    event: Uuid,
+    image: Uuid,
+    variants: &[ImageVariant],
+    focal_point: Option<FocalPoint>,
+) -> ImageSet {
+    ImageSet {
+        focal_point,
+        ..ImageSet::new(
+            &format!("/{}", &*EVENT_IMAGES_BUCKET_PREFIX),
+            event,
+            image,
+            variants,
+        )
+    }
+}
+
+/// Names of the files an image is stored as, within the directory of its event
+pub fn image_file_names(image: Uuid, variants: &[ImageVariant]) -> Vec<String> {
+    if variants.is_empty() {
+        return vec![format!("{image}.avif")];
+    }
+    variants
+        .iter()
+        .map(|variant| variant.file_name(image))
+        .collect()
+}
+
+/// Keys of the objects an image is stored as
+fn image_keys(event: Uuid, image: Uuid, variants: &[ImageVariant]) -> Vec<String> {
+    let prefix = &*EVENT_IMAGES_BUCKET_PREFIX;
+    image_file_names(image, variants)
+        .into_iter()
+        .map(|file_name| format!("{prefix}/{event}/{file_name}"))
+        .collect()
+}
+
+pub fn is_image_within_bounds(image: &DynamicImage, max_dimension: u32) -> bool {
     let size = image.dimensions();
-    size.0 < MAX_IMAGE_DIMENSION && size.1 < MAX_IMAGE_DIMENSION
+    size.0 <= max_dimension && size.1 <= max_dimension
 }
 
 pub fn is_image_too_small(image: &DynamicImage) -> bool {
@@ -23,75 +177,444 @@ pub fn is_image_too_small(image: &DynamicImage) -> bool {
     size.0 < MIN_IMAGE_DIMENSION || size.1 < MIN_IMAGE_DIMENSION
 }
 
-pub fn assert_image_size(image: DynamicImage) -> DynamicImage {
-    if is_image_within_bounds(&image) {
-        return image;
+pub fn assert_image_size(
+    image: &DynamicImage,
+    max_dimension: u32,
+    filter: FilterType,
+) -> DynamicImage {
+    if is_image_within_bounds(image, max_dimension) {
+        return image.clone();
     }
 
-    info!(
-        "Resizing image to fit within {}x{}",
-        MAX_IMAGE_DIMENSION, MAX_IMAGE_DIMENSION
-    );
-    image.resize(
-        MAX_IMAGE_DIMENSION,
-        MAX_IMAGE_DIMENSION,
-        image::imageops::FilterType::CatmullRom,
-    )
+    info!("Resizing image to fit within {max_dimension}x{max_dimension}");
+    image.resize(max_dimension, max_dimension, filter)
 }
 
-pub async fn conform_image(image: DynamicImage) -> Result<Vec<u8>, ImageUploadError> {
-    let incoming_image = assert_image_size(image);
-
-    info!("Encoding file as avif");
+pub fn encode_image(
+    image: &DynamicImage,
+    format: ImageFormat,
+    settings: &ImageSettings,
+) -> Result<Vec<u8>, ImageUploadError> {
+    info!("Encoding file as {}", format.extension());
     let mut encoded_image: Vec<u8> = Vec::new();
-    incoming_image
-        .write_to(
-            &mut Cursor::new(&mut encoded_image),
-            image::ImageFormat::Avif,
-        )
-        .map_err(|e| {
-            sentry::capture_error(&e);
-            ImageUploadError::ImageEncodingError {}
-        })?;
-    info!("Encoded file as avif");
+    let result = match format {
+        ImageFormat::Avif => image.write_with_encoder(AvifEncoder::new_with_speed_quality(
+            &mut encoded_image,
+            settings.avif_speed,
+            settings.avif_quality,
+        )),
+        // JPEG has no alpha channel
+        ImageFormat::Jpeg => image
+            .to_rgb8()
+            .write_with_encoder(JpegEncoder::new_with_quality(
+                &mut encoded_image,
+                JPEG_QUALITY,
+            )),
+    };
+    result.map_err(|e| {
+        sentry::capture_error(&e);
+        ImageUploadError::ImageEncodingError {}
+    })?;
+    info!("Encoded file as {}", format.extension());
 
     Ok(encoded_image)
 }
 
+/// The image in every size, cropped around the focal point if its shape is too extreme, and the
+/// focal point within the variants. Sizes larger than the image are left out, except for the full
+/// size, which is the image itself when it is small enough.
+pub fn resize_to_variants(
+    image: &DynamicImage,
+    focal_point: Option<FocalPoint>,
+    filter: FilterType,
+) -> (Vec<(ImageSize, DynamicImage)>, Option<FocalPoint>) {
+    let (image, focal_point) = framing::limit_aspect_ratio(image, focal_point);
+    let (width, height) = image.dimensions();
+    let variants = ImageSize::ALL
+        .into_iter()
+        .filter(|size| *size == ImageSize::Full || width.max(height) > size.max_dimension())
+        .map(|size| {
+            (
+                size,
+                assert_image_size(&image, size.max_dimension(), filter),
+            )
+        })
+        .collect();
+    (variants, focal_point)
+}
+
+/// The encoded variants of an image, and its focal point within them
+pub struct RenderedImage {
+    pub variants: Vec<(ImageVariant, Vec<u8>)>,
+    pub focal_point: Option<FocalPoint>,
+}
+
+/// Renders the image in every size and format
+pub async fn conform_image(
+    image: DynamicImage,
+    focal_point: Option<FocalPoint>,
+) -> Result<RenderedImage, ImageUploadError> {
+    let settings = ImageSettings::configured();
+    run_blocking(move || render_variants(&image, focal_point, &settings)).await
+}
+
+pub fn render_variants(
+    image: &DynamicImage,
+    focal_point: Option<FocalPoint>,
+    settings: &ImageSettings,
+) -> Result<RenderedImage, ImageUploadError> {
+    let (resized, focal_point) = resize_to_variants(image, focal_point, settings.resize_filter);
+    let mut encoded = Vec::new();
+    for (size, resized) in resized {
+        for format in ImageFormat::ALL {
+            let variant = ImageVariant {
+                size,
+                format,
+                width: resized.width(),
+                height: resized.height(),
+            };
+            encoded.push((variant, encode_image(&resized, format, settings)?));
+        }
+    }
+
+    Ok(RenderedImage {
+        variants: encoded,
+        focal_point,
+    })
+}
+
 pub async fn upload_image(
     s3: &aws_sdk_s3::Client,
     event: Uuid,
-    image: Vec<u8>,
-) -> Result<Uuid, ImageUploadError> {
+    variants: Vec<(ImageVariant, Vec<u8>)>,
+) -> Result<(Uuid, Vec<ImageVariant>), ImageUploadError> {
     let new_image_id = Uuid::new_v4();
 
-    let body = SdkBody::from(image);
-    let image = ByteStream::from(body);
-    let path = format!(
-        "{prefix}/{event}/{id}.avif",
-        prefix = &*EVENT_IMAGES_BUCKET_PREFIX,
-        event = event,
-        id = new_image_id
-    );
-    s3.put_object()
+    let mut uploaded = Vec::with_capacity(variants.len());
+    for (variant, image) in variants {
+        let body = SdkBody::from(image);
+        let image = ByteStream::from(body);
+        let path = format!(
+            "{prefix}/{event}/{file_name}",
+            prefix = &*EVENT_IMAGES_BUCKET_PREFIX,
+            event = event,
+            file_name = variant.file_name(new_image_id)
+        );
+        s3.put_object()
+            .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+            .key(path)
+            .content_type(variant.format.mime_type())
+            .body(image)
+            .send()
+            .await
+            .map_err(|e| {
+                sentry::capture_error(&e);
+                ImageUploadError::StorageError
+            })?;
+        uploaded.push(variant);
+    }
+
+    Ok((new_image_id, uploaded))
+}
+
+/// Deletes the objects with the given keys, at most 1000 at a time as S3 allows
+pub async fn delete_objects(
+    s3: &aws_sdk_s3::Client,
+    keys: Vec<String>,
+) -> Result<(), ImageDeletionError> {
+    if keys.is_empty() {
+        return Ok(());
+    }
+    let objects = keys
+        .into_iter()
+        .map(|key| ObjectIdentifier::builder().key(key).build())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| {
+            error!("Failed to build object identifier: {e:?}");
+            ImageDeletionError
+        })?;
+
+    let delete = Delete::builder()
+        .set_objects(Some(objects))
+        .quiet(true)
+        .build()
+        .map_err(|e| {
+            error!("Failed to build image deletion: {e:?}");
+            ImageDeletionError
+        })?;
+    let res = s3
+        .delete_objects()
         .bucket(&*EVENT_IMAGES_BUCKET_NAME)
-        .key(path)
-        .body(image)
+        .delete(delete)
         .send()
         .await
         .map_err(|e| {
+            error!("Failed to delete images: {e:?}");
             sentry::capture_error(&e);
-            ImageUploadError::StorageError
+            ImageDeletionError
         })?;
+    if !res.errors().is_empty() {
+        error!("Failed to delete some images: {:?}", res.errors());
+        return Err(ImageDeletionError);
+    }
 
-    Ok(new_image_id)
+    Ok(())
+}
+
+/// Deletes every stored variant of an image
+pub async fn delete_image(
+    s3: &aws_sdk_s3::Client,
+    event: Uuid,
+    image: Uuid,
+    variants: &[ImageVariant],
+) -> Result<(), ImageDeletionError> {
+    delete_objects(s3, image_keys(event, image, variants)).await
+}
+
+/// Deletes the files of an image that is no longer the cover image of the event, unless the
+/// gallery still shows it. Failures are only reported, as the event is already updated and the
+/// image cleanup command removes whatever is left behind.
+pub async fn discard_cover_image(
+    s3: &aws_sdk_s3::Client,
+    dynamodb: &DynamodbQueries,
+    event_id: Uuid,
+    image_id: Uuid,
+    variants: &[ImageVariant],
+) {
+    match dynamodb.get_gallery_image(event_id, image_id).await {
+        Err(GalleryError::NotFound) => {}
+        Ok(_) => {
+            tracing::debug!("Keeping former cover image {image_id}, which is in the gallery");
+            return;
+        }
+        Err(e) => {
+            error!("Failed to check whether {image_id} is in the gallery: {e:?}");
+            return;
+        }
+    }
+
+    if let Err(e) = delete_image(s3, event_id, image_id, variants).await {
+        error!("Failed to delete files of former cover image {image_id}: {e:?}");
+        sentry::capture_error(&e);
+    }
+}
+
+/// Deletes every image stored for the event
+pub async fn delete_event_images(
+    s3: &aws_sdk_s3::Client,
+    event: Uuid,
+) -> Result<(), ImageDeletionError> {
+    let prefix = format!(
+        "{prefix}/{event}/",
+        prefix = &*EVENT_IMAGES_BUCKET_PREFIX,
+        event = event
+    );
+    let mut pages = s3
+        .list_objects_v2()
+        .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+        .prefix(prefix)
+        .into_paginator()
+        .send();
+
+    while let Some(page) = pages.next().await {
+        let page = page.map_err(|e| {
+            error!("Failed to list images: {e:?}");
+            sentry::capture_error(&e);
+            ImageDeletionError
+        })?;
+        let keys = page
+            .contents()
+            .iter()
+            .filter_map(|object| object.key())
+            .map(str::to_owned)
+            .collect();
+        delete_objects(s3, keys).await?;
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
+    use bytes::Bytes;
+    use image::{
+        codecs::gif::GifEncoder, imageops::FilterType, DynamicImage, Frame, GenericImageView, Rgb,
+        RgbImage, Rgba, RgbaImage,
+    };
     use rstest::rstest;
 
-    use crate::images::MIN_IMAGE_DIMENSION;
+    use crate::{
+        images::{
+            color::tests::display_p3_profile,
+            encode_image,
+            errors::ImageUploadError,
+            formats::UploadFormat,
+            read_image, resize_to_variants,
+            settings::ImageSettings,
+            variants::{ImageFormat, ImageSize},
+            MAX_DECODED_DIMENSION, MIN_IMAGE_DIMENSION,
+        },
+        test_fixtures::encoded_image,
+    };
+
+    /// Puts an application segment right after the start of the JPEG
+    fn with_segment(jpeg: &[u8], marker: u8, data: &[u8]) -> Vec<u8> {
+        let length = (data.len() as u16 + 2).to_be_bytes();
+        [&jpeg[..2], &[0xff, marker], &length, data, &jpeg[2..]].concat()
+    }
+
+    /// EXIF metadata with only the orientation tag
+    fn exif_orientation(orientation: u16) -> Vec<u8> {
+        let mut exif = b"Exif\0\0II*\0\x08\0\0\0\x01\0".to_vec();
+        exif.extend(0x0112u16.to_le_bytes());
+        exif.extend(3u16.to_le_bytes());
+        exif.extend(1u32.to_le_bytes());
+        exif.extend(orientation.to_le_bytes());
+        exif.extend([0; 2 + 4]);
+        exif
+    }
+
+    /// Whether the JPEG has EXIF or XMP (APP1), or ICC (APP2) metadata. Markers cannot occur in
+    /// the compressed data, which escapes every 0xff.
+    fn has_metadata(jpeg: &[u8]) -> bool {
+        jpeg.windows(2)
+            .any(|bytes| bytes[0] == 0xff && matches!(bytes[1], 0xe1 | 0xe2))
+    }
+
+    #[rstest]
+    #[case(1, (40, 20), [255, 0, 0])]
+    // Rotated a quarter clockwise, which puts the bottom left corner at the top left
+    #[case(6, (20, 40), [0, 0, 255])]
+    #[case(3, (40, 20), [0, 255, 0])]
+    fn test_read_image_orientation(
+        #[case] orientation: u16,
+        #[case] dimensions: (u32, u32),
+        #[case] top_left: [u8; 3],
+    ) {
+        // Red at the top left, green at the bottom right, and blue elsewhere
+        let image = RgbImage::from_fn(40, 20, |x, y| match (x < 10, y < 10) {
+            (true, true) => Rgb([255, 0, 0]),
+            _ if x >= 30 && y >= 10 => Rgb([0, 255, 0]),
+            _ => Rgb([0, 0, 255]),
+        });
+        let photo = with_segment(
+            &encoded_image(&DynamicImage::ImageRgb8(image), image::ImageFormat::Jpeg),
+            0xe1,
+            &exif_orientation(orientation),
+        );
+
+        let image = read_image(Bytes::from(photo), UploadFormat::Jpeg).unwrap();
+        assert_eq!(image.dimensions(), dimensions);
+        let [r, g, b, _] = image.get_pixel(5, 5).0;
+        let difference = [r, g, b]
+            .iter()
+            .zip(top_left)
+            .map(|(value, expected)| value.abs_diff(expected))
+            .max();
+        assert!(difference < Some(20), "{:?} != {top_left:?}", [r, g, b]);
+    }
+
+    #[test]
+    fn test_read_image_color_profile() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 16, Rgb([200, 100, 50])));
+        let mut icc = b"ICC_PROFILE\0\x01\x01".to_vec();
+        icc.extend(display_p3_profile());
+        let photo = with_segment(&encoded_image(&image, image::ImageFormat::Jpeg), 0xe2, &icc);
+
+        let image = read_image(Bytes::from(photo), UploadFormat::Jpeg).unwrap();
+        let [r, g, b, _] = image.get_pixel(8, 8).0;
+        // Display P3 is wider, so its colors are more saturated in sRGB
+        assert!(r > 210 && b < 40, "{:?} is not converted", [r, g, b]);
+    }
+
+    #[test]
+    fn test_read_image_format_mismatch() {
+        let photo = encoded_image(&DynamicImage::new_rgb8(16, 16), image::ImageFormat::Jpeg);
+        assert!(matches!(
+            read_image(Bytes::from(photo), UploadFormat::Png),
+            Err(ImageUploadError::FormatMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_read_image_too_large() {
+        let image = DynamicImage::new_rgb8(MAX_DECODED_DIMENSION + 1, 1);
+        let png = encoded_image(&image, image::ImageFormat::Png);
+        assert!(matches!(
+            read_image(Bytes::from(png), UploadFormat::Png),
+            Err(ImageUploadError::ImageTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_read_image_webp() {
+        let image = DynamicImage::ImageRgb8(RgbImage::from_pixel(16, 8, Rgb([10, 20, 30])));
+        let webp = encoded_image(&image, image::ImageFormat::WebP);
+
+        let decoded = read_image(Bytes::from(webp), UploadFormat::WebP).unwrap();
+        assert_eq!(decoded.to_rgb8(), image.to_rgb8());
+    }
+
+    #[test]
+    fn test_read_image_gif_first_frame() {
+        let mut gif = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut gif);
+            for color in [[255, 0, 0, 255], [0, 0, 255, 255]] {
+                encoder
+                    .encode_frame(Frame::new(RgbaImage::from_pixel(8, 8, Rgba(color))))
+                    .expect("Failed to encode test frame");
+            }
+        }
+
+        let image = read_image(Bytes::from(gif), UploadFormat::Gif).unwrap();
+        assert_eq!(image.dimensions(), (8, 8));
+        assert_eq!(image.get_pixel(4, 4), Rgba([255, 0, 0, 255]));
+    }
+
+    #[rstest]
+    #[case((16, 12), [220, 40, 40])]
+    #[case((48, 12), [40, 180, 60])]
+    #[case((16, 36), [40, 60, 200])]
+    #[case((48, 36), [240, 240, 240])]
+    fn test_read_image_heic(#[case] (x, y): (u32, u32), #[case] expected: [u8; 3]) {
+        // A 64x48 photo in four colors, encoded with libheif and x265
+        let heic = include_bytes!("test_data/quadrants.heic");
+
+        let image = read_image(Bytes::from_static(heic), UploadFormat::Heic).unwrap();
+        assert_eq!(image.dimensions(), (64, 48));
+        let [r, g, b, _] = image.get_pixel(x, y).0;
+        let difference = [r, g, b]
+            .iter()
+            .zip(expected)
+            .map(|(value, expected)| value.abs_diff(expected))
+            .max();
+        assert!(difference < Some(5), "{:?} != {expected:?}", [r, g, b]);
+    }
+
+    #[test]
+    fn test_read_image_heic_broken() {
+        let heic = b"\0\0\0\x18ftypheic\0\0\0\0mif1heic";
+        assert!(matches!(
+            read_image(Bytes::from_static(heic), UploadFormat::Heic),
+            Err(ImageUploadError::ImageDecodingError)
+        ));
+    }
+
+    #[test]
+    fn test_stored_image_has_no_metadata() {
+        let image = DynamicImage::new_rgb8(40, 20);
+        let photo = with_segment(
+            &encoded_image(&image, image::ImageFormat::Jpeg),
+            0xe1,
+            &exif_orientation(6),
+        );
+        assert!(has_metadata(&photo));
+
+        let image = read_image(Bytes::from(photo), UploadFormat::Jpeg).unwrap();
+        let stored = encode_image(&image, ImageFormat::Jpeg, &ImageSettings::configured()).unwrap();
+        assert!(!has_metadata(&stored));
+    }
 
     #[rstest]
     #[case(MIN_IMAGE_DIMENSION as i32, (MIN_IMAGE_DIMENSION as i32) - 1i32)]
@@ -101,4 +624,23 @@ mod tests {
         let image = image::DynamicImage::new_rgb8(height as u32, width as u32);
         assert!(super::is_image_too_small(&image));
     }
+
+    #[rstest]
+    #[case(2000, 1500, &[(ImageSize::Thumbnail, 320, 240), (ImageSize::Card, 640, 480), (ImageSize::Full, 1280, 960)])]
+    #[case(900, 800, &[(ImageSize::Thumbnail, 320, 284), (ImageSize::Card, 640, 569), (ImageSize::Full, 900, 800)])]
+    // A panorama is cropped to twice as wide as high
+    #[case(3000, 1000, &[(ImageSize::Thumbnail, 320, 160), (ImageSize::Card, 640, 320), (ImageSize::Full, 1280, 640)])]
+    fn test_resize_to_variants(
+        #[case] width: u32,
+        #[case] height: u32,
+        #[case] expected: &[(ImageSize, u32, u32)],
+    ) {
+        let image = image::DynamicImage::new_rgb8(width, height);
+        let (variants, _) = resize_to_variants(&image, None, FilterType::CatmullRom);
+        let sizes: Vec<_> = variants
+            .into_iter()
+            .map(|(size, image)| (size, image.width(), image.height()))
+            .collect();
+        assert_eq!(sizes, expected);
+    }
 }
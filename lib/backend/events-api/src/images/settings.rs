@@ -0,0 +1,52 @@
+use image::imageops::FilterType;
+
+use crate::configuration::{AVIF_QUALITY, AVIF_SPEED, IMAGE_RESIZE_FILTER};
+
+/// How images are resized and encoded. Slower AVIF speeds and better filters give nicer
+/// images, at the cost of CPU time in the image Lambda.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ImageSettings {
+    /// From 1, the slowest with the smallest files, to 10
+    pub avif_speed: u8,
+    /// From 1 to 100
+    pub avif_quality: u8,
+    pub resize_filter: FilterType,
+}
+
+impl ImageSettings {
+    /// The settings the environment configures
+    pub fn configured() -> Self {
+        Self {
+            avif_speed: *AVIF_SPEED,
+            avif_quality: *AVIF_QUALITY,
+            resize_filter: *IMAGE_RESIZE_FILTER,
+        }
+    }
+}
+
+pub fn parse_resize_filter(name: &str) -> Option<FilterType> {
+    match name.to_ascii_lowercase().as_str() {
+        "nearest" => Some(FilterType::Nearest),
+        "triangle" => Some(FilterType::Triangle),
+        "catmull-rom" | "catmullrom" => Some(FilterType::CatmullRom),
+        "gaussian" => Some(FilterType::Gaussian),
+        "lanczos3" => Some(FilterType::Lanczos3),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::imageops::FilterType;
+    use rstest::rstest;
+
+    use super::parse_resize_filter;
+
+    #[rstest]
+    #[case("catmull-rom", Some(FilterType::CatmullRom))]
+    #[case("Lanczos3", Some(FilterType::Lanczos3))]
+    #[case("bicubic", None)]
+    fn test_parse_resize_filter(#[case] name: &str, #[case] expected: Option<FilterType>) {
+        assert_eq!(parse_resize_filter(name), expected);
+    }
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::framing::FocalPoint;
+
+/// Widths an image is stored in, so clients can load the smallest one that looks sharp
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageSize {
+    Thumbnail,
+    Card,
+    Full,
+}
+
+impl ImageSize {
+    pub const ALL: [ImageSize; 3] = [ImageSize::Thumbnail, ImageSize::Card, ImageSize::Full];
+
+    /// Neither side of the image is longer than this
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            ImageSize::Thumbnail => 320,
+            ImageSize::Card => 640,
+            ImageSize::Full => 1280,
+        }
+    }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            ImageSize::Thumbnail => "thumbnail",
+            ImageSize::Card => "card",
+            ImageSize::Full => "full",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageFormat {
+    Avif,
+    /// Fallback for clients without AVIF support, such as older Safari and email clients
+    Jpeg,
+}
+
+impl ImageFormat {
+    pub const ALL: [ImageFormat; 2] = [ImageFormat::Avif, ImageFormat::Jpeg];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "avif",
+            ImageFormat::Jpeg => "jpg",
+        }
+    }
+
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Avif => "image/avif",
+            ImageFormat::Jpeg => "image/jpeg",
+        }
+    }
+}
+
+/// A stored rendition of an event image
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct ImageVariant {
+    pub size: ImageSize,
+    pub format: ImageFormat,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ImageVariant {
+    /// Name of the file within the images of the event
+    pub fn file_name(&self, image: Uuid) -> String {
+        format!(
+            "{image}-{size}.{extension}",
+            size = self.size.as_str(),
+            extension = self.format.extension()
+        )
+    }
+}
+
+/// Images of one format, for a `<source>` element
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSource {
+    #[serde(rename = "type")]
+    pub mime_type: &'static str,
+    pub srcset: String,
+}
+
+/// Everything needed to render an event image as a `<picture>`. Clients that support none of
+/// the sources load `src`.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageSet {
+    pub sources: Vec<ImageSource>,
+    pub src: String,
+    pub width: u32,
+    pub height: u32,
+    /// Where to center the image when it is shown cropped, e.g. with `object-position`
+    pub focal_point: Option<FocalPoint>,
+}
+
+impl ImageSet {
+    /// Images are served from `base_url`, under a directory per event. Images uploaded before
+    /// variants were stored only exist as a single AVIF.
+    pub fn new(base_url: &str, event: Uuid, image: Uuid, variants: &[ImageVariant]) -> Self {
+        let url = |file_name: &str| format!("{base_url}/{event}/{file_name}");
+        if variants.is_empty() {
+            return Self {
+                sources: vec![],
+                src: url(&format!("{image}.avif")),
+                width: 0,
+                height: 0,
+                focal_point: None,
+            };
+        }
+
+        let srcset = |format: ImageFormat| {
+            variants
+                .iter()
+                .filter(|variant| variant.format == format)
+                .map(|variant| format!("{} {}w", url(&variant.file_name(image)), variant.width))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let sources = ImageFormat::ALL
+            .into_iter()
+            .map(|format| ImageSource {
+                mime_type: format.mime_type(),
+                srcset: srcset(format),
+            })
+            .filter(|source| !source.srcset.is_empty())
+            .collect();
+        // The largest variant in the most widely supported format
+        let fallback = variants
+            .iter()
+            .max_by_key(|variant| (variant.format == ImageFormat::Jpeg, variant.width))
+            .expect("There is at least one variant");
+        Self {
+            sources,
+            src: url(&fallback.file_name(image)),
+            width: fallback.width,
+            height: fallback.height,
+            focal_point: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::{ImageFormat, ImageSet, ImageSize, ImageVariant};
+
+    fn variant(size: ImageSize, format: ImageFormat, width: u32) -> ImageVariant {
+        ImageVariant {
+            size,
+            format,
+            width,
+            height: width * 3 / 4,
+        }
+    }
+
+    #[test]
+    fn test_image_set() {
+        let event = Uuid::new_v4();
+        let image = Uuid::new_v4();
+        let variants: Vec<ImageVariant> = ImageFormat::ALL
+            .into_iter()
+            .flat_map(|format| {
+                [
+                    variant(ImageSize::Thumbnail, format, 320),
+                    variant(ImageSize::Card, format, 640),
+                    variant(ImageSize::Full, format, 1280),
+                ]
+            })
+            .collect();
+
+        let set = ImageSet::new("/static/events", event, image, &variants);
+
+        let base = format!("/static/events/{event}/{image}");
+        assert_eq!(set.sources.len(), 2);
+        assert_eq!(set.sources[0].mime_type, "image/avif");
+        assert_eq!(
+            set.sources[0].srcset,
+            format!("{base}-thumbnail.avif 320w, {base}-card.avif 640w, {base}-full.avif 1280w")
+        );
+        assert_eq!(set.sources[1].mime_type, "image/jpeg");
+        assert_eq!(set.src, format!("{base}-full.jpg"));
+        assert_eq!((set.width, set.height), (1280, 960));
+    }
+
+    #[test]
+    fn test_image_set_without_variants() {
+        let event = Uuid::new_v4();
+        let image = Uuid::new_v4();
+
+        let set = ImageSet::new("/static/events", event, image, &[]);
+
+        assert!(set.sources.is_empty());
+        assert_eq!(set.src, format!("/static/events/{event}/{image}.avif"));
+    }
+}
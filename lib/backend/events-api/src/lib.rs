@@ -0,0 +1,18 @@
+pub mod api;
+pub mod audit;
+pub mod authentication;
+pub mod calendar;
+pub mod configuration;
+pub mod database;
+pub mod events;
+pub mod gallery;
+pub mod images;
+pub mod localization;
+pub mod maintenance;
+pub mod notifications;
+pub mod organizers;
+pub mod signing;
+pub mod signups;
+#[cfg(test)]
+mod test_fixtures;
+pub mod uploads;
@@ -0,0 +1,141 @@
+//! Picks which language of an event to serve, from the languages a client asks for
+
+use std::collections::HashMap;
+
+use crate::configuration::FALLBACK_LANGUAGES;
+
+/// Languages a client accepts, most preferred first
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LanguagePreference(Vec<String>);
+
+/// Languages of an `Accept-Language` header, ordered by their quality. Wildcards and languages
+/// with a quality of zero are left out, as they never select a particular language.
+fn parse_accept_language(header: &str) -> Vec<String> {
+    let mut languages: Vec<(String, f32)> = header
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split(';');
+            let language = parts.next()?.trim();
+            let quality = parts
+                .find_map(|parameter| parameter.trim().strip_prefix("q="))
+                .map_or(Some(1.0), |quality| quality.trim().parse::<f32>().ok())?;
+            (!language.is_empty() && language != "*" && quality > 0.0)
+                .then(|| (language.to_owned(), quality))
+        })
+        .collect();
+    // The sort is stable, so languages of equal quality keep the order the client gave
+    languages.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+    languages
+        .into_iter()
+        .map(|(language, _)| language)
+        .collect()
+}
+
+/// The language `language` is a variant of, e.g. `sv` for `sv-FI`
+fn primary_subtag(language: &str) -> &str {
+    language.split('-').next().unwrap_or(language)
+}
+
+impl LanguagePreference {
+    /// An explicitly requested `language` comes before those of the `Accept-Language` header
+    pub fn new(language: Option<&str>, accept_language: Option<&str>) -> Self {
+        let mut languages: Vec<String> = language
+            .map(str::trim)
+            .filter(|language| !language.is_empty())
+            .map(str::to_owned)
+            .into_iter()
+            .collect();
+        languages.extend(
+            accept_language
+                .map(parse_accept_language)
+                .unwrap_or_default(),
+        );
+        Self(languages)
+    }
+
+    /// Picks one of `available`: the first preferred language there is, then the first language
+    /// of `fallback` there is, and otherwise the alphabetically first one. A preferred variant
+    /// such as `sv-SE` matches `sv`.
+    pub fn resolve_with_fallback<'a, S>(
+        &self,
+        available: impl IntoIterator<Item = &'a S>,
+        fallback: &[String],
+    ) -> Option<&'a str>
+    where
+        S: AsRef<str> + ?Sized + 'a,
+    {
+        let available: Vec<&'a str> = available.into_iter().map(AsRef::as_ref).collect();
+        let find = |wanted: &str| {
+            available
+                .iter()
+                .find(|language| language.eq_ignore_ascii_case(wanted))
+                .or_else(|| {
+                    available
+                        .iter()
+                        .find(|language| language.eq_ignore_ascii_case(primary_subtag(wanted)))
+                })
+                .copied()
+        };
+        self.0
+            .iter()
+            .chain(fallback)
+            .find_map(|wanted| find(wanted))
+            .or_else(|| available.iter().min().copied())
+    }
+
+    /// Picks one of `available`, falling back through the configured fallback languages
+    pub fn resolve<'a, S>(&self, available: impl IntoIterator<Item = &'a S>) -> Option<&'a str>
+    where
+        S: AsRef<str> + ?Sized + 'a,
+    {
+        self.resolve_with_fallback(available, &FALLBACK_LANGUAGES)
+    }
+}
+
+/// The text in `language`, or an empty text when there is none
+pub fn localized(texts: &HashMap<String, String>, language: &str) -> String {
+    texts.get(language).cloned().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{parse_accept_language, LanguagePreference};
+
+    #[rstest]
+    #[case("sv", &["sv"])]
+    #[case("en-GB,en;q=0.8,sv;q=0.9", &["en-GB", "sv", "en"])]
+    #[case("de, *;q=0.5, fr;q=0", &["de"])]
+    #[case("sv;q=0.5, en;q=0.5", &["sv", "en"])]
+    #[case("sv;q=invalid, en", &["en"])]
+    #[case("", &[])]
+    fn test_parse_accept_language(#[case] header: &str, #[case] expected: &[&str]) {
+        assert_eq!(parse_accept_language(header), expected);
+    }
+
+    #[rstest]
+    // The explicit language wins over the header
+    #[case(Some("en"), Some("sv"), &["en", "sv"], Some("en"))]
+    #[case(None, Some("de, sv-FI;q=0.8"), &["en", "sv"], Some("sv"))]
+    // Unavailable languages fall back to the fallback chain
+    #[case(Some("de"), None, &["en", "sv"], Some("sv"))]
+    #[case(None, None, &["en", "fi"], Some("en"))]
+    // Then to any language there is
+    #[case(None, None, &["fi", "de"], Some("de"))]
+    #[case(None, None, &[], None)]
+    fn test_resolve(
+        #[case] language: Option<&str>,
+        #[case] accept_language: Option<&str>,
+        #[case] available: &[&str],
+        #[case] expected: Option<&str>,
+    ) {
+        let available: Vec<String> = available.iter().map(|l| l.to_string()).collect();
+        let fallback = vec!["sv".to_owned(), "en".to_owned()];
+        let preference = LanguagePreference::new(language, accept_language);
+        assert_eq!(
+            preference.resolve_with_fallback(&available, &fallback),
+            expected
+        );
+    }
+}
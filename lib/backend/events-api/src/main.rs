@@ -1,23 +1,22 @@
-use api::get_event::get_event;
-use authentication::content_creator_authorizer_middleware;
 use axum::{
     extract::{DefaultBodyLimit, FromRef},
     middleware,
-    routing::{get, put},
+    routing::{delete, get, patch, post, put},
     Router,
 };
-use configuration::EVENT_TABLE;
-use events::queries::DynamodbQueries;
+use events_api::{
+    api::{self, get_event::get_event},
+    authentication::permissions::{
+        require_permission, CanCreateEvents, CanManageUsers, CanOrganizeEvents, CanViewAuditLogs,
+    },
+    configuration::{EVENTS_API_HANDLER, EVENT_TABLE},
+    events::{self, queries::DynamodbQueries},
+    maintenance, notifications, signing, uploads,
+};
 use lambda_http::{run, Error};
+use lambda_runtime::service_fn;
 use tracing_subscriber::{fmt::format, EnvFilter};
 
-mod api;
-mod authentication;
-mod configuration;
-mod database;
-mod events;
-mod images;
-
 #[derive(Clone)]
 struct ApiState {
     dynamodb_queries: events::queries::DynamodbQueries,
@@ -44,6 +43,7 @@ fn setup_logging() {
 }
 
 async fn real_main() -> Result<(), Error> {
+    signing::load_signing_key().await?;
     let config = aws_config::load_from_env().await;
     let dynamodb_client = aws_sdk_dynamodb::Client::new(&config);
     let dynamodb_queries =
@@ -55,22 +55,173 @@ async fn real_main() -> Result<(), Error> {
         s3_client,
     };
 
-    let public_router = Router::new().route("/event/{eventId}", get(get_event));
+    let public_router = Router::new()
+        .route("/events", get(api::list_events::list_events))
+        .route("/event/{eventId}", get(get_event))
+        .route(
+            "/event/{eventId}/gallery",
+            get(api::gallery::get_public_gallery),
+        )
+        .route(
+            "/event/{eventId}/calendar.ics",
+            get(api::calendar::get_event_calendar),
+        )
+        .route(
+            "/calendar/organizer/{token}/calendar.ics",
+            get(api::calendar::get_organizer_calendar),
+        )
+        .route(
+            "/calendar/participant/{token}/calendar.ics",
+            get(api::calendar::get_participant_calendar),
+        )
+        .route(
+            "/event/{eventId}/signup",
+            post(api::post_signup::post_signup),
+        )
+        .route(
+            "/signup/{token}",
+            get(api::own_signup::get_own_signup)
+                .patch(api::own_signup::patch_own_signup)
+                .delete(api::own_signup::delete_own_signup),
+        );
 
     let admin_api = Router::new()
-        .route("/event/{eventId}/image", put(api::put_image::put_image))
+        .route(
+            "/event/{eventId}/image",
+            put(api::put_image::put_image).delete(api::put_image::delete_event_image),
+        )
+        .route(
+            "/event/{eventId}/gallery",
+            get(api::gallery::get_gallery)
+                .post(api::gallery::post_gallery)
+                .put(api::gallery::put_gallery_order),
+        )
         // 10 mb limit for images
         .layer(DefaultBodyLimit::disable())
-        .route_layer(middleware::from_fn(content_creator_authorizer_middleware));
+        .route(
+            "/event/{eventId}/image/uploads",
+            post(api::image_upload::post_image_upload),
+        )
+        .route(
+            "/event/{eventId}/image/uploads/{uploadId}",
+            get(api::image_upload::get_image_upload),
+        )
+        .route(
+            "/calendar/token",
+            get(api::calendar::get_organizer_feed_token),
+        )
+        .route(
+            "/events",
+            get(api::list_creator_events::list_creator_events),
+        )
+        .route(
+            "/event/{eventId}",
+            get(api::get_event::get_own_event)
+                .patch(api::patch_event::patch_event)
+                .delete(api::delete_event::delete_event),
+        )
+        .route(
+            "/event/{eventId}/organizers",
+            get(api::organizers::get_organizers),
+        )
+        .route(
+            "/event/{eventId}/organizers/{username}",
+            put(api::organizers::put_organizer).delete(api::organizers::delete_organizer),
+        )
+        .route("/event/{eventId}/owner", put(api::organizers::put_owner))
+        .route(
+            "/event/{eventId}/gallery/{imageId}",
+            patch(api::gallery::patch_gallery_image).delete(api::gallery::delete_gallery_image),
+        )
+        .route(
+            "/event/{eventId}/gallery/{imageId}/cover",
+            put(api::gallery::put_gallery_cover),
+        )
+        .route(
+            "/event/{eventId}/cancel",
+            post(api::delete_event::cancel_event),
+        )
+        .route(
+            "/event/{eventId}/preview",
+            post(api::post_preview::post_preview),
+        )
+        .route(
+            "/event/{eventId}/waitlist",
+            get(api::get_waitlist::get_waitlist).put(api::put_waitlist::put_waitlist),
+        )
+        .route(
+            "/event/{eventId}/waitlist/{signupId}/promote",
+            post(api::promote_signup::promote_signup),
+        )
+        .route(
+            "/event/{eventId}/participants",
+            get(api::get_participants::get_participants),
+        )
+        .route(
+            "/event/{eventId}/participants/export",
+            get(api::get_participants::export_participants),
+        )
+        .route(
+            "/event/{eventId}/signup/{signupId}",
+            delete(api::delete_signup::delete_signup),
+        )
+        .route_layer(middleware::from_fn(require_permission::<CanOrganizeEvents>));
+
+    let create_api = Router::new()
+        .route("/event", post(api::post_event::post_event))
+        .route_layer(middleware::from_fn(require_permission::<CanCreateEvents>));
+
+    let users_api = Router::new()
+        .route(
+            "/users/{username}/events",
+            get(api::list_creator_events::list_user_events),
+        )
+        .route_layer(middleware::from_fn(require_permission::<CanManageUsers>));
+
+    let audit_api = Router::new()
+        .route("/event/{eventId}/audit", get(api::audit_log::get_audit_log))
+        .route_layer(middleware::from_fn(require_permission::<CanViewAuditLogs>));
 
     let app = Router::new()
         .nest("/api/public", public_router)
-        .nest("/api/admin", admin_api)
+        .nest(
+            "/api/admin",
+            admin_api
+                .merge(create_api)
+                .merge(users_api)
+                .merge(audit_api),
+        )
         .with_state(state);
 
     run(app).await
 }
 
+/// Processes images uploaded to the bucket, as S3 notifies about them
+async fn image_processing_main() -> Result<(), Error> {
+    let config = aws_config::load_from_env().await;
+    let dynamodb_queries =
+        events::queries::DynamodbQueries::new(aws_sdk_dynamodb::Client::new(&config), &EVENT_TABLE);
+    let s3_client = aws_sdk_s3::Client::new(&config);
+
+    lambda_runtime::run(service_fn(|event| {
+        uploads::processing::handle_s3_event(&s3_client, &dynamodb_queries, event)
+    }))
+    .await
+}
+
+/// Emails participants about notices queued in the table, as its stream tells about them
+async fn notifications_main() -> Result<(), Error> {
+    let config = aws_config::load_from_env().await;
+    let dynamodb_queries =
+        events::queries::DynamodbQueries::new(aws_sdk_dynamodb::Client::new(&config), &EVENT_TABLE);
+    let notifier = notifications::Notifier::new(&config);
+
+    lambda_runtime::run(service_fn(|event| {
+        notifications::handle_stream_event(&notifier, &dynamodb_queries, event)
+    }))
+    .await
+}
+
 fn main() -> Result<(), Error> {
     setup_logging();
     let _guard = sentry::init(sentry::ClientOptions {
@@ -78,9 +229,18 @@ fn main() -> Result<(), Error> {
         ..Default::default()
     });
 
-    tokio::runtime::Builder::new_multi_thread()
+    let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
-        .unwrap()
-        .block_on(real_main())
+        .unwrap();
+    // Maintenance commands are given as arguments, while Lambda starts the API without any
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        return runtime.block_on(maintenance::run(&args));
+    }
+    match EVENTS_API_HANDLER.as_deref() {
+        Some("image-processing") => runtime.block_on(image_processing_main()),
+        Some("notifications") => runtime.block_on(notifications_main()),
+        _ => runtime.block_on(real_main()),
+    }
 }
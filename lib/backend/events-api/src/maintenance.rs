@@ -0,0 +1,278 @@
+//! Maintenance tasks that are run by hand rather than through the API, e.g.
+//! `events-api cleanup-images --dry-run` or `events-api normalize-dates`
+
+use std::collections::{BTreeMap, HashSet};
+
+use lambda_http::Error;
+use uuid::Uuid;
+
+use crate::{
+    configuration::{EVENT_IMAGES_BUCKET_NAME, EVENT_IMAGES_BUCKET_PREFIX, EVENT_TABLE},
+    events::{
+        errors::{GetEventError, UpdateEventError},
+        models::Event,
+        queries::DynamodbQueries,
+    },
+    gallery::models::GalleryImage,
+    images::{delete_objects, image_file_names},
+};
+
+const USAGE: &str = "Usage: events-api cleanup-images [--dry-run] [EVENT_ID...]
+       events-api normalize-dates [--dry-run]";
+
+/// Images are stored before the event item refers to them, so recent objects may belong to an
+/// upload that is still in progress
+const MIN_ORPHAN_AGE: time::Duration = time::Duration::hours(1);
+
+struct StoredObject {
+    key: String,
+    /// Name of the file within the directory of its event
+    file_name: String,
+    last_modified: Option<i64>,
+}
+
+/// The event a stored image belongs to and its file name, from its key `{prefix}/{event}/{file}`
+fn split_key(key: &str) -> Option<(Uuid, &str)> {
+    let (event, file_name) = key
+        .strip_prefix(EVENT_IMAGES_BUCKET_PREFIX.as_str())?
+        .strip_prefix('/')?
+        .split_once('/')?;
+    Some((event.parse().ok()?, file_name))
+}
+
+/// Files of every image the event shows, as its cover or in its gallery
+fn referenced_file_names(event: Option<&Event>, gallery: &[GalleryImage]) -> HashSet<String> {
+    let cover = event.and_then(|event| {
+        event
+            .image
+            .map(|image| image_file_names(image, &event.image_variants))
+    });
+    cover
+        .into_iter()
+        .flatten()
+        .chain(
+            gallery
+                .iter()
+                .flat_map(|image| image_file_names(image.image_id, &image.variants)),
+        )
+        .collect()
+}
+
+/// Objects nothing refers to, which were stored before `cutoff`
+fn orphaned_keys(
+    objects: &[StoredObject],
+    referenced: &HashSet<String>,
+    cutoff: time::OffsetDateTime,
+) -> Vec<String> {
+    objects
+        .iter()
+        .filter(|object| !referenced.contains(&object.file_name))
+        .filter(|object| {
+            object
+                .last_modified
+                .is_some_and(|modified| modified < cutoff.unix_timestamp())
+        })
+        .map(|object| object.key.clone())
+        .collect()
+}
+
+/// Keys and modification times of the objects under `prefix`
+async fn list_objects(
+    s3: &aws_sdk_s3::Client,
+    prefix: String,
+) -> Result<Vec<(String, Option<i64>)>, Error> {
+    let mut objects = Vec::new();
+    let mut pages = s3
+        .list_objects_v2()
+        .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+        .prefix(prefix)
+        .into_paginator()
+        .send();
+    while let Some(page) = pages.next().await {
+        objects.extend(page?.contents().iter().filter_map(|object| {
+            Some((
+                object.key()?.to_owned(),
+                object.last_modified().map(|modified| modified.secs()),
+            ))
+        }));
+    }
+    Ok(objects)
+}
+
+/// Deletes the images of `events`, or of every event when none are given, that neither the
+/// event nor its gallery refers to. Images of events that no longer exist are all deleted.
+async fn cleanup_images(
+    dynamodb: &DynamodbQueries,
+    s3: &aws_sdk_s3::Client,
+    events: &[Uuid],
+    dry_run: bool,
+) -> Result<(), Error> {
+    let prefix = &*EVENT_IMAGES_BUCKET_PREFIX;
+    let mut objects = Vec::new();
+    if events.is_empty() {
+        objects = list_objects(s3, format!("{prefix}/")).await?;
+    }
+    for event in events {
+        objects.extend(list_objects(s3, format!("{prefix}/{event}/")).await?);
+    }
+
+    let mut by_event: BTreeMap<Uuid, Vec<StoredObject>> = BTreeMap::new();
+    for (key, last_modified) in objects {
+        match split_key(&key) {
+            Some((event, file_name)) => by_event.entry(event).or_default().push(StoredObject {
+                file_name: file_name.to_owned(),
+                key,
+                last_modified,
+            }),
+            None => println!("Skipping {key}, which belongs to no event"),
+        }
+    }
+
+    let cutoff = time::OffsetDateTime::now_utc() - MIN_ORPHAN_AGE;
+    let mut orphans = Vec::new();
+    for (event_id, objects) in by_event {
+        let event = match dynamodb.get_event(event_id).await {
+            Ok(event) => Some(event),
+            Err(GetEventError::NotFound) => None,
+            Err(e) => {
+                println!("Skipping images of {event_id}, which failed to load: {e:?}");
+                continue;
+            }
+        };
+        let gallery = match event {
+            Some(_) => dynamodb.get_gallery(event_id).await?,
+            None => vec![],
+        };
+
+        let referenced = referenced_file_names(event.as_ref(), &gallery);
+        for key in orphaned_keys(&objects, &referenced, cutoff) {
+            println!(
+                "{} {key}",
+                if dry_run { "Would delete" } else { "Deleting" }
+            );
+            orphans.push(key);
+        }
+    }
+
+    println!("Found {} unreferenced images", orphans.len());
+    if !dry_run {
+        for keys in orphans.chunks(1000) {
+            delete_objects(s3, keys.to_vec()).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Rewrites the dates of every event in UTC. Events stored before dates were kept in UTC
+/// otherwise sort out of date order in the listing index.
+async fn normalize_dates(dynamodb: &DynamodbQueries, dry_run: bool) -> Result<(), Error> {
+    let mut normalized = 0;
+    for event_id in dynamodb.list_event_ids().await? {
+        match dynamodb.normalize_event_dates(event_id, dry_run).await {
+            Ok(false) => {}
+            Ok(true) => {
+                println!(
+                    "{} {event_id}",
+                    if dry_run {
+                        "Would normalize"
+                    } else {
+                        "Normalized"
+                    }
+                );
+                normalized += 1;
+            }
+            Err(UpdateEventError::VersionConflict) => {
+                println!("Skipping {event_id}, which changed while normalizing. Run again.");
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    println!("Found {normalized} events with dates outside of UTC");
+    Ok(())
+}
+
+/// Runs the maintenance command given by `args`, without the program name
+pub async fn run(args: &[String]) -> Result<(), Error> {
+    let Some((command, options)) = args.split_first() else {
+        return Err(USAGE.into());
+    };
+    let dry_run = options.iter().any(|option| option == "--dry-run");
+    let arguments: Vec<&String> = options
+        .iter()
+        .filter(|option| *option != "--dry-run")
+        .collect();
+
+    let config = aws_config::load_from_env().await;
+    let dynamodb = DynamodbQueries::new(aws_sdk_dynamodb::Client::new(&config), &EVENT_TABLE);
+    match command.as_str() {
+        "cleanup-images" => {
+            let events = arguments
+                .into_iter()
+                .map(|event| event.parse())
+                .collect::<Result<Vec<Uuid>, _>>()
+                .map_err(|_| USAGE)?;
+            let s3 = aws_sdk_s3::Client::new(&config);
+            cleanup_images(&dynamodb, &s3, &events, dry_run).await
+        }
+        "normalize-dates" if arguments.is_empty() => normalize_dates(&dynamodb, dry_run).await,
+        _ => Err(USAGE.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+    use time::macros::datetime;
+    use uuid::Uuid;
+
+    use crate::{events::models::Event, gallery::models::GalleryImage, test_fixtures::event};
+
+    use super::{orphaned_keys, referenced_file_names, StoredObject};
+
+    fn object(file_name: String, last_modified: time::OffsetDateTime) -> StoredObject {
+        StoredObject {
+            key: format!("events/{file_name}"),
+            file_name,
+            last_modified: Some(last_modified.unix_timestamp()),
+        }
+    }
+
+    #[rstest]
+    fn test_orphaned_keys(mut event: Event) {
+        let cover = Uuid::new_v4();
+        event.image = Some(cover);
+        let gallery = [GalleryImage {
+            event_id: event.id,
+            image_id: Uuid::new_v4(),
+            position: 0,
+            alt_text: HashMap::new(),
+            variants: vec![],
+            focal_point: None,
+        }];
+        let old = datetime!(2025-01-01 12:00 UTC);
+        let cutoff = datetime!(2025-01-02 11:00 UTC);
+        let orphan = format!("{}.avif", Uuid::new_v4());
+        let objects = [
+            object(format!("{cover}.avif"), old),
+            object(format!("{}.avif", gallery[0].image_id), old),
+            object(orphan.clone(), old),
+            // Possibly still being uploaded
+            object(
+                format!("{}.avif", Uuid::new_v4()),
+                cutoff + time::Duration::MINUTE,
+            ),
+        ];
+
+        let referenced = referenced_file_names(Some(&event), &gallery);
+        assert_eq!(
+            orphaned_keys(&objects, &referenced, cutoff),
+            [format!("events/{orphan}")]
+        );
+        // Nothing is referenced once the event is gone
+        let referenced = referenced_file_names(None, &[]);
+        assert_eq!(orphaned_keys(&objects, &referenced, cutoff).len(), 3);
+    }
+}
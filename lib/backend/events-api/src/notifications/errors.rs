@@ -0,0 +1,13 @@
+use crate::database::errors::{DatabaseQueryFailed, ModelError};
+
+#[derive(thiserror::Error, Debug)]
+pub enum NotificationError {
+    #[error("No sender is configured for notifications")]
+    NotConfigured,
+    #[error("Failed to send email")]
+    SendFailed,
+    #[error("Failed to read notice")]
+    InvalidNotice(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
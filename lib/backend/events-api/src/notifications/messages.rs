@@ -0,0 +1,103 @@
+use time::macros::format_description;
+use time_tz::OffsetDateTimeExt;
+
+use crate::{
+    events::models::Event,
+    localization::{localized, LanguagePreference},
+};
+
+use super::models::Notice;
+
+/// Fixed texts of the emails. Languages without a translation fall back to English.
+struct Labels {
+    cancelled_subject: &'static str,
+    cancelled_body: &'static str,
+    deleted_subject: &'static str,
+    deleted_body: &'static str,
+}
+
+const ENGLISH: Labels = Labels {
+    cancelled_subject: "Cancelled: {title}",
+    cancelled_body: "{title} on {date} has been cancelled by the organizer.",
+    deleted_subject: "Cancelled: {title}",
+    deleted_body: "{title} on {date} has been cancelled by the organizer, and your signup \
+                   has been removed.",
+};
+
+const SWEDISH: Labels = Labels {
+    cancelled_subject: "Inställt: {title}",
+    cancelled_body: "{title} den {date} har ställts in av arrangören.",
+    deleted_subject: "Inställt: {title}",
+    deleted_body: "{title} den {date} har ställts in av arrangören, och din anmälan har \
+                   tagits bort.",
+};
+
+fn labels(language: &str) -> &'static Labels {
+    match language {
+        "sv" => &SWEDISH,
+        _ => &ENGLISH,
+    }
+}
+
+pub struct Message {
+    pub subject: String,
+    pub body: String,
+}
+
+/// The email telling participants about `notice`, in the language the event is served in by
+/// default since signups don't record the language of the participant
+pub fn message(event: &Event, notice: Notice) -> Message {
+    let language = LanguagePreference::default()
+        .resolve(event.title.keys())
+        .unwrap_or_default();
+    let labels = labels(language);
+    let (subject, body) = match notice {
+        Notice::Cancelled => (labels.cancelled_subject, labels.cancelled_body),
+        Notice::Deleted => (labels.deleted_subject, labels.deleted_body),
+    };
+
+    let date = match event.tz() {
+        Some(time_zone) => event.event_date.to_timezone(time_zone),
+        None => event.event_date,
+    };
+    let date = date
+        .format(format_description!("[year]-[month]-[day]"))
+        .unwrap_or_default();
+    let title = localized(&event.title, language);
+    let fill = |text: &str| text.replace("{title}", &title).replace("{date}", &date);
+    Message {
+        subject: fill(subject),
+        body: fill(body),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use time::macros::datetime;
+
+    use crate::{events::models::Event, test_fixtures::event};
+
+    use super::{message, Notice};
+
+    #[rstest]
+    fn test_message_in_event_time_zone(mut event: Event) {
+        event.event_date = datetime!(2025-03-08 23:30 UTC);
+        event.time_zone = "Europe/Stockholm".to_owned();
+
+        let message = message(&event, Notice::Cancelled);
+        assert_eq!(message.subject, "Inställt: Utflykt");
+        assert_eq!(
+            message.body,
+            "Utflykt den 2025-03-09 har ställts in av arrangören."
+        );
+    }
+
+    #[rstest]
+    fn test_message_falls_back_to_english(mut event: Event) {
+        event.title = [("de".to_owned(), "Ausflug".to_owned())].into();
+
+        let message = message(&event, Notice::Deleted);
+        assert_eq!(message.subject, "Cancelled: Ausflug");
+    }
+}
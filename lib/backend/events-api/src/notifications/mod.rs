@@ -0,0 +1,146 @@
+//! Emails participants when their event is cancelled or deleted. The email is queued in the
+//! table along with the change to the event, and sent with SES by a separate Lambda as the stream
+//! of the table tells about it. Sending is retried until every participant has been emailed.
+
+use aws_config::SdkConfig;
+use aws_lambda_events::dynamodb::Event as DynamodbEvent;
+use aws_sdk_sesv2::types::{Body, Content, Destination, EmailContent, Message};
+use futures_util::{future, StreamExt};
+use lambda_runtime::{Error, LambdaEvent};
+use serde::Deserialize;
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{configuration::NOTIFICATION_SENDER, events::queries::DynamodbQueries};
+
+use self::{
+    errors::NotificationError,
+    models::{Notice, PendingNotice},
+};
+
+pub mod errors;
+mod messages;
+pub mod models;
+mod queries;
+
+/// Emails sent at once, kept well below the sending rate of SES
+const MAX_CONCURRENT_SENDS: usize = 8;
+
+#[derive(Clone)]
+pub struct Notifier {
+    ses: aws_sdk_sesv2::Client,
+}
+
+/// The keys of a changed item, as told by the stream of the table
+#[derive(Deserialize)]
+struct ItemKey {
+    #[serde(rename = "PK")]
+    partition_key: String,
+    #[serde(rename = "SK")]
+    sort_key: String,
+}
+
+fn content(data: &str) -> Result<Content, NotificationError> {
+    Content::builder()
+        .data(data)
+        .charset("UTF-8")
+        .build()
+        .map_err(|e| {
+            error!("Failed to build email: {e:?}");
+            NotificationError::SendFailed
+        })
+}
+
+impl Notifier {
+    pub fn new(config: &SdkConfig) -> Self {
+        Self {
+            ses: aws_sdk_sesv2::Client::new(config),
+        }
+    }
+
+    async fn send_email(
+        &self,
+        sender: &str,
+        recipient: &str,
+        notice: &PendingNotice,
+    ) -> Result<(), NotificationError> {
+        let message = Message::builder()
+            .subject(content(&notice.subject)?)
+            .body(Body::builder().text(content(&notice.body)?).build())
+            .build();
+        self.ses
+            .send_email()
+            .from_email_address(sender)
+            .destination(Destination::builder().to_addresses(recipient).build())
+            .content(EmailContent::builder().simple(message).build())
+            .send()
+            .await
+            .map_err(|e| {
+                error!("Failed to send email: {e:?}");
+                sentry::capture_error(&e);
+                NotificationError::SendFailed
+            })?;
+        Ok(())
+    }
+
+    /// Emails the participants not yet emailed about `notice`. Fails if anyone could not be
+    /// emailed, so that the stream retries them.
+    pub async fn send_notice(
+        &self,
+        dynamodb: &DynamodbQueries,
+        event_id: Uuid,
+        notice: Notice,
+    ) -> Result<(), NotificationError> {
+        let Some(pending) = dynamodb.get_pending_notice(event_id, notice).await? else {
+            tracing::warn!("{notice} notice of {event_id} is gone, not notifying");
+            return Ok(());
+        };
+        let Some(sender) = NOTIFICATION_SENDER.as_deref() else {
+            error!("Notifications are not configured, can't send {notice} notice of {event_id}");
+            return Err(NotificationError::NotConfigured);
+        };
+
+        let pending = &pending;
+        let failed = futures_util::stream::iter(&pending.recipients)
+            .map(|recipient| async move {
+                self.send_email(sender, recipient, pending).await?;
+                dynamodb.mark_notified(event_id, notice, recipient).await
+            })
+            .buffer_unordered(MAX_CONCURRENT_SENDS)
+            .filter(|result| future::ready(result.is_err()))
+            .count()
+            .await;
+        tracing::debug!(
+            "Notified {} participants of {event_id} about {notice}",
+            pending.recipients.len() - failed
+        );
+        if failed > 0 {
+            error!("Failed to notify {failed} participants of {event_id}");
+            return Err(NotificationError::SendFailed);
+        }
+        Ok(())
+    }
+}
+
+/// Handles the stream of the table, which only passes on newly queued notices
+pub async fn handle_stream_event(
+    notifier: &Notifier,
+    dynamodb: &DynamodbQueries,
+    event: LambdaEvent<DynamodbEvent>,
+) -> Result<(), Error> {
+    for record in event.payload.records {
+        let key: ItemKey = match serde_dynamo::from_item(record.change.keys) {
+            Ok(key) => key,
+            Err(e) => {
+                error!("Failed to read keys of stream record: {e:?}");
+                continue;
+            }
+        };
+        let Some((event_id, notice)) = PendingNotice::parse_key(&key.partition_key, &key.sort_key)
+        else {
+            continue;
+        };
+        notifier.send_notice(dynamodb, event_id, notice).await?;
+    }
+    Ok(())
+}
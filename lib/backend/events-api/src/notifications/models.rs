@@ -0,0 +1,246 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    str::FromStr,
+};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{datetime_attribute, get_datetime, get_field},
+    },
+    events::models::{columns, Event},
+    signups::models::Signup,
+};
+
+use super::messages::message;
+
+/// How long a notice is kept after it was queued, sent or not
+const NOTICE_RETENTION: time::Duration = time::Duration::days(14);
+
+/// What happened to the event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Notice {
+    Cancelled,
+    Deleted,
+}
+
+impl Notice {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Notice::Cancelled => "Cancelled",
+            Notice::Deleted => "Deleted",
+        }
+    }
+}
+
+impl Display for Notice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Notice {
+    type Err = ModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Cancelled" => Ok(Notice::Cancelled),
+            "Deleted" => Ok(Notice::Deleted),
+            _ => Err(ModelError::InvalidData(format!("Unknown notice {s}"))),
+        }
+    }
+}
+
+/// Every participant once, whether confirmed or waitlisted
+fn recipients(signups: &[Signup]) -> BTreeSet<String> {
+    signups
+        .iter()
+        .map(|signup| signup.email.trim().to_lowercase())
+        .filter(|email| !email.is_empty())
+        .collect()
+}
+
+/// An email to the participants of an event that is waiting to be sent. It is stored with the
+/// change to the event, and sent by the notification Lambda once the stream of the table tells
+/// about it. The email is written when the notice is queued, as the event may be gone when it
+/// is sent.
+#[derive(Debug, PartialEq)]
+pub struct PendingNotice {
+    pub event_id: Uuid,
+    pub notice: Notice,
+    pub subject: String,
+    pub body: String,
+    /// Those not emailed yet. Recipients are removed as they are emailed, so a retry only
+    /// emails the rest.
+    pub recipients: BTreeSet<String>,
+    pub queued_at: OffsetDateTime,
+}
+
+impl PendingNotice {
+    pub const SORT_KEY_PREFIX: &str = columns::NOTICE_SORT_KEY_PREFIX;
+
+    /// The notice to everyone signed up for `event`, or `None` if no one is
+    pub fn new(event: &Event, signups: &[Signup], notice: Notice) -> Option<Self> {
+        let recipients = recipients(signups);
+        if recipients.is_empty() {
+            return None;
+        }
+        let message = message(event, notice);
+        Some(Self {
+            event_id: event.id,
+            notice,
+            subject: message.subject,
+            body: message.body,
+            recipients,
+            queued_at: OffsetDateTime::now_utc(),
+        })
+    }
+
+    /// An event gets each notice once, so a retried request doesn't queue it again
+    pub fn sort_key(notice: Notice) -> String {
+        format!("{}{notice}", Self::SORT_KEY_PREFIX)
+    }
+
+    /// The event and notice of the item with the given keys
+    pub fn parse_key(partition_key: &str, sort_key: &str) -> Option<(Uuid, Notice)> {
+        let notice = sort_key.strip_prefix(Self::SORT_KEY_PREFIX)?.parse().ok()?;
+        Some((partition_key.parse().ok()?, notice))
+    }
+
+    pub fn key(event_id: Uuid, notice: Notice) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(Self::sort_key(notice)),
+            ),
+        ])
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for PendingNotice {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        let sort_key: String = get_field(item, columns::SORTING_KEY_COLUMN)?;
+        let notice = sort_key
+            .strip_prefix(Self::SORT_KEY_PREFIX)
+            .ok_or_else(|| ModelError::InvalidData(format!("{sort_key} is not a notice")))?
+            .parse()?;
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            notice,
+            subject: get_field(item, columns::EMAIL_SUBJECT_COLUMN)?,
+            body: get_field(item, columns::EMAIL_BODY_COLUMN)?,
+            // The set is removed along with its last recipient
+            recipients: item
+                .get(columns::RECIPIENTS_COLUMN)
+                .and_then(|recipients| recipients.as_ss().ok())
+                .map(|recipients| recipients.iter().cloned().collect())
+                .unwrap_or_default(),
+            queued_at: get_datetime(item, columns::REQUESTED_AT_COLUMN)?,
+        })
+    }
+}
+
+impl TryFrom<&PendingNotice> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(notice: &PendingNotice) -> Result<Self, Self::Error> {
+        let expires_at = notice.queued_at + NOTICE_RETENTION;
+        let mut item = PendingNotice::key(notice.event_id, notice.notice);
+        item.extend([
+            (
+                columns::EMAIL_SUBJECT_COLUMN.to_owned(),
+                AttributeValue::S(notice.subject.clone()),
+            ),
+            (
+                columns::EMAIL_BODY_COLUMN.to_owned(),
+                AttributeValue::S(notice.body.clone()),
+            ),
+            (
+                columns::REQUESTED_AT_COLUMN.to_owned(),
+                datetime_attribute(columns::REQUESTED_AT_COLUMN, &notice.queued_at)?,
+            ),
+            // Read by the time to live of the table, which expects seconds since the epoch
+            (
+                columns::EXPIRES_AT_COLUMN.to_owned(),
+                AttributeValue::N(expires_at.unix_timestamp().to_string()),
+            ),
+        ]);
+        if !notice.recipients.is_empty() {
+            item.insert(
+                columns::RECIPIENTS_COLUMN.to_owned(),
+                AttributeValue::Ss(notice.recipients.iter().cloned().collect()),
+            );
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+    use uuid::Uuid;
+
+    use crate::{
+        events::models::Event,
+        signups::models::{Signup, SignupStatus},
+        test_fixtures::event,
+    };
+
+    use super::{recipients, Notice, PendingNotice};
+
+    fn signup(email: &str, status: SignupStatus) -> Signup {
+        Signup {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            name: "Participant".to_owned(),
+            email: email.to_owned(),
+            phone: None,
+            signup_date: time::OffsetDateTime::now_utc(),
+            status,
+            waitlist_position: None,
+            answers: HashMap::new(),
+            calendar_secret: None,
+        }
+    }
+
+    #[test]
+    fn test_recipients() {
+        let signups = [
+            signup("anna@example.com", SignupStatus::Confirmed),
+            signup("Anna@Example.com ", SignupStatus::Confirmed),
+            signup("bertil@example.com", SignupStatus::Waitlisted),
+        ];
+        assert_eq!(
+            recipients(&signups).into_iter().collect::<Vec<_>>(),
+            ["anna@example.com", "bertil@example.com"]
+        );
+    }
+
+    #[rstest]
+    fn test_notice_item(event: Event) {
+        let signups = [signup("anna@example.com", SignupStatus::Confirmed)];
+        let notice = PendingNotice::new(&event, &signups, Notice::Deleted).unwrap();
+
+        let item = HashMap::try_from(&notice).unwrap();
+        assert_eq!(PendingNotice::try_from(&item).unwrap(), notice);
+        assert_eq!(
+            PendingNotice::parse_key(&event.id.to_string(), "Notice#Deleted"),
+            Some((event.id, Notice::Deleted))
+        );
+    }
+
+    #[rstest]
+    fn test_no_notice_without_participants(event: Event) {
+        assert_eq!(PendingNotice::new(&event, &[], Notice::Cancelled), None);
+    }
+}
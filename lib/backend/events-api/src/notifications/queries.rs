@@ -0,0 +1,210 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::{AttributeValue, Put};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    database::errors::DatabaseQueryFailed,
+    events::{models::columns, queries::DynamodbQueries},
+};
+
+use super::{
+    errors::NotificationError,
+    models::{Notice, PendingNotice},
+};
+
+fn notice_item(
+    notice: &PendingNotice,
+) -> Result<HashMap<String, AttributeValue>, DatabaseQueryFailed> {
+    HashMap::try_from(notice).map_err(|e| {
+        error!("Failed to convert notice to item: {e:?}");
+        sentry::capture_error(&e);
+        DatabaseQueryFailed
+    })
+}
+
+impl DynamodbQueries {
+    /// The write queueing `notice`, for transactions that make the change participants are told
+    /// about. A notice is only queued once, so it fails if the event already has it.
+    pub(crate) fn queue_notice_request(
+        &self,
+        notice: &PendingNotice,
+    ) -> Result<Put, DatabaseQueryFailed> {
+        Put::builder()
+            .table_name(self.table_name)
+            .set_item(Some(notice_item(notice)?))
+            .condition_expression("attribute_not_exists(PK)")
+            .build()
+            .map_err(|e| {
+                error!("Failed to build transaction item: {e:?}");
+                sentry::capture_error(&e);
+                DatabaseQueryFailed
+            })
+    }
+
+    /// Queues `notice` to be sent. Queueing a notice the event already has keeps the first one,
+    /// so participants are not emailed twice when a request is retried.
+    pub async fn queue_notice(&self, notice: &PendingNotice) -> Result<(), DatabaseQueryFailed> {
+        match self
+            .client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(notice_item(notice)?))
+            .condition_expression("attribute_not_exists(PK)")
+            .send()
+            .await
+        {
+            Ok(_) => Ok(()),
+            Err(s)
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                tracing::debug!(
+                    "{} notice already queued for {}",
+                    notice.notice,
+                    notice.event_id
+                );
+                Ok(())
+            }
+            Err(s) => {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                Err(DatabaseQueryFailed)
+            }
+        }
+    }
+
+    pub async fn get_pending_notice(
+        &self,
+        event_id: Uuid,
+        notice: Notice,
+    ) -> Result<Option<PendingNotice>, NotificationError> {
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(PendingNotice::key(event_id, notice)))
+            // Recipients emailed by an earlier attempt must not be emailed again
+            .consistent_read(true)
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                NotificationError::from(DatabaseQueryFailed)
+            })?;
+
+        res.item()
+            .map(PendingNotice::try_from)
+            .transpose()
+            .map_err(|e| {
+                error!("Failed to parse notice: {e:?}");
+                sentry::capture_error(&e);
+                NotificationError::from(e)
+            })
+    }
+
+    /// Removes `recipient` from those still to be emailed about `notice`
+    pub async fn mark_notified(
+        &self,
+        event_id: Uuid,
+        notice: Notice,
+        recipient: &str,
+    ) -> Result<(), NotificationError> {
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(PendingNotice::key(event_id, notice)))
+            .update_expression("DELETE #R :recipient")
+            .expression_attribute_names("#R", columns::RECIPIENTS_COLUMN)
+            .expression_attribute_values(
+                ":recipient",
+                AttributeValue::Ss(vec![recipient.to_owned()]),
+            )
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                NotificationError::from(DatabaseQueryFailed)
+            })?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use uuid::Uuid;
+
+    use crate::{
+        events::queries::DynamodbQueries,
+        notifications::models::{Notice, PendingNotice},
+        signups::models::{Signup, SignupStatus},
+        test_fixtures::{event, init_dynamodb, insert_test_event},
+    };
+
+    fn signup(email: &str) -> Signup {
+        Signup {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            name: "Participant".to_owned(),
+            email: email.to_owned(),
+            phone: None,
+            signup_date: time::OffsetDateTime::now_utc(),
+            status: SignupStatus::Confirmed,
+            waitlist_position: None,
+            answers: HashMap::new(),
+            calendar_secret: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_and_mark_notified() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let event = crate::events::models::Event {
+            id: event_id,
+            ..event()
+        };
+        let signups = [signup("anna@example.com"), signup("bertil@example.com")];
+        let notice = PendingNotice::new(&event, &signups, Notice::Cancelled).unwrap();
+
+        queries.queue_notice(&notice).await.unwrap();
+        queries
+            .mark_notified(event_id, Notice::Cancelled, "anna@example.com")
+            .await
+            .unwrap();
+        // Queueing again keeps who has been emailed
+        queries.queue_notice(&notice).await.unwrap();
+
+        let pending = queries
+            .get_pending_notice(event_id, Notice::Cancelled)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            pending.recipients.into_iter().collect::<Vec<_>>(),
+            ["bertil@example.com"]
+        );
+
+        queries
+            .mark_notified(event_id, Notice::Cancelled, "bertil@example.com")
+            .await
+            .unwrap();
+        let pending = queries
+            .get_pending_notice(event_id, Notice::Cancelled)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pending.recipients.is_empty());
+        assert!(queries
+            .get_pending_notice(event_id, Notice::Deleted)
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,21 @@
+use crate::database::errors::{DatabaseQueryFailed, ModelError};
+
+#[derive(thiserror::Error, Debug)]
+pub enum OrganizerError {
+    #[error("Organizer not found")]
+    NotFound,
+    #[error("Owners can only be changed by transferring ownership")]
+    InvalidRole,
+    #[error("User already owns the event")]
+    AlreadyOwner,
+    #[error("Event owner changed while transferring ownership")]
+    OwnershipConflict,
+    #[error("Failed to read organizer")]
+    InvalidStoredOrganizer(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("Organizer role does not allow this")]
+pub struct InsufficientRoleError;
@@ -0,0 +1,140 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use aws_sdk_dynamodb::types::AttributeValue;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    database::{errors::ModelError, util::get_field},
+    events::models::columns,
+};
+
+/// What an organizer may do with an event. Each role can do everything the roles below it can.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OrganizerRole {
+    /// Can manage organizers, transfer ownership, and cancel or delete the event
+    Owner,
+    /// Can change the event and manage its signups
+    Editor,
+    /// Can see the event and its participants
+    Viewer,
+}
+
+/// The kind of access a handler needs to an event
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EventAccess {
+    View,
+    Edit,
+    Manage,
+}
+
+impl OrganizerRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrganizerRole::Owner => "Owner",
+            OrganizerRole::Editor => "Editor",
+            OrganizerRole::Viewer => "Viewer",
+        }
+    }
+
+    pub fn allows(&self, access: EventAccess) -> bool {
+        match self {
+            OrganizerRole::Owner => true,
+            OrganizerRole::Editor => access != EventAccess::Manage,
+            OrganizerRole::Viewer => access == EventAccess::View,
+        }
+    }
+}
+
+impl Display for OrganizerRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for OrganizerRole {
+    type Err = ModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Owner" => Ok(OrganizerRole::Owner),
+            "Editor" => Ok(OrganizerRole::Editor),
+            "Viewer" => Ok(OrganizerRole::Viewer),
+            _ => Err(ModelError::InvalidData(format!(
+                "Unknown organizer role {s}"
+            ))),
+        }
+    }
+}
+
+/// A co-organizer of an event. The owner is the creator of the event, and has no item of its own.
+/// Organizer items store the username in the creator column, so the events by creator index also
+/// finds the events a user co-organizes.
+pub struct Organizer {
+    pub event_id: Uuid,
+    pub username: String,
+    pub role: OrganizerRole,
+}
+
+impl Organizer {
+    pub const SORT_KEY_PREFIX: &str = "Organizer#";
+
+    pub fn sort_key_for(username: &str) -> String {
+        format!("{}{}", Self::SORT_KEY_PREFIX, username)
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for Organizer {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            username: get_field(item, columns::CREATOR_COLUMN)?,
+            role: get_field(item, columns::ORGANIZER_ROLE_COLUMN)?,
+        })
+    }
+}
+
+impl From<&Organizer> for HashMap<String, AttributeValue> {
+    fn from(organizer: &Organizer) -> Self {
+        HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(organizer.event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(Organizer::sort_key_for(&organizer.username)),
+            ),
+            (
+                columns::CREATOR_COLUMN.to_owned(),
+                AttributeValue::S(organizer.username.clone()),
+            ),
+            (
+                columns::ORGANIZER_ROLE_COLUMN.to_owned(),
+                AttributeValue::S(organizer.role.to_string()),
+            ),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::{EventAccess, OrganizerRole};
+
+    #[rstest]
+    #[case(OrganizerRole::Owner, EventAccess::Manage, true)]
+    #[case(OrganizerRole::Editor, EventAccess::Manage, false)]
+    #[case(OrganizerRole::Editor, EventAccess::Edit, true)]
+    #[case(OrganizerRole::Viewer, EventAccess::Edit, false)]
+    #[case(OrganizerRole::Viewer, EventAccess::View, true)]
+    fn test_role_allows(
+        #[case] role: OrganizerRole,
+        #[case] access: EventAccess,
+        #[case] allowed: bool,
+    ) {
+        assert_eq!(role.allows(access), allowed);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::{
+    error::BuildError,
+    types::{AttributeValue, Delete, Put, TransactWriteItem, Update},
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    database::{errors::DatabaseQueryFailed, util::failed_transaction_condition},
+    events::{
+        models::{columns, Event},
+        queries::DynamodbQueries,
+    },
+};
+
+use super::{
+    errors::OrganizerError,
+    models::{Organizer, OrganizerRole},
+};
+
+fn transaction_build_failed(e: BuildError) -> DatabaseQueryFailed {
+    error!("Failed to build transaction item: {e:?}");
+    sentry::capture_error(&e);
+    DatabaseQueryFailed
+}
+
+impl DynamodbQueries {
+    fn organizer_key(event_id: Uuid, username: &str) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+            (
+                "SK".to_owned(),
+                AttributeValue::S(Organizer::sort_key_for(username)),
+            ),
+        ])
+    }
+
+    /// The role `username` has for `event`, if they organize it at all
+    pub async fn organizer_role(
+        &self,
+        event: &Event,
+        username: &str,
+    ) -> Result<Option<OrganizerRole>, OrganizerError> {
+        if event.creator_username == username {
+            return Ok(Some(OrganizerRole::Owner));
+        }
+
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::organizer_key(event.id, username)))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                OrganizerError::from(DatabaseQueryFailed)
+            })?;
+
+        res.item
+            .map(|item| Organizer::try_from(&item).map(|organizer| organizer.role))
+            .transpose()
+            .map_err(|e| {
+                error!("Failed to parse organizer: {e:?}");
+                sentry::capture_error(&e);
+                OrganizerError::from(e)
+            })
+    }
+
+    /// Lists the co-organizers of an event, not including its owner
+    pub async fn get_organizers(&self, event_id: Uuid) -> Result<Vec<Organizer>, OrganizerError> {
+        let mut organizers = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .key_condition_expression("PK = :eventId AND begins_with(SK, :prefix)")
+                .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(Organizer::SORT_KEY_PREFIX.to_owned()),
+                )
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    OrganizerError::from(DatabaseQueryFailed)
+                })?;
+
+            for item in res.items() {
+                let organizer = Organizer::try_from(item).map_err(|e| {
+                    error!("Failed to parse organizer: {e:?}");
+                    sentry::capture_error(&e);
+                    OrganizerError::from(e)
+                })?;
+                organizers.push(organizer);
+            }
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(organizers)
+    }
+
+    /// Adds a co-organizer, or changes the role of an existing one
+    pub async fn put_organizer(&self, organizer: &Organizer) -> Result<(), OrganizerError> {
+        if organizer.role == OrganizerRole::Owner {
+            return Err(OrganizerError::InvalidRole);
+        }
+
+        self.client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(organizer.into()))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                OrganizerError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    pub async fn remove_organizer(
+        &self,
+        event_id: Uuid,
+        username: &str,
+    ) -> Result<(), OrganizerError> {
+        self.client
+            .delete_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::organizer_key(event_id, username)))
+            .condition_expression("attribute_exists(PK)")
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    return OrganizerError::NotFound;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                OrganizerError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    /// Makes `new_owner` the owner of the event. The previous owner stays on as an editor.
+    pub async fn transfer_ownership(
+        &self,
+        event: &Event,
+        new_owner: &str,
+    ) -> Result<(), OrganizerError> {
+        if event.creator_username == new_owner {
+            return Err(OrganizerError::AlreadyOwner);
+        }
+
+        let change_owner = Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(event.id)))
+            .update_expression("SET #C = :new ADD #V :one")
+            .condition_expression("#C = :old")
+            .expression_attribute_names("#C", columns::CREATOR_COLUMN)
+            .expression_attribute_names("#V", columns::VERSION_COLUMN)
+            .expression_attribute_values(":new", AttributeValue::S(new_owner.to_owned()))
+            .expression_attribute_values(":old", AttributeValue::S(event.creator_username.clone()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        let previous_owner = Organizer {
+            event_id: event.id,
+            username: event.creator_username.clone(),
+            role: OrganizerRole::Editor,
+        };
+        let keep_previous_owner = Put::builder()
+            .table_name(self.table_name)
+            .set_item(Some((&previous_owner).into()))
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        // The new owner is the creator of the event, so needs no organizer item of their own
+        let remove_new_owner = Delete::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::organizer_key(event.id, new_owner)))
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        self.client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().update(change_owner).build())
+            .transact_items(
+                TransactWriteItem::builder()
+                    .put(keep_previous_owner)
+                    .build(),
+            )
+            .transact_items(
+                TransactWriteItem::builder()
+                    .delete(remove_new_owner)
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|s| {
+                if failed_transaction_condition(s.as_service_error()) == Some(0) {
+                    tracing::debug!("Owner of event {} changed during transfer", event.id);
+                    return OrganizerError::OwnershipConflict;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                OrganizerError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        events::queries::DynamodbQueries,
+        organizers::models::{Organizer, OrganizerRole},
+        test_fixtures::{init_dynamodb, insert_test_event},
+    };
+
+    #[tokio::test]
+    async fn test_transfer_ownership() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        queries
+            .put_organizer(&Organizer {
+                event_id,
+                username: "editor".to_owned(),
+                role: OrganizerRole::Editor,
+            })
+            .await
+            .expect("Failed to add organizer");
+
+        queries
+            .transfer_ownership(&event, "editor")
+            .await
+            .expect("Failed to transfer ownership");
+
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        let organizers = queries
+            .get_organizers(event_id)
+            .await
+            .expect("Failed to get organizers");
+        assert_eq!(event.creator_username, "editor");
+        assert_eq!(organizers.len(), 1);
+        assert_eq!(organizers[0].username, "Google_104204918422142738931");
+        assert_eq!(organizers[0].role, OrganizerRole::Editor);
+    }
+}
@@ -0,0 +1,179 @@
+use std::{env, sync::OnceLock, time::Duration};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use sha2::Sha256;
+use tracing::error;
+
+use crate::configuration::{TOKEN_SIGNING_SECRET, TOKEN_SIGNING_SECRET_ARN};
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Port of the AWS Parameters and Secrets Lambda extension, unless configured otherwise
+const DEFAULT_EXTENSION_PORT: &str = "2773";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+static SIGNING_KEY: OnceLock<Vec<u8>> = OnceLock::new();
+
+#[derive(thiserror::Error, Debug)]
+#[error("Token is invalid")]
+pub struct InvalidTokenError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum SigningKeyError {
+    #[error("Neither TOKEN_SIGNING_SECRET_ARN nor TOKEN_SIGNING_SECRET is set")]
+    NotConfigured,
+    #[error("Failed to fetch signing key")]
+    FetchFailed,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SecretValue {
+    secret_string: String,
+}
+
+/// Reads the secret through the Parameters and Secrets extension, which runs next to the
+/// function and authenticates with the credentials of its role
+fn fetch_secret(arn: &str) -> Result<String, SigningKeyError> {
+    let port = env::var("PARAMETERS_SECRETS_EXTENSION_HTTP_PORT")
+        .unwrap_or_else(|_| DEFAULT_EXTENSION_PORT.to_owned());
+    let session_token = env::var("AWS_SESSION_TOKEN").unwrap_or_default();
+    let body = ureq::get(&format!("http://localhost:{port}/secretsmanager/get"))
+        .query("secretId", arn)
+        .set("X-Aws-Parameters-Secrets-Token", &session_token)
+        .timeout(FETCH_TIMEOUT)
+        .call()
+        .map_err(|e| {
+            error!("Failed to fetch signing key: {e:?}");
+            sentry::capture_error(&e);
+            SigningKeyError::FetchFailed
+        })?
+        .into_string()
+        .map_err(|e| {
+            error!("Failed to read signing key: {e:?}");
+            sentry::capture_error(&e);
+            SigningKeyError::FetchFailed
+        })?;
+    let secret: SecretValue = serde_json::from_str(&body).map_err(|e| {
+        error!("Failed to parse signing key: {e:?}");
+        SigningKeyError::FetchFailed
+    })?;
+    Ok(secret.secret_string)
+}
+
+/// Loads the signing key, once when the function starts rather than on every token
+pub async fn load_signing_key() -> Result<(), SigningKeyError> {
+    let key = match (
+        TOKEN_SIGNING_SECRET.as_ref(),
+        TOKEN_SIGNING_SECRET_ARN.as_ref(),
+    ) {
+        (Some(key), _) => key.clone(),
+        (None, Some(arn)) => {
+            let arn = arn.clone();
+            tokio::task::spawn_blocking(move || fetch_secret(&arn))
+                .await
+                .map_err(|e| {
+                    error!("Signing key fetch did not complete: {e:?}");
+                    SigningKeyError::FetchFailed
+                })??
+        }
+        (None, None) => return Err(SigningKeyError::NotConfigured),
+    };
+    // Loading twice keeps the first key, which tokens may already be signed with
+    let _ = SIGNING_KEY.set(key.into_bytes());
+    Ok(())
+}
+
+fn signing_key() -> &'static [u8] {
+    SIGNING_KEY
+        .get()
+        .expect("Signing key is loaded when the function starts")
+}
+
+fn mac(purpose: &str, key: &[u8]) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any length");
+    // Keeps a token signed for one purpose from being accepted for another
+    mac.update(purpose.as_bytes());
+    mac.update(&[0]);
+    mac
+}
+
+/// Signs `payload` into a URL safe token of the form `<payload>.<signature>`
+pub fn sign_with_key(purpose: &str, payload: &[u8], key: &[u8]) -> String {
+    let mut mac = mac(purpose, key);
+    mac.update(payload);
+    let signature = mac.finalize().into_bytes();
+    format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(payload),
+        URL_SAFE_NO_PAD.encode(signature)
+    )
+}
+
+/// Returns the payload of a token created by [`sign_with_key`], if the signature is valid
+pub fn verify_with_key(
+    purpose: &str,
+    token: &str,
+    key: &[u8],
+) -> Result<Vec<u8>, InvalidTokenError> {
+    let (payload, signature) = token.split_once('.').ok_or(InvalidTokenError)?;
+    let payload = URL_SAFE_NO_PAD
+        .decode(payload)
+        .map_err(|_| InvalidTokenError)?;
+    let signature = URL_SAFE_NO_PAD
+        .decode(signature)
+        .map_err(|_| InvalidTokenError)?;
+
+    let mut mac = mac(purpose, key);
+    mac.update(&payload);
+    mac.verify_slice(&signature)
+        .map_err(|_| InvalidTokenError)?;
+
+    Ok(payload)
+}
+
+pub fn sign(purpose: &str, payload: &[u8]) -> String {
+    sign_with_key(purpose, payload, signing_key())
+}
+
+pub fn verify(purpose: &str, token: &str) -> Result<Vec<u8>, InvalidTokenError> {
+    verify_with_key(purpose, token, signing_key())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{sign_with_key, verify_with_key};
+
+    const KEY: &[u8] = b"test key";
+
+    #[test]
+    fn test_roundtrip() {
+        let token = sign_with_key("test", b"payload", KEY);
+        let payload = verify_with_key("test", &token, KEY).expect("Token should be valid");
+        assert_eq!(payload, b"payload");
+    }
+
+    #[test]
+    fn test_tampered_payload() {
+        let token = sign_with_key("test", b"payload", KEY);
+        let (_, signature) = token.split_once('.').unwrap();
+        let other_payload = sign_with_key("test", b"other", KEY);
+        let (other_payload, _) = other_payload.split_once('.').unwrap();
+        let tampered = format!("{other_payload}.{signature}");
+        assert!(verify_with_key("test", &tampered, KEY).is_err());
+    }
+
+    #[test]
+    fn test_other_purpose() {
+        let token = sign_with_key("test", b"payload", KEY);
+        assert!(verify_with_key("other", &token, KEY).is_err());
+    }
+
+    #[test]
+    fn test_other_key() {
+        let token = sign_with_key("test", b"payload", KEY);
+        assert!(verify_with_key("test", &token, b"other key").is_err());
+    }
+}
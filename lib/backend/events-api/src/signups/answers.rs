@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::events::questions::{Question, QuestionKind};
+
+/// A validated answer. Single choices are stored as the id of the chosen option.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Boolean(bool),
+    Number(f64),
+    Text(String),
+    Choices(Vec<String>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnswerError {
+    Required,
+    InvalidType,
+    UnknownOption,
+    TooLong,
+    OutOfRange,
+    UnknownQuestion,
+}
+
+impl AnswerError {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnswerError::Required => "REQUIRED",
+            AnswerError::InvalidType => "INVALID_TYPE",
+            AnswerError::UnknownOption => "UNKNOWN_OPTION",
+            AnswerError::TooLong => "TOO_LONG",
+            AnswerError::OutOfRange => "OUT_OF_RANGE",
+            AnswerError::UnknownQuestion => "UNKNOWN_QUESTION",
+        }
+    }
+}
+
+fn validate_answer(question: &Question, value: &Value) -> Result<Option<Answer>, AnswerError> {
+    let is_blank = match value {
+        Value::Null => true,
+        Value::String(s) => s.trim().is_empty(),
+        Value::Array(a) => a.is_empty(),
+        _ => false,
+    };
+    if is_blank {
+        return match question.required {
+            true => Err(AnswerError::Required),
+            false => Ok(None),
+        };
+    }
+
+    let answer = match (&question.kind, value) {
+        (QuestionKind::FreeText { max_length }, Value::String(text)) => {
+            if max_length.is_some_and(|max_length| text.chars().count() > max_length) {
+                return Err(AnswerError::TooLong);
+            }
+            Answer::Text(text.trim().to_owned())
+        }
+        (QuestionKind::SingleChoice { options }, Value::String(choice)) => {
+            if !options.iter().any(|option| &option.id == choice) {
+                return Err(AnswerError::UnknownOption);
+            }
+            Answer::Text(choice.clone())
+        }
+        (QuestionKind::MultiChoice { options }, Value::Array(choices)) => {
+            let mut chosen = Vec::with_capacity(choices.len());
+            for choice in choices {
+                let choice = choice.as_str().ok_or(AnswerError::InvalidType)?;
+                if !options.iter().any(|option| option.id == choice) {
+                    return Err(AnswerError::UnknownOption);
+                }
+                if !chosen.iter().any(|c| c == choice) {
+                    chosen.push(choice.to_owned());
+                }
+            }
+            Answer::Choices(chosen)
+        }
+        (QuestionKind::Number { min, max }, Value::Number(number)) => {
+            let number = number.as_f64().ok_or(AnswerError::InvalidType)?;
+            if min.is_some_and(|min| number < min) || max.is_some_and(|max| number > max) {
+                return Err(AnswerError::OutOfRange);
+            }
+            Answer::Number(number)
+        }
+        (QuestionKind::Boolean, Value::Bool(answer)) => Answer::Boolean(*answer),
+        _ => return Err(AnswerError::InvalidType),
+    };
+
+    Ok(Some(answer))
+}
+
+/// Validates answers against the questions of the event, keyed by question id. Returns every
+/// invalid answer, so they can all be shown to the participant at once.
+pub fn validate_answers(
+    questions: &[Question],
+    answers: &HashMap<String, Value>,
+) -> Result<HashMap<String, Answer>, HashMap<String, AnswerError>> {
+    let mut errors: HashMap<String, AnswerError> = answers
+        .keys()
+        .filter(|id| !questions.iter().any(|question| &question.id == *id))
+        .map(|id| (id.clone(), AnswerError::UnknownQuestion))
+        .collect();
+    let mut validated = HashMap::new();
+
+    for question in questions {
+        let value = answers.get(&question.id).unwrap_or(&Value::Null);
+        match validate_answer(question, value) {
+            Ok(Some(answer)) => {
+                validated.insert(question.id.clone(), answer);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                errors.insert(question.id.clone(), e);
+            }
+        }
+    }
+
+    match errors.is_empty() {
+        true => Ok(validated),
+        false => Err(errors),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::{fixture, rstest};
+    use serde_json::{json, Value};
+
+    use crate::events::questions::Question;
+
+    use super::{validate_answers, Answer, AnswerError};
+
+    #[fixture]
+    fn questions() -> Vec<Question> {
+        serde_json::from_value(json!([
+            { "id": "diet", "label": { "en": "Diet" }, "required": true, "type": "singleChoice",
+              "options": [{ "id": "vegetarian", "label": { "en": "Vegetarian" } },
+                          { "id": "vegan", "label": { "en": "Vegan" } }] },
+            { "id": "size", "label": { "en": "T-shirt size" }, "required": false, "type": "multiChoice",
+              "options": [{ "id": "s", "label": { "en": "S" } }, { "id": "m", "label": { "en": "M" } }] },
+            { "id": "guests", "label": { "en": "Guests" }, "required": false, "type": "number",
+              "min": 0, "max": 3 },
+            { "id": "car", "label": { "en": "Bringing a car?" }, "required": true, "type": "boolean" },
+            { "id": "comment", "label": { "en": "Comment" }, "required": false, "type": "freeText",
+              "maxLength": 10 }
+        ]))
+        .expect("Failed to parse questions")
+    }
+
+    fn answers(value: Value) -> HashMap<String, Value> {
+        serde_json::from_value(value).expect("Failed to parse answers")
+    }
+
+    #[rstest]
+    fn test_valid_answers(questions: Vec<Question>) {
+        let validated = validate_answers(
+            &questions,
+            &answers(json!({ "diet": "vegan", "size": ["s", "s"], "car": false, "comment": "" })),
+        )
+        .expect("Answers should be valid");
+
+        assert_eq!(
+            validated.get("diet"),
+            Some(&Answer::Text("vegan".to_owned()))
+        );
+        assert_eq!(
+            validated.get("size"),
+            Some(&Answer::Choices(vec!["s".to_owned()]))
+        );
+        assert_eq!(validated.get("car"), Some(&Answer::Boolean(false)));
+        assert!(!validated.contains_key("comment"));
+    }
+
+    #[rstest]
+    #[case(json!({ "car": true }), "diet", AnswerError::Required)]
+    #[case(json!({ "diet": "meat", "car": true }), "diet", AnswerError::UnknownOption)]
+    #[case(json!({ "diet": "vegan", "car": "yes" }), "car", AnswerError::InvalidType)]
+    #[case(json!({ "diet": "vegan", "car": true, "guests": 4 }), "guests", AnswerError::OutOfRange)]
+    #[case(json!({ "diet": "vegan", "car": true, "comment": "far too long" }), "comment", AnswerError::TooLong)]
+    #[case(json!({ "diet": "vegan", "car": true, "other": 1 }), "other", AnswerError::UnknownQuestion)]
+    fn test_invalid_answers(
+        questions: Vec<Question>,
+        #[case] given: Value,
+        #[case] question: &str,
+        #[case] expected: AnswerError,
+    ) {
+        let errors =
+            validate_answers(&questions, &answers(given)).expect_err("Answers should be invalid");
+        assert_eq!(errors.get(question), Some(&expected));
+    }
+}
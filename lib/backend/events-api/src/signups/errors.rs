@@ -0,0 +1,76 @@
+use std::collections::HashMap;
+
+use crate::{
+    database::errors::{DatabaseQueryFailed, ModelError, UnknownSdkError},
+    events::errors::GetEventError,
+};
+
+use super::answers::AnswerError;
+
+#[derive(thiserror::Error, Debug)]
+pub enum CreateSignupError {
+    #[error("Event has no spots remaining")]
+    EventFull,
+    #[error("Event has spots remaining")]
+    EventNotFull,
+    #[error("Failed to convert signup to a database item")]
+    InvalidSignupItem(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum GetSignupError {
+    #[error("Signup not found")]
+    NotFound,
+    #[error("Failed to read signup")]
+    InvalidStoredSignup(uuid::Uuid),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+    #[error(transparent)]
+    UnexpectedSdkError(#[from] UnknownSdkError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum UpdateSignupError {
+    #[error("Signup was changed by someone else")]
+    Conflict,
+    #[error("Event has no spots remaining")]
+    EventFull,
+    #[error("Only the first {0} signups on the waitlist were reordered")]
+    PartiallyReordered(usize),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+    #[error(transparent)]
+    GetEventError(#[from] GetEventError),
+    #[error(transparent)]
+    GetSignupError(#[from] GetSignupError),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SignupValidationError {
+    #[error("Signup deadline has passed")]
+    SignupClosed,
+    #[error("Event is cancelled")]
+    EventCancelled,
+    #[error("Participant has no name")]
+    MissingName,
+    #[error("Participant has no valid email")]
+    InvalidEmail,
+    #[error("Participant is not on the waitlist")]
+    NotWaitlisted,
+    #[error("Waitlist order does not match the waitlist")]
+    InvalidWaitlistOrder,
+    #[error("Signup token is invalid")]
+    InvalidToken,
+    #[error("Answers to signup questions are invalid")]
+    InvalidAnswers(HashMap<String, AnswerError>),
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error("Event has no title in the requested language")]
+    UnsupportedLanguage(String),
+    #[error("Failed to write export")]
+    WriteFailed,
+}
@@ -0,0 +1,402 @@
+use std::borrow::Cow;
+
+use bytes::Bytes;
+use serde::Deserialize;
+use time::format_description::well_known::Rfc3339;
+use tracing::error;
+
+use crate::events::{
+    models::Event,
+    questions::{ChoiceOption, Question, QuestionKind},
+};
+
+use super::{
+    answers::Answer,
+    errors::ExportError,
+    models::{waitlist, Signup, SignupStatus},
+};
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Csv,
+    Xlsx,
+}
+
+impl ExportFormat {
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Xlsx => {
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"
+            }
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Xlsx => "xlsx",
+        }
+    }
+}
+
+/// Fixed texts of the export. Languages without a translation fall back to English.
+struct Labels {
+    name: &'static str,
+    email: &'static str,
+    phone: &'static str,
+    signup_date: &'static str,
+    status: &'static str,
+    waitlist_position: &'static str,
+    confirmed: &'static str,
+    waitlisted: &'static str,
+    yes: &'static str,
+    no: &'static str,
+}
+
+const ENGLISH: Labels = Labels {
+    name: "Name",
+    email: "Email",
+    phone: "Phone",
+    signup_date: "Signup date",
+    status: "Status",
+    waitlist_position: "Waitlist position",
+    confirmed: "Confirmed",
+    waitlisted: "Waitlisted",
+    yes: "Yes",
+    no: "No",
+};
+
+const SWEDISH: Labels = Labels {
+    name: "Namn",
+    email: "E-post",
+    phone: "Telefon",
+    signup_date: "Anmälningsdatum",
+    status: "Status",
+    waitlist_position: "Plats på väntelistan",
+    confirmed: "Bekräftad",
+    waitlisted: "Väntelista",
+    yes: "Ja",
+    no: "Nej",
+};
+
+fn labels(language: &str) -> &'static Labels {
+    match language {
+        "sv" => &SWEDISH,
+        _ => &ENGLISH,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Cell {
+    Text(String),
+    Number(f64),
+    Empty,
+}
+
+/// Participants of an event as rows, with headers in one of the event's languages
+#[derive(Debug)]
+pub struct ParticipantTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+fn option_label(options: &[ChoiceOption], id: &str, language: &str) -> String {
+    options
+        .iter()
+        .find(|option| option.id == id)
+        .and_then(|option| option.label.get(language))
+        .cloned()
+        .unwrap_or_else(|| id.to_owned())
+}
+
+fn answer_cell(question: &Question, answer: Option<&Answer>, language: &str) -> Cell {
+    let labels = labels(language);
+    match (&question.kind, answer) {
+        (_, None) => Cell::Empty,
+        (_, Some(Answer::Boolean(value))) => {
+            Cell::Text(if *value { labels.yes } else { labels.no }.to_owned())
+        }
+        (_, Some(Answer::Number(value))) => Cell::Number(*value),
+        (QuestionKind::SingleChoice { options }, Some(Answer::Text(id))) => {
+            Cell::Text(option_label(options, id, language))
+        }
+        (_, Some(Answer::Text(text))) => Cell::Text(text.clone()),
+        (QuestionKind::MultiChoice { options }, Some(Answer::Choices(ids))) => Cell::Text(
+            ids.iter()
+                .map(|id| option_label(options, id, language))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+        (_, Some(Answer::Choices(ids))) => Cell::Text(ids.join(", ")),
+    }
+}
+
+impl ParticipantTable {
+    /// Lists confirmed participants in signup order, followed by the waitlist in line order
+    pub fn new(event: &Event, signups: Vec<Signup>, language: &str) -> Self {
+        let labels = labels(language);
+        let mut headers: Vec<String> = [
+            labels.name,
+            labels.email,
+            labels.phone,
+            labels.signup_date,
+            labels.status,
+            labels.waitlist_position,
+        ]
+        .into_iter()
+        .map(str::to_owned)
+        .collect();
+        headers.extend(event.questions.iter().map(|question| {
+            question
+                .label
+                .get(language)
+                .cloned()
+                .unwrap_or_else(|| question.id.clone())
+        }));
+
+        let (mut confirmed, waitlisted): (Vec<_>, Vec<_>) = signups
+            .into_iter()
+            .partition(|s| s.status == SignupStatus::Confirmed);
+        confirmed.sort_by_key(|s| s.signup_date);
+
+        let rows = confirmed
+            .iter()
+            .map(|signup| (signup, None))
+            .chain(
+                waitlist(waitlisted)
+                    .iter()
+                    .enumerate()
+                    .map(|(index, signup)| (signup, Some(index + 1))),
+            )
+            .map(|(signup, position)| {
+                let mut row = vec![
+                    Cell::Text(signup.name.clone()),
+                    Cell::Text(signup.email.clone()),
+                    signup.phone.clone().map_or(Cell::Empty, Cell::Text),
+                    Cell::Text(signup.signup_date.format(&Rfc3339).unwrap_or_default()),
+                    Cell::Text(
+                        match signup.status {
+                            SignupStatus::Confirmed => labels.confirmed,
+                            SignupStatus::Waitlisted => labels.waitlisted,
+                        }
+                        .to_owned(),
+                    ),
+                    position.map_or(Cell::Empty, |p| Cell::Number(p as f64)),
+                ];
+                row.extend(event.questions.iter().map(|question| {
+                    answer_cell(question, signup.answers.get(&question.id), language)
+                }));
+                row
+            })
+            .collect();
+
+        Self { headers, rows }
+    }
+
+    /// The CSV file one line at a time, so it can be sent as it is written
+    pub fn into_csv_lines(self) -> impl Iterator<Item = Result<Bytes, ExportError>> {
+        let headers = self.headers.into_iter().map(Cell::Text).collect();
+        std::iter::once(headers)
+            .chain(self.rows)
+            .map(|row| csv_line(&row))
+    }
+
+    pub fn to_xlsx(&self) -> Result<Vec<u8>, ExportError> {
+        let write_failed = |e: rust_xlsxwriter::XlsxError| {
+            error!("Failed to write XLSX: {e:?}");
+            sentry::capture_error(&e);
+            ExportError::WriteFailed
+        };
+        let mut workbook = rust_xlsxwriter::Workbook::new();
+        let worksheet = workbook.add_worksheet();
+        let bold = rust_xlsxwriter::Format::new().set_bold();
+        for (column, header) in self.headers.iter().enumerate() {
+            worksheet
+                .write_string_with_format(0, column as u16, header, &bold)
+                .map_err(write_failed)?;
+        }
+        for (row_index, row) in self.rows.iter().enumerate() {
+            let row_number = row_index as u32 + 1;
+            for (column, cell) in row.iter().enumerate() {
+                match cell {
+                    Cell::Text(text) => worksheet.write_string(row_number, column as u16, text),
+                    Cell::Number(number) => {
+                        worksheet.write_number(row_number, column as u16, *number)
+                    }
+                    Cell::Empty => continue,
+                }
+                .map_err(write_failed)?;
+            }
+        }
+        workbook.save_to_buffer().map_err(write_failed)
+    }
+}
+
+/// Spreadsheets run cells starting with these as formulas, so they are quoted to stay text
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+fn escape_formula(text: &str) -> Cow<'_, str> {
+    if text.starts_with(FORMULA_PREFIXES) {
+        Cow::Owned(format!("'{text}"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+fn csv_line(row: &[Cell]) -> Result<Bytes, ExportError> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(row.iter().map(|cell| match cell {
+            Cell::Text(text) => escape_formula(text).into_owned(),
+            Cell::Number(number) => number.to_string(),
+            Cell::Empty => String::new(),
+        }))
+        .map_err(|e| {
+            error!("Failed to write CSV: {e:?}");
+            sentry::capture_error(&e);
+            ExportError::WriteFailed
+        })?;
+    writer.into_inner().map(Bytes::from).map_err(|e| {
+        error!("Failed to flush CSV: {e:?}");
+        ExportError::WriteFailed
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+    use time::macros::datetime;
+    use uuid::Uuid;
+
+    use crate::test_fixtures::event;
+
+    use super::*;
+
+    fn signup(name: &str, status: SignupStatus, position: Option<u64>) -> Signup {
+        Signup {
+            id: Uuid::new_v4(),
+            event_id: Uuid::new_v4(),
+            name: name.to_owned(),
+            email: format!("{name}@example.com"),
+            phone: None,
+            signup_date: datetime!(2025-01-01 12:00 UTC),
+            status,
+            waitlist_position: position,
+            answers: HashMap::new(),
+            calendar_secret: None,
+        }
+    }
+
+    fn event_with_questions(mut event: Event) -> Event {
+        event.questions = serde_json::from_value(serde_json::json!([
+            { "id": "diet", "label": { "en": "Diet", "sv": "Kost" }, "required": false,
+              "type": "singleChoice",
+              "options": [{ "id": "vegan", "label": { "en": "Vegan", "sv": "Vegansk" } }] },
+            { "id": "car", "label": { "en": "Car", "sv": "Bil" }, "required": false,
+              "type": "boolean" }
+        ]))
+        .unwrap();
+        event
+    }
+
+    #[rstest]
+    fn test_headers_are_localized(event: Event) {
+        let event = event_with_questions(event);
+        let table = ParticipantTable::new(&event, vec![], "sv");
+        assert_eq!(
+            table.headers,
+            vec![
+                "Namn",
+                "E-post",
+                "Telefon",
+                "Anmälningsdatum",
+                "Status",
+                "Plats på väntelistan",
+                "Kost",
+                "Bil"
+            ]
+        );
+    }
+
+    #[rstest]
+    fn test_confirmed_before_waitlist(event: Event) {
+        let event = event_with_questions(event);
+        let mut confirmed = signup("anna", SignupStatus::Confirmed, None);
+        confirmed.answers = HashMap::from([
+            ("diet".to_owned(), Answer::Text("vegan".to_owned())),
+            ("car".to_owned(), Answer::Boolean(true)),
+        ]);
+        let signups = vec![
+            signup("bertil", SignupStatus::Waitlisted, Some(2)),
+            signup("cecilia", SignupStatus::Waitlisted, Some(1)),
+            confirmed,
+        ];
+
+        let table = ParticipantTable::new(&event, signups, "en");
+
+        let names: Vec<&Cell> = table.rows.iter().map(|row| &row[0]).collect();
+        assert_eq!(
+            names,
+            vec![
+                &Cell::Text("anna".to_owned()),
+                &Cell::Text("cecilia".to_owned()),
+                &Cell::Text("bertil".to_owned())
+            ]
+        );
+        assert_eq!(table.rows[0][6], Cell::Text("Vegan".to_owned()));
+        assert_eq!(table.rows[0][7], Cell::Text("Yes".to_owned()));
+        assert_eq!(table.rows[1][5], Cell::Number(1.0));
+        assert_eq!(table.rows[1][6], Cell::Empty);
+    }
+
+    #[rstest]
+    fn test_csv_export(event: Event) {
+        let table = ParticipantTable::new(
+            &event,
+            vec![signup("anna", SignupStatus::Confirmed, None)],
+            "en",
+        );
+        let csv: Vec<u8> = table
+            .into_csv_lines()
+            .map(|line| line.unwrap())
+            .collect::<Vec<_>>()
+            .concat();
+        assert_eq!(
+            String::from_utf8(csv).unwrap(),
+            "Name,Email,Phone,Signup date,Status,Waitlist position\n\
+             anna,anna@example.com,,2025-01-01T12:00:00Z,Confirmed,\n"
+        );
+    }
+
+    #[rstest]
+    #[case(
+        "=HYPERLINK(\"http://example.com\")",
+        "'=HYPERLINK(\"http://example.com\")"
+    )]
+    #[case("+46701234567", "'+46701234567")]
+    #[case("-1", "'-1")]
+    #[case("@SUM(A1)", "'@SUM(A1)")]
+    #[case("\tanna", "'\tanna")]
+    #[case("\ranna", "'\ranna")]
+    #[case("anna", "anna")]
+    #[case("anna=bertil", "anna=bertil")]
+    fn test_escape_formula(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(escape_formula(text), expected);
+    }
+
+    #[rstest]
+    fn test_xlsx_export(event: Event) {
+        let table = ParticipantTable::new(
+            &event,
+            vec![signup("anna", SignupStatus::Confirmed, None)],
+            "en",
+        );
+        let xlsx = table.to_xlsx().unwrap();
+        // XLSX files are zip archives
+        assert!(xlsx.starts_with(b"PK"));
+    }
+}
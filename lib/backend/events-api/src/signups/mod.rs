@@ -0,0 +1,7 @@
+pub mod answers;
+pub mod errors;
+pub mod export;
+pub mod models;
+pub mod queries;
+pub mod tokens;
+pub mod validation;
@@ -0,0 +1,190 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{
+            datetime_attribute, get_datetime, get_delimited, get_field, get_optional_field,
+            get_optional_nested_object, nested_object_attribute,
+        },
+    },
+    events::models::columns,
+};
+
+use super::answers::Answer;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SignupStatus {
+    Confirmed,
+    Waitlisted,
+}
+
+impl SignupStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignupStatus::Confirmed => "Confirmed",
+            SignupStatus::Waitlisted => "Waitlisted",
+        }
+    }
+}
+
+impl Display for SignupStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for SignupStatus {
+    type Err = ModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Confirmed" => Ok(SignupStatus::Confirmed),
+            "Waitlisted" => Ok(SignupStatus::Waitlisted),
+            _ => Err(ModelError::InvalidData(format!(
+                "Unknown signup status {s}"
+            ))),
+        }
+    }
+}
+
+pub struct Signup {
+    pub id: Uuid,
+    pub event_id: Uuid,
+    pub name: String,
+    pub email: String,
+    pub phone: Option<String>,
+    pub signup_date: time::OffsetDateTime,
+    pub status: SignupStatus,
+    /// Sorting key within the waitlist. Only the relative order is meaningful.
+    pub waitlist_position: Option<u64>,
+    /// Answers to the questions of the event, keyed by question id
+    pub answers: HashMap<String, Answer>,
+    /// Groups the signups shown in one participant calendar feed. Signups made before feeds were
+    /// keyed this way have none, and are in no feed.
+    pub calendar_secret: Option<Uuid>,
+}
+
+impl Signup {
+    pub const SORT_KEY_PREFIX: &str = "Signup#";
+
+    pub fn sort_key(&self) -> String {
+        Self::sort_key_for(self.id)
+    }
+
+    pub fn sort_key_for(id: Uuid) -> String {
+        format!("{}{}", Self::SORT_KEY_PREFIX, id)
+    }
+}
+
+/// Orders waitlisted signups first in line to last in line
+pub fn waitlist(signups: Vec<Signup>) -> Vec<Signup> {
+    let mut waitlist: Vec<Signup> = signups
+        .into_iter()
+        .filter(|s| s.status == SignupStatus::Waitlisted)
+        .collect();
+    waitlist.sort_by_key(|s| (s.waitlist_position, s.signup_date));
+    waitlist
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for Signup {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: get_delimited(item, columns::SORTING_KEY_COLUMN)?,
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            name: get_field(item, columns::NAME_COLUMN)?,
+            email: get_field(item, columns::EMAIL_COLUMN)?,
+            phone: get_optional_field(item, columns::PHONE_COLUMN, ScalarAttributeType::S)?,
+            signup_date: get_datetime(item, columns::SIGNUP_DATE_COLUMN)?,
+            status: get_field(item, columns::SIGNUP_STATUS_COLUMN)?,
+            waitlist_position: get_optional_field(
+                item,
+                columns::WAITLIST_POSITION_COLUMN,
+                ScalarAttributeType::N,
+            )?,
+            answers: get_optional_nested_object(item, columns::ANSWERS_COLUMN)?.unwrap_or_default(),
+            calendar_secret: get_optional_field(
+                item,
+                columns::CALENDAR_SECRET_COLUMN,
+                ScalarAttributeType::S,
+            )?,
+        })
+    }
+}
+
+/// A signup as found through its calendar secret, which only projects the status
+pub struct ParticipantSignup {
+    pub event_id: Uuid,
+    pub status: SignupStatus,
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for ParticipantSignup {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            status: get_field(item, columns::SIGNUP_STATUS_COLUMN)?,
+        })
+    }
+}
+
+impl TryFrom<&Signup> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(signup: &Signup) -> Result<Self, Self::Error> {
+        let mut item = HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(signup.event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(signup.sort_key()),
+            ),
+            (
+                columns::NAME_COLUMN.to_owned(),
+                AttributeValue::S(signup.name.clone()),
+            ),
+            (
+                columns::EMAIL_COLUMN.to_owned(),
+                AttributeValue::S(signup.email.clone()),
+            ),
+            (
+                columns::SIGNUP_DATE_COLUMN.to_owned(),
+                datetime_attribute(columns::SIGNUP_DATE_COLUMN, &signup.signup_date)?,
+            ),
+            (
+                columns::SIGNUP_STATUS_COLUMN.to_owned(),
+                AttributeValue::S(signup.status.to_string()),
+            ),
+            (
+                columns::ANSWERS_COLUMN.to_owned(),
+                nested_object_attribute(columns::ANSWERS_COLUMN, &signup.answers)?,
+            ),
+        ]);
+
+        if let Some(phone) = &signup.phone {
+            item.insert(
+                columns::PHONE_COLUMN.to_owned(),
+                AttributeValue::S(phone.clone()),
+            );
+        }
+        if let Some(position) = signup.waitlist_position {
+            item.insert(
+                columns::WAITLIST_POSITION_COLUMN.to_owned(),
+                AttributeValue::N(position.to_string()),
+            );
+        }
+        if let Some(secret) = signup.calendar_secret {
+            item.insert(
+                columns::CALENDAR_SECRET_COLUMN.to_owned(),
+                AttributeValue::S(secret.to_string()),
+            );
+        }
+
+        Ok(item)
+    }
+}
@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::{
+    error::BuildError,
+    types::{AttributeValue, Delete, Put, ReturnValue, TransactWriteItem, Update},
+};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::DatabaseQueryFailed,
+        util::{failed_transaction_condition, get_field, nested_object_attribute},
+    },
+    events::{
+        models::{columns, Event},
+        queries::DynamodbQueries,
+    },
+};
+
+use super::{
+    errors::{CreateSignupError, GetSignupError, UpdateSignupError},
+    models::{waitlist, ParticipantSignup, Signup, SignupStatus},
+};
+
+/// How many times a signup is retried when the event fills up or frees up while signing up
+const SIGNUP_ATTEMPTS: usize = 3;
+
+/// DynamoDB allows at most 100 items in a single transaction
+const MAX_TRANSACTION_ITEMS: usize = 100;
+
+fn transaction_build_failed(e: BuildError) -> DatabaseQueryFailed {
+    error!("Failed to build transaction item: {e:?}");
+    sentry::capture_error(&e);
+    DatabaseQueryFailed
+}
+
+impl DynamodbQueries {
+    pub(crate) fn event_key(event_id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+            (
+                "SK".to_owned(),
+                AttributeValue::S(Event::SORT_KEY_VALUE.to_owned()),
+            ),
+        ])
+    }
+
+    fn signup_key(event_id: Uuid, signup_id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+            (
+                "SK".to_owned(),
+                AttributeValue::S(Signup::sort_key_for(signup_id)),
+            ),
+        ])
+    }
+
+    pub async fn get_signup(
+        &self,
+        event_id: Uuid,
+        signup_id: Uuid,
+    ) -> Result<Signup, GetSignupError> {
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::signup_key(event_id, signup_id)))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                GetSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        let item = res.item.ok_or_else(|| {
+            tracing::debug!("Failed to find signup {signup_id} for event {event_id}");
+            GetSignupError::NotFound
+        })?;
+        Signup::try_from(&item).map_err(|e| {
+            error!("Failed to parse signup: {e:?}");
+            sentry::capture_error(&e);
+            GetSignupError::InvalidStoredSignup(signup_id)
+        })
+    }
+
+    pub async fn get_signups(&self, event_id: Uuid) -> Result<Vec<Signup>, GetSignupError> {
+        let mut signups = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .key_condition_expression("PK = :eventId AND begins_with(SK, :prefix)")
+                .expression_attribute_values(":eventId", AttributeValue::S(event_id.to_string()))
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(Signup::SORT_KEY_PREFIX.to_owned()),
+                )
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    GetSignupError::from(DatabaseQueryFailed)
+                })?;
+
+            for item in res.items() {
+                let signup = Signup::try_from(item).map_err(|e| {
+                    error!("Failed to parse signup: {e:?}");
+                    sentry::capture_error(&e);
+                    GetSignupError::InvalidStoredSignup(event_id)
+                })?;
+                signups.push(signup);
+            }
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(signups)
+    }
+
+    /// Finds the signups in the participant calendar feed of `calendar_secret`, across all events
+    pub async fn get_signups_by_calendar(
+        &self,
+        calendar_secret: Uuid,
+    ) -> Result<Vec<ParticipantSignup>, GetSignupError> {
+        let mut signups = Vec::new();
+        let mut exclusive_start_key = None;
+        loop {
+            let res = self
+                .client
+                .query()
+                .table_name(self.table_name)
+                .index_name(columns::SIGNUPS_BY_CALENDAR_INDEX)
+                .key_condition_expression("#S = :secret AND begins_with(SK, :prefix)")
+                .expression_attribute_names("#S", columns::CALENDAR_SECRET_COLUMN)
+                .expression_attribute_values(
+                    ":secret",
+                    AttributeValue::S(calendar_secret.to_string()),
+                )
+                .expression_attribute_values(
+                    ":prefix",
+                    AttributeValue::S(Signup::SORT_KEY_PREFIX.to_owned()),
+                )
+                .set_exclusive_start_key(exclusive_start_key)
+                .send()
+                .await
+                .map_err(|s| {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    GetSignupError::from(DatabaseQueryFailed)
+                })?;
+
+            for item in res.items() {
+                let signup = ParticipantSignup::try_from(item).map_err(|e| {
+                    error!("Failed to parse signup: {e:?}");
+                    sentry::capture_error(&e);
+                    GetSignupError::InvalidStoredSignup(
+                        get_field(item, columns::PARTITION_KEY_COLUMN).unwrap_or_default(),
+                    )
+                })?;
+                signups.push(signup);
+            }
+
+            exclusive_start_key = res.last_evaluated_key;
+            if exclusive_start_key.is_none() {
+                break;
+            }
+        }
+
+        Ok(signups)
+    }
+
+    /// Signs up for a spot on the event, or for the waitlist if the event is full. Returns the
+    /// stored signup with its final status.
+    pub async fn sign_up(&self, mut signup: Signup) -> Result<Signup, CreateSignupError> {
+        for _ in 0..SIGNUP_ATTEMPTS {
+            signup.status = SignupStatus::Confirmed;
+            signup.waitlist_position = None;
+            match self.create_signup(&signup).await {
+                Err(CreateSignupError::EventFull) => {}
+                result => return result.map(|_| signup),
+            }
+
+            signup.status = SignupStatus::Waitlisted;
+            signup.waitlist_position = Some(self.next_waitlist_position(signup.event_id).await?);
+            match self.create_waitlisted_signup(&signup).await {
+                Err(CreateSignupError::EventNotFull) => {
+                    tracing::debug!("Spot freed up for {}, retrying signup", signup.id);
+                }
+                result => return result.map(|_| signup),
+            }
+        }
+
+        Err(CreateSignupError::EventFull)
+    }
+
+    /// Stores the signup and claims a spot on the event in a single transaction, so the
+    /// participants limit holds even when several people sign up at once. Spots go to those on
+    /// the waitlist first, so nobody gets one while anyone is waiting.
+    pub async fn create_signup(&self, signup: &Signup) -> Result<(), CreateSignupError> {
+        let item = HashMap::<String, AttributeValue>::try_from(signup).map_err(|e| {
+            error!("Failed to convert signup to item: {e:?}");
+            sentry::capture_error(&e);
+            CreateSignupError::from(e)
+        })?;
+
+        let claim_spot = Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(signup.event_id)))
+            .update_expression("SET #C = if_not_exists(#C, :zero) + :one")
+            .condition_expression(
+                "attribute_exists(PK) AND (attribute_not_exists(#L) OR attribute_not_exists(#C) OR #C < #L) \
+                 AND (attribute_not_exists(#W) OR #W = :zero)",
+            )
+            .expression_attribute_names("#C", columns::PARTICIPANTS_COUNT_COLUMN)
+            .expression_attribute_names("#L", columns::PARTICIPANTS_LIMIT_COLUMN)
+            .expression_attribute_names("#W", columns::WAITLIST_COUNT_COLUMN)
+            .expression_attribute_values(":zero", AttributeValue::N("0".to_owned()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        let put_signup = Put::builder()
+            .table_name(self.table_name)
+            .set_item(Some(item))
+            .condition_expression("attribute_not_exists(PK)")
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        self.client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().update(claim_spot).build())
+            .transact_items(TransactWriteItem::builder().put(put_signup).build())
+            .send()
+            .await
+            .map_err(|s| {
+                if failed_transaction_condition(s.as_service_error()) == Some(0) {
+                    tracing::debug!("Event {} is full or has a waitlist", signup.event_id);
+                    return CreateSignupError::EventFull;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                CreateSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    async fn next_waitlist_position(&self, event_id: Uuid) -> Result<u64, CreateSignupError> {
+        let res = self
+            .client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(event_id)))
+            .update_expression("ADD #S :one")
+            .condition_expression("attribute_exists(PK)")
+            .expression_attribute_names("#S", columns::WAITLIST_SEQUENCE_COLUMN)
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .return_values(ReturnValue::UpdatedNew)
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                CreateSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        res.attributes
+            .as_ref()
+            .and_then(|attributes| attributes.get(columns::WAITLIST_SEQUENCE_COLUMN))
+            .and_then(|position| position.as_n().ok())
+            .and_then(|position| position.parse().ok())
+            .ok_or_else(|| {
+                error!("Update did not return the new waitlist sequence");
+                CreateSignupError::from(DatabaseQueryFailed)
+            })
+    }
+
+    /// Stores a waitlisted signup, provided the event is still full or others are still waiting
+    async fn create_waitlisted_signup(&self, signup: &Signup) -> Result<(), CreateSignupError> {
+        let item = HashMap::<String, AttributeValue>::try_from(signup).map_err(|e| {
+            error!("Failed to convert signup to item: {e:?}");
+            sentry::capture_error(&e);
+            CreateSignupError::from(e)
+        })?;
+
+        let join_waitlist = Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(signup.event_id)))
+            .update_expression("SET #W = if_not_exists(#W, :zero) + :one")
+            .condition_expression("(attribute_exists(#L) AND #C >= #L) OR #W > :zero")
+            .expression_attribute_names("#W", columns::WAITLIST_COUNT_COLUMN)
+            .expression_attribute_names("#C", columns::PARTICIPANTS_COUNT_COLUMN)
+            .expression_attribute_names("#L", columns::PARTICIPANTS_LIMIT_COLUMN)
+            .expression_attribute_values(":zero", AttributeValue::N("0".to_owned()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        let put_signup = Put::builder()
+            .table_name(self.table_name)
+            .set_item(Some(item))
+            .condition_expression("attribute_not_exists(PK)")
+            .build()
+            .map_err(transaction_build_failed)?;
+
+        self.client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().update(join_waitlist).build())
+            .transact_items(TransactWriteItem::builder().put(put_signup).build())
+            .send()
+            .await
+            .map_err(|s| {
+                if failed_transaction_condition(s.as_service_error()) == Some(0) {
+                    tracing::debug!("Event {} has free spots again", signup.event_id);
+                    return CreateSignupError::EventNotFull;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                CreateSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    /// Updates the participant details and answers of a signup, leaving its place on the event
+    /// untouched
+    pub async fn update_signup_details(&self, signup: &Signup) -> Result<(), UpdateSignupError> {
+        let answers =
+            nested_object_attribute(columns::ANSWERS_COLUMN, &signup.answers).map_err(|e| {
+                error!("Failed to convert answers to attribute: {e:?}");
+                sentry::capture_error(&e);
+                UpdateSignupError::from(DatabaseQueryFailed)
+            })?;
+        let mut values = HashMap::from([
+            (":name".to_owned(), AttributeValue::S(signup.name.clone())),
+            (":email".to_owned(), AttributeValue::S(signup.email.clone())),
+            (":answers".to_owned(), answers),
+        ]);
+        let update_expression = match &signup.phone {
+            Some(phone) => {
+                values.insert(":phone".to_owned(), AttributeValue::S(phone.clone()));
+                "SET #N = :name, #E = :email, #A = :answers, #P = :phone"
+            }
+            None => "SET #N = :name, #E = :email, #A = :answers REMOVE #P",
+        };
+
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::signup_key(signup.event_id, signup.id)))
+            .update_expression(update_expression)
+            .condition_expression("attribute_exists(PK)")
+            .expression_attribute_names("#N", columns::NAME_COLUMN)
+            .expression_attribute_names("#E", columns::EMAIL_COLUMN)
+            .expression_attribute_names("#P", columns::PHONE_COLUMN)
+            .expression_attribute_names("#A", columns::ANSWERS_COLUMN)
+            .set_expression_attribute_values(Some(values))
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    tracing::debug!("Signup {} was removed while editing", signup.id);
+                    return UpdateSignupError::Conflict;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UpdateSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    /// Removes a signup. If it held a spot which is now free, the first person on the waitlist
+    /// is promoted in the same transaction.
+    pub async fn cancel_signup(
+        &self,
+        event_id: Uuid,
+        signup_id: Uuid,
+    ) -> Result<(), UpdateSignupError> {
+        for _ in 1..SIGNUP_ATTEMPTS {
+            match self.try_cancel_signup(event_id, signup_id).await {
+                Err(UpdateSignupError::Conflict) => {
+                    tracing::debug!("Event {event_id} changed while cancelling, retrying");
+                }
+                result => return result,
+            }
+        }
+
+        self.try_cancel_signup(event_id, signup_id).await
+    }
+
+    /// Cancels a signup based on the event as it is read, failing with a conflict if the
+    /// participants or the waitlist change before the transaction is written
+    async fn try_cancel_signup(
+        &self,
+        event_id: Uuid,
+        signup_id: Uuid,
+    ) -> Result<(), UpdateSignupError> {
+        let event = self.get_event(event_id).await?;
+        let mut signups = self.get_signups(event_id).await?;
+        let index = signups
+            .iter()
+            .position(|s| s.id == signup_id)
+            .ok_or(GetSignupError::NotFound)?;
+        let signup = signups.swap_remove(index);
+
+        // Organizers may have promoted past the limit, in which case no spot is freed
+        let frees_spot = signup.status == SignupStatus::Confirmed
+            && event
+                .limit
+                .is_some_and(|limit| event.participants_count <= limit);
+        let waitlist = waitlist(signups);
+        let next_in_line = waitlist.first().filter(|_| frees_spot);
+
+        let delete_signup = Delete::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::signup_key(event_id, signup_id)))
+            .condition_expression("#St = :status")
+            .expression_attribute_names("#St", columns::SIGNUP_STATUS_COLUMN)
+            .expression_attribute_values(":status", AttributeValue::S(signup.status.to_string()))
+            .build()
+            .map_err(transaction_build_failed)?;
+        let mut transaction = self
+            .client
+            .transact_write_items()
+            .transact_items(TransactWriteItem::builder().delete(delete_signup).build());
+
+        // The counts may have changed since the event was read, so the decision to promote or
+        // not is only written if it still holds
+        let (counter, condition_expression) = match (signup.status, next_in_line) {
+            (SignupStatus::Confirmed, Some(next_in_line)) => {
+                transaction = transaction.transact_items(
+                    TransactWriteItem::builder()
+                        .update(self.confirm_waitlisted(next_in_line)?)
+                        .build(),
+                );
+                (columns::WAITLIST_COUNT_COLUMN, "#N > :zero AND #C <= #L")
+            }
+            (SignupStatus::Confirmed, None) => (
+                columns::PARTICIPANTS_COUNT_COLUMN,
+                "#N > :zero AND (attribute_not_exists(#L) OR #C > #L \
+                 OR attribute_not_exists(#W) OR #W = :zero)",
+            ),
+            (SignupStatus::Waitlisted, _) => (columns::WAITLIST_COUNT_COLUMN, "#N > :zero"),
+        };
+        let mut release_spot = Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(event_id)))
+            .update_expression("SET #N = #N - :one")
+            .condition_expression(condition_expression)
+            .expression_attribute_names("#N", counter)
+            .expression_attribute_values(":zero", AttributeValue::N("0".to_owned()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()));
+        if signup.status == SignupStatus::Confirmed {
+            release_spot = release_spot
+                .expression_attribute_names("#C", columns::PARTICIPANTS_COUNT_COLUMN)
+                .expression_attribute_names("#L", columns::PARTICIPANTS_LIMIT_COLUMN);
+        }
+        if signup.status == SignupStatus::Confirmed && next_in_line.is_none() {
+            release_spot =
+                release_spot.expression_attribute_names("#W", columns::WAITLIST_COUNT_COLUMN);
+        }
+        let release_spot = release_spot.build().map_err(transaction_build_failed)?;
+
+        transaction
+            .transact_items(TransactWriteItem::builder().update(release_spot).build())
+            .send()
+            .await
+            .map_err(|s| {
+                if failed_transaction_condition(s.as_service_error()).is_some() {
+                    tracing::debug!("Signup {signup_id} changed while cancelling");
+                    return UpdateSignupError::Conflict;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UpdateSignupError::from(DatabaseQueryFailed)
+            })?;
+
+        if let Some(next_in_line) = next_in_line {
+            tracing::debug!("Promoted {} from the waitlist", next_in_line.id);
+        }
+
+        Ok(())
+    }
+
+    fn confirm_waitlisted(&self, signup: &Signup) -> Result<Update, DatabaseQueryFailed> {
+        Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::signup_key(signup.event_id, signup.id)))
+            .update_expression("SET #St = :confirmed REMOVE #P")
+            .condition_expression("#St = :waitlisted")
+            .expression_attribute_names("#St", columns::SIGNUP_STATUS_COLUMN)
+            .expression_attribute_names("#P", columns::WAITLIST_POSITION_COLUMN)
+            .expression_attribute_values(
+                ":confirmed",
+                AttributeValue::S(SignupStatus::Confirmed.to_string()),
+            )
+            .expression_attribute_values(
+                ":waitlisted",
+                AttributeValue::S(SignupStatus::Waitlisted.to_string()),
+            )
+            .build()
+            .map_err(transaction_build_failed)
+    }
+
+    /// Moves a signup from the waitlist to a confirmed spot. Organizers may promote past the
+    /// participants limit by not enforcing it.
+    pub async fn promote_signup(
+        &self,
+        signup: &Signup,
+        enforce_limit: bool,
+    ) -> Result<(), UpdateSignupError> {
+        let condition_expression = if enforce_limit {
+            "#W > :zero AND (attribute_not_exists(#L) OR attribute_not_exists(#C) OR #C < #L)"
+        } else {
+            "#W > :zero"
+        };
+        let claim_spot = Update::builder()
+            .table_name(self.table_name)
+            .set_key(Some(Self::event_key(signup.event_id)))
+            .update_expression("SET #C = if_not_exists(#C, :zero) + :one, #W = #W - :one")
+            .condition_expression(condition_expression)
+            .expression_attribute_names("#C", columns::PARTICIPANTS_COUNT_COLUMN)
+            .expression_attribute_names("#W", columns::WAITLIST_COUNT_COLUMN)
+            .expression_attribute_values(":zero", AttributeValue::N("0".to_owned()))
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()));
+        let claim_spot = if enforce_limit {
+            claim_spot.expression_attribute_names("#L", columns::PARTICIPANTS_LIMIT_COLUMN)
+        } else {
+            claim_spot
+        }
+        .build()
+        .map_err(transaction_build_failed)?;
+
+        self.client
+            .transact_write_items()
+            .transact_items(
+                TransactWriteItem::builder()
+                    .update(self.confirm_waitlisted(signup)?)
+                    .build(),
+            )
+            .transact_items(TransactWriteItem::builder().update(claim_spot).build())
+            .send()
+            .await
+            .map_err(
+                |s| match failed_transaction_condition(s.as_service_error()) {
+                    Some(1) if enforce_limit => {
+                        tracing::debug!("Event {} is full", signup.event_id);
+                        UpdateSignupError::EventFull
+                    }
+                    Some(_) => {
+                        tracing::debug!("Signup {} changed while promoting", signup.id);
+                        UpdateSignupError::Conflict
+                    }
+                    None => {
+                        error!("Failed to query database: {s:?}");
+                        sentry::capture_error(&s);
+                        UpdateSignupError::from(DatabaseQueryFailed)
+                    }
+                },
+            )?;
+
+        Ok(())
+    }
+
+    /// Promotes people from the waitlist, in order, until the event is full again. Returns how
+    /// many were promoted.
+    pub async fn fill_from_waitlist(&self, event_id: Uuid) -> Result<usize, UpdateSignupError> {
+        let signups = self.get_signups(event_id).await?;
+        let mut promoted = 0;
+        for signup in waitlist(signups) {
+            match self.promote_signup(&signup, true).await {
+                Ok(()) => promoted += 1,
+                Err(UpdateSignupError::EventFull) => break,
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(promoted)
+    }
+
+    /// Rewrites the waitlist positions so the signups are in line in the given order. Long
+    /// waitlists take several transactions, and if one of the later ones fails the earlier ones
+    /// stay written. Positions are set rather than moved, so writing the same order again
+    /// completes it.
+    pub async fn reorder_waitlist(
+        &self,
+        event_id: Uuid,
+        order: &[Uuid],
+    ) -> Result<(), UpdateSignupError> {
+        for (chunk_index, chunk) in order.chunks(MAX_TRANSACTION_ITEMS).enumerate() {
+            let mut transaction = self.client.transact_write_items();
+            for (index, signup_id) in chunk.iter().enumerate() {
+                let position = chunk_index * MAX_TRANSACTION_ITEMS + index + 1;
+                let update = Update::builder()
+                    .table_name(self.table_name)
+                    .set_key(Some(Self::signup_key(event_id, *signup_id)))
+                    .update_expression("SET #P = :position")
+                    .condition_expression("#St = :waitlisted")
+                    .expression_attribute_names("#P", columns::WAITLIST_POSITION_COLUMN)
+                    .expression_attribute_names("#St", columns::SIGNUP_STATUS_COLUMN)
+                    .expression_attribute_values(
+                        ":position",
+                        AttributeValue::N(position.to_string()),
+                    )
+                    .expression_attribute_values(
+                        ":waitlisted",
+                        AttributeValue::S(SignupStatus::Waitlisted.to_string()),
+                    )
+                    .build()
+                    .map_err(transaction_build_failed)?;
+                transaction =
+                    transaction.transact_items(TransactWriteItem::builder().update(update).build());
+            }
+
+            transaction.send().await.map_err(|s| {
+                let error = if failed_transaction_condition(s.as_service_error()).is_some() {
+                    tracing::debug!("Waitlist for {event_id} changed while reordering");
+                    UpdateSignupError::Conflict
+                } else {
+                    error!("Failed to query database: {s:?}");
+                    sentry::capture_error(&s);
+                    UpdateSignupError::from(DatabaseQueryFailed)
+                };
+                if chunk_index == 0 {
+                    return error;
+                }
+                let reordered = chunk_index * MAX_TRANSACTION_ITEMS;
+                error!("Only the first {reordered} on the waitlist of {event_id} were reordered");
+                UpdateSignupError::PartiallyReordered(reordered)
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use uuid::Uuid;
+
+    use crate::{
+        events::{models::columns, queries::DynamodbQueries},
+        signups::models::{waitlist, Signup, SignupStatus},
+        test_fixtures::{init_dynamodb, insert_test_event},
+    };
+
+    fn signup(event_id: Uuid) -> Signup {
+        Signup {
+            id: Uuid::new_v4(),
+            event_id,
+            name: "Participant".to_owned(),
+            email: "participant@example.com".to_owned(),
+            phone: None,
+            signup_date: time::OffsetDateTime::now_utc(),
+            status: SignupStatus::Confirmed,
+            waitlist_position: None,
+            answers: HashMap::new(),
+            calendar_secret: Some(Uuid::new_v4()),
+        }
+    }
+
+    /// Signs up until the test event is full, returning the confirmed signups
+    async fn fill_event(queries: &DynamodbQueries, event_id: Uuid) -> Vec<Signup> {
+        let limit = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database")
+            .limit
+            .expect("Test event has a participants limit");
+
+        let mut signups = Vec::new();
+        for _ in 0..limit {
+            signups.push(
+                queries
+                    .sign_up(signup(event_id))
+                    .await
+                    .expect("Failed to sign up"),
+            );
+        }
+        signups
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_counts_participants() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+
+        let signup = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        assert_eq!(signup.status, SignupStatus::Confirmed);
+        assert_eq!(event.participants_count, 1);
+        assert_eq!(event.spots_remaining(), event.limit.map(|limit| limit - 1));
+    }
+
+    #[tokio::test]
+    async fn test_get_signups_by_calendar() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let first = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+        // Signing up with the same email doesn't join the feed
+        queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        let signups = queries
+            .get_signups_by_calendar(first.calendar_secret.unwrap())
+            .await
+            .expect("Failed to get signups");
+
+        assert_eq!(signups.len(), 1);
+        assert_eq!(signups[0].event_id, event_id);
+        assert_eq!(signups[0].status, SignupStatus::Confirmed);
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_waitlists_when_full() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        fill_event(&queries, event_id).await;
+
+        let first = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+        let second = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        assert_eq!(first.status, SignupStatus::Waitlisted);
+        assert!(first.waitlist_position < second.waitlist_position);
+        assert_eq!(Some(event.participants_count), event.limit);
+        assert_eq!(event.waitlist_count, 2);
+    }
+
+    #[tokio::test]
+    async fn test_sign_up_waitlists_while_others_wait() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client.clone(), "events");
+        fill_event(&queries, event_id).await;
+        let waiting = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+        // A spot frees up that nobody on the waitlist has been given yet
+        client
+            .update_item()
+            .table_name("events")
+            .set_key(Some(DynamodbQueries::event_key(event_id)))
+            .update_expression("ADD #L :one")
+            .expression_attribute_names("#L", columns::PARTICIPANTS_LIMIT_COLUMN)
+            .expression_attribute_values(":one", AttributeValue::N("1".to_owned()))
+            .send()
+            .await
+            .expect("Failed to raise the participants limit");
+
+        let late = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        assert_eq!(waiting.status, SignupStatus::Waitlisted);
+        assert_eq!(late.status, SignupStatus::Waitlisted);
+        assert!(waiting.waitlist_position < late.waitlist_position);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_signup_promotes_first_in_line() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let confirmed = fill_event(&queries, event_id).await;
+        let first_in_line = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+        let second_in_line = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        queries
+            .cancel_signup(event_id, confirmed[0].id)
+            .await
+            .expect("Failed to cancel signup");
+
+        let promoted = queries
+            .get_signup(event_id, first_in_line.id)
+            .await
+            .expect("Failed to get signup");
+        let event = queries
+            .get_event(event_id)
+            .await
+            .expect("Failed to get event from database");
+        let waitlist = waitlist(
+            queries
+                .get_signups(event_id)
+                .await
+                .expect("Failed to get signups"),
+        );
+        assert_eq!(promoted.status, SignupStatus::Confirmed);
+        assert_eq!(Some(event.participants_count), event.limit);
+        assert_eq!(event.waitlist_count, 1);
+        assert_eq!(waitlist[0].id, second_in_line.id);
+    }
+
+    #[tokio::test]
+    async fn test_reorder_waitlist() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        fill_event(&queries, event_id).await;
+        let first = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+        let second = queries
+            .sign_up(signup(event_id))
+            .await
+            .expect("Failed to sign up");
+
+        queries
+            .reorder_waitlist(event_id, &[second.id, first.id])
+            .await
+            .expect("Failed to reorder waitlist");
+
+        let waitlist: Vec<Uuid> = waitlist(
+            queries
+                .get_signups(event_id)
+                .await
+                .expect("Failed to get signups"),
+        )
+        .iter()
+        .map(|signup| signup.id)
+        .collect();
+        assert_eq!(waitlist, vec![second.id, first.id]);
+    }
+}
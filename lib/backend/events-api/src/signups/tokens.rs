@@ -0,0 +1,62 @@
+use uuid::Uuid;
+
+use crate::signing::{self, InvalidTokenError};
+
+const PURPOSE: &str = "signup";
+
+/// Identifies a signup for participants, who have no accounts
+pub struct SignupToken {
+    pub event_id: Uuid,
+    pub signup_id: Uuid,
+}
+
+impl SignupToken {
+    fn payload(&self) -> Vec<u8> {
+        [
+            self.event_id.as_bytes().as_slice(),
+            self.signup_id.as_bytes(),
+        ]
+        .concat()
+    }
+
+    fn from_payload(payload: &[u8]) -> Result<Self, InvalidTokenError> {
+        if payload.len() != 32 {
+            return Err(InvalidTokenError);
+        }
+        let (event_id, signup_id) = payload.split_at(16);
+        Ok(Self {
+            event_id: Uuid::from_slice(event_id).map_err(|_| InvalidTokenError)?,
+            signup_id: Uuid::from_slice(signup_id).map_err(|_| InvalidTokenError)?,
+        })
+    }
+
+    pub fn encode(&self) -> String {
+        signing::sign(PURPOSE, &self.payload())
+    }
+
+    pub fn decode(token: &str) -> Result<Self, InvalidTokenError> {
+        Self::from_payload(&signing::verify(PURPOSE, token)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::signing::{sign_with_key, verify_with_key};
+
+    use super::{SignupToken, PURPOSE};
+
+    #[test]
+    fn test_payload_roundtrip() {
+        let token = SignupToken {
+            event_id: Uuid::new_v4(),
+            signup_id: Uuid::new_v4(),
+        };
+        let encoded = sign_with_key(PURPOSE, &token.payload(), b"key");
+        let payload = verify_with_key(PURPOSE, &encoded, b"key").expect("Token should be valid");
+        let decoded = SignupToken::from_payload(&payload).expect("Payload should be valid");
+        assert_eq!(decoded.event_id, token.event_id);
+        assert_eq!(decoded.signup_id, token.signup_id);
+    }
+}
@@ -0,0 +1,92 @@
+use time::OffsetDateTime;
+
+use crate::events::models::Event;
+
+use super::{errors::SignupValidationError, models::Signup};
+
+pub fn is_signup_open(event: &Event, now: OffsetDateTime) -> bool {
+    now <= event.signup_end_date
+}
+
+pub fn validate_signup(event: &Event, signup: &Signup) -> Result<(), SignupValidationError> {
+    if event.cancelled_at.is_some() {
+        return Err(SignupValidationError::EventCancelled);
+    }
+    if !is_signup_open(event, signup.signup_date) {
+        return Err(SignupValidationError::SignupClosed);
+    }
+
+    validate_participant(signup)
+}
+
+pub fn validate_participant(signup: &Signup) -> Result<(), SignupValidationError> {
+    if signup.name.trim().is_empty() {
+        return Err(SignupValidationError::MissingName);
+    }
+
+    if signup.email.trim().is_empty() || !signup.email.contains('@') {
+        return Err(SignupValidationError::InvalidEmail);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use rstest::rstest;
+    use time::Duration;
+    use uuid::Uuid;
+
+    use crate::{
+        events::models::Event,
+        signups::{
+            errors::SignupValidationError,
+            models::{Signup, SignupStatus},
+        },
+        test_fixtures::event,
+    };
+
+    use super::validate_signup;
+
+    fn signup(event: &Event, signup_date: time::OffsetDateTime) -> Signup {
+        Signup {
+            id: Uuid::new_v4(),
+            event_id: event.id,
+            name: "Participant".to_owned(),
+            email: "participant@example.com".to_owned(),
+            phone: None,
+            signup_date,
+            status: SignupStatus::Confirmed,
+            waitlist_position: None,
+            answers: HashMap::new(),
+            calendar_secret: None,
+        }
+    }
+
+    #[rstest]
+    fn test_signup_before_deadline(event: Event) {
+        let signup = signup(&event, event.signup_end_date - Duration::minutes(1));
+        assert!(validate_signup(&event, &signup).is_ok());
+    }
+
+    #[rstest]
+    fn test_signup_for_cancelled_event(mut event: Event) {
+        event.cancelled_at = Some(event.signup_end_date - Duration::days(1));
+        let signup = signup(&event, event.signup_end_date - Duration::minutes(1));
+        assert!(matches!(
+            validate_signup(&event, &signup),
+            Err(SignupValidationError::EventCancelled)
+        ));
+    }
+
+    #[rstest]
+    fn test_signup_after_deadline(event: Event) {
+        let signup = signup(&event, event.signup_end_date + Duration::minutes(1));
+        assert!(matches!(
+            validate_signup(&event, &signup),
+            Err(SignupValidationError::SignupClosed)
+        ));
+    }
+}
@@ -0,0 +1,283 @@
+use std::{collections::HashMap, io::Cursor, path::PathBuf};
+
+use aws_config::Region;
+use aws_sdk_dynamodb::types::{
+    builders::KeySchemaElementBuilder, AttributeDefinition, AttributeValue, BillingMode,
+    GlobalSecondaryIndex, Projection, ProjectionType,
+};
+use rstest::fixture;
+use serde_json::Value;
+use testcontainers_modules::{
+    localstack::LocalStack,
+    testcontainers::{runners::AsyncRunner, ContainerAsync, ImageExt},
+};
+use time::macros::datetime;
+use uuid::Uuid;
+
+use crate::events::models::{columns, Event};
+
+fn json_to_dynamodb(json: serde_json::Value) -> HashMap<String, AttributeValue> {
+    let mut item = HashMap::new();
+    if let serde_json::Value::Object(map) = json {
+        for (key, value) in map {
+            let attr_value = match value {
+                serde_json::Value::String(s) => AttributeValue::S(s),
+                serde_json::Value::Number(n) => AttributeValue::N(n.to_string()),
+                serde_json::Value::Bool(b) => AttributeValue::Bool(b),
+                _ => continue, // Skip unsupported types
+            };
+            item.insert(key, attr_value);
+        }
+    }
+    item
+}
+
+fn index(name: &str, partition_key: &str, sort_key: &str) -> GlobalSecondaryIndex {
+    GlobalSecondaryIndex::builder()
+        .index_name(name)
+        .key_schema(
+            KeySchemaElementBuilder::default()
+                .attribute_name(partition_key)
+                .key_type(aws_sdk_dynamodb::types::KeyType::Hash)
+                .build()
+                .expect("Failed to build test index key schema"),
+        )
+        .key_schema(
+            KeySchemaElementBuilder::default()
+                .attribute_name(sort_key)
+                .key_type(aws_sdk_dynamodb::types::KeyType::Range)
+                .build()
+                .expect("Failed to build test index key schema"),
+        )
+        .projection(
+            Projection::builder()
+                .projection_type(ProjectionType::All)
+                .build(),
+        )
+        .build()
+        .expect("Failed to build test table index")
+}
+
+pub async fn init_dynamodb() -> (ContainerAsync<LocalStack>, aws_sdk_dynamodb::Client) {
+    let request = LocalStack::default().with_env_var("SERVICES", "dynamodb");
+    let container = request.start().await.expect("Failed to start localstack");
+
+    let endpoint_url = format!(
+        "http://{}:{}",
+        container
+            .get_host()
+            .await
+            .expect("Failed to get local stack host"),
+        container
+            .get_host_port_ipv4(4566)
+            .await
+            .expect("Failed to get local stack port")
+    );
+    let creds = aws_sdk_dynamodb::config::Credentials::new("fake", "fake", None, None, "test");
+    let config = aws_sdk_dynamodb::config::Builder::default()
+        .behavior_version_latest()
+        .credentials_provider(creds)
+        .region(Region::new("us-east-1"))
+        .endpoint_url(endpoint_url)
+        .build();
+
+    let client = aws_sdk_dynamodb::Client::from_conf(config);
+
+    let pk_attribute = AttributeDefinition::builder()
+        .attribute_name(columns::PARTITION_KEY_COLUMN)
+        .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
+        .build()
+        .expect("Failed to build test table attribute definition");
+    let sk_attribute = AttributeDefinition::builder()
+        .attribute_name(columns::SORTING_KEY_COLUMN)
+        .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
+        .build()
+        .expect("Failed to build test table attribute definition");
+
+    let pk_schema = KeySchemaElementBuilder::default()
+        .attribute_name("PK")
+        .key_type(aws_sdk_dynamodb::types::KeyType::Hash)
+        .build()
+        .expect("Failed to build test table key schema");
+    let sk_schema = KeySchemaElementBuilder::default()
+        .attribute_name("SK")
+        .key_type(aws_sdk_dynamodb::types::KeyType::Range)
+        .build()
+        .expect("Failed to build test table key schema");
+
+    let event_date_attribute = AttributeDefinition::builder()
+        .attribute_name(columns::EVENT_DATE_COLUMN)
+        .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
+        .build()
+        .expect("Failed to build test table attribute definition");
+    let creator_attribute = AttributeDefinition::builder()
+        .attribute_name(columns::CREATOR_COLUMN)
+        .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
+        .build()
+        .expect("Failed to build test table attribute definition");
+    let calendar_secret_attribute = AttributeDefinition::builder()
+        .attribute_name(columns::CALENDAR_SECRET_COLUMN)
+        .attribute_type(aws_sdk_dynamodb::types::ScalarAttributeType::S)
+        .build()
+        .expect("Failed to build test table attribute definition");
+
+    client
+        .create_table()
+        .table_name("events")
+        .attribute_definitions(pk_attribute)
+        .attribute_definitions(sk_attribute)
+        .attribute_definitions(event_date_attribute)
+        .attribute_definitions(creator_attribute)
+        .attribute_definitions(calendar_secret_attribute)
+        .global_secondary_indexes(index(
+            columns::EVENTS_LISTING_INDEX,
+            columns::SORTING_KEY_COLUMN,
+            columns::EVENT_DATE_COLUMN,
+        ))
+        .global_secondary_indexes(index(
+            columns::EVENTS_BY_CREATOR_INDEX,
+            columns::CREATOR_COLUMN,
+            columns::PARTITION_KEY_COLUMN,
+        ))
+        .global_secondary_indexes(index(
+            columns::SIGNUPS_BY_CALENDAR_INDEX,
+            columns::CALENDAR_SECRET_COLUMN,
+            columns::SORTING_KEY_COLUMN,
+        ))
+        .billing_mode(BillingMode::PayPerRequest)
+        .key_schema(pk_schema)
+        .key_schema(sk_schema)
+        .send()
+        .await
+        .expect("Failed to create test table");
+
+    (container, client)
+}
+
+/// `image` as a client would upload it, encoded in `format`
+pub fn encoded_image(image: &image::DynamicImage, format: image::ImageFormat) -> Vec<u8> {
+    let mut data = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut data), format)
+        .expect("Failed to encode test image");
+    data
+}
+
+/// Bucket the images of tests are stored in, under the prefixes the configuration is read from
+pub const TEST_IMAGES_BUCKET: &str = "event-images";
+
+pub async fn init_s3() -> (ContainerAsync<LocalStack>, aws_sdk_s3::Client) {
+    // Every test uses the same values, so it does not matter which test sets them first
+    std::env::set_var("EVENT_IMAGES_BUCKET_NAME", TEST_IMAGES_BUCKET);
+    std::env::set_var("EVENT_IMAGES_BUCKET_PREFIX", "static/events");
+    std::env::set_var("EVENT_IMAGE_UPLOADS_PREFIX", "uploads");
+
+    let request = LocalStack::default().with_env_var("SERVICES", "s3");
+    let container = request.start().await.expect("Failed to start localstack");
+
+    let endpoint_url = format!(
+        "http://{}:{}",
+        container
+            .get_host()
+            .await
+            .expect("Failed to get local stack host"),
+        container
+            .get_host_port_ipv4(4566)
+            .await
+            .expect("Failed to get local stack port")
+    );
+    let creds = aws_sdk_s3::config::Credentials::new("fake", "fake", None, None, "test");
+    let config = aws_sdk_s3::config::Builder::default()
+        .behavior_version_latest()
+        .credentials_provider(creds)
+        .region(Region::new("us-east-1"))
+        .endpoint_url(endpoint_url)
+        .force_path_style(true)
+        .build();
+
+    let client = aws_sdk_s3::Client::from_conf(config);
+    client
+        .create_bucket()
+        .bucket(TEST_IMAGES_BUCKET)
+        .send()
+        .await
+        .expect("Failed to create test bucket");
+
+    (container, client)
+}
+
+pub async fn insert_test_event(client: &aws_sdk_dynamodb::Client) -> Uuid {
+    let event: Value = serde_json::from_str(include_str!("event.json")).unwrap();
+    let event_id = Uuid::parse_str(
+        event
+            .as_object()
+            .unwrap()
+            .get("PK")
+            .unwrap()
+            .as_str()
+            .unwrap(),
+    )
+    .unwrap();
+    let item = json_to_dynamodb(event);
+    client
+        .put_item()
+        .table_name("events")
+        .set_item(Some(item))
+        .send()
+        .await
+        .expect("Failed to insert test event");
+
+    event_id
+}
+
+#[fixture]
+pub fn event() -> Event {
+    Event {
+        id: Uuid::new_v4(),
+        title: HashMap::from([
+            ("sv".to_owned(), "Utflykt".to_owned()),
+            ("en".to_owned(), "Excursion".to_owned()),
+        ]),
+        signup_end_date: datetime!(2025-02-05 03:00 UTC),
+        event_date: datetime!(2025-03-08 09:00 UTC),
+        creator_username: "creator".to_owned(),
+        description: HashMap::from([
+            ("sv".to_owned(), "Vi ska på utflykt".to_owned()),
+            ("en".to_owned(), "We're going on a trip".to_owned()),
+        ]),
+        limit: Some(5),
+        image: None,
+        image_variants: vec![],
+        image_focal_point: None,
+        event_visible: true,
+        phone: None,
+        email: "creator@example.com".to_owned(),
+        email_visible: true,
+        organizer_name: None,
+        location_name: "Tåkern".to_owned(),
+        location_link: "https://maps.app.goo.gl/enEHVHCjwMR7cBX4A".to_owned(),
+        version: 1,
+        participants_count: 0,
+        waitlist_count: 0,
+        cancelled_at: None,
+        end_date: None,
+        all_day: false,
+        time_zone: "Europe/Stockholm".to_owned(),
+        questions: vec![],
+    }
+}
+
+pub const TEST_ISSUER: &str = "https://cognito-idp.eu-north-1.amazonaws.com/eu-north-1_test";
+pub const TEST_CLIENT_ID: &str = "test-client";
+
+/// Key set holding the public part of the key that signs test tokens
+pub fn test_key_set_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/test_fixtures/jwks.json")
+}
+
+pub fn sign_test_token(claims: &Value) -> String {
+    let mut header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::RS256);
+    header.kid = Some("test-key".to_owned());
+    let key = jsonwebtoken::EncodingKey::from_rsa_der(include_bytes!("jwt_signing_key.der"));
+    jsonwebtoken::encode(&header, claims, &key).expect("Failed to sign test token")
+}
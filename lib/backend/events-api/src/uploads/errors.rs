@@ -0,0 +1,13 @@
+use crate::database::errors::{DatabaseQueryFailed, ModelError};
+
+#[derive(thiserror::Error, Debug)]
+pub enum UploadError {
+    #[error("Image upload not found")]
+    NotFound,
+    #[error("Failed to create an upload link")]
+    PresigningFailed,
+    #[error("Failed to read image upload")]
+    InvalidStoredUpload(#[from] ModelError),
+    #[error(transparent)]
+    DatabaseQueryFailed(#[from] DatabaseQueryFailed),
+}
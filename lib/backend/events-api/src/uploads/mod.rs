@@ -0,0 +1,4 @@
+pub mod errors;
+pub mod models;
+pub mod processing;
+pub mod queries;
@@ -0,0 +1,264 @@
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+
+use aws_sdk_dynamodb::types::{AttributeValue, ScalarAttributeType};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::{
+    database::{
+        errors::ModelError,
+        util::{
+            datetime_attribute, get_datetime, get_field, get_optional_field,
+            get_optional_nested_object, nested_object_attribute,
+        },
+    },
+    events::models::columns,
+    images::framing::ImageFraming,
+};
+
+/// How long the status of an upload is kept
+const UPLOAD_RETENTION: time::Duration = time::Duration::days(1);
+/// How long processing may take before another attempt may take over the upload. Longer than
+/// the minute the Lambda may run, so only attempts which were stopped are taken over.
+pub const PROCESSING_LEASE: time::Duration = time::Duration::seconds(90);
+/// S3 retries notifying about an upload a couple of times within minutes. Uploads still
+/// processing after this are not going to finish.
+const PROCESSING_ABANDONED_AFTER: time::Duration = time::Duration::minutes(10);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UploadStatus {
+    /// Waiting for the client to upload the image
+    Pending,
+    Processing,
+    /// The image is the cover image of the event
+    Complete,
+    Failed,
+}
+
+impl UploadStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UploadStatus::Pending => "Pending",
+            UploadStatus::Processing => "Processing",
+            UploadStatus::Complete => "Complete",
+            UploadStatus::Failed => "Failed",
+        }
+    }
+}
+
+impl Display for UploadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for UploadStatus {
+    type Err = ModelError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Pending" => Ok(UploadStatus::Pending),
+            "Processing" => Ok(UploadStatus::Processing),
+            "Complete" => Ok(UploadStatus::Complete),
+            "Failed" => Ok(UploadStatus::Failed),
+            _ => Err(ModelError::InvalidData(format!(
+                "Unknown upload status {s}"
+            ))),
+        }
+    }
+}
+
+/// An image uploaded straight to the bucket, to become the cover image of the event once it
+/// is processed
+pub struct ImageUpload {
+    pub event_id: Uuid,
+    pub upload_id: Uuid,
+    pub status: UploadStatus,
+    pub requested_at: time::OffsetDateTime,
+    /// The processed image, once the upload is complete
+    pub image_id: Option<Uuid>,
+    /// Error code of a failed upload
+    pub error_code: Option<String>,
+    /// How to frame the image once it arrives
+    pub framing: ImageFraming,
+    /// When the latest attempt to process the upload started
+    pub processing_started_at: Option<time::OffsetDateTime>,
+}
+
+impl ImageUpload {
+    pub const SORT_KEY_PREFIX: &str = "Upload#";
+
+    pub fn new(event_id: Uuid) -> Self {
+        Self {
+            event_id,
+            upload_id: Uuid::new_v4(),
+            status: UploadStatus::Pending,
+            requested_at: time::OffsetDateTime::now_utc(),
+            image_id: None,
+            error_code: None,
+            framing: ImageFraming::default(),
+            processing_started_at: None,
+        }
+    }
+
+    /// Whether processing started but stopped without recording how it went, e.g. because the
+    /// Lambda timed out, and no more attempts are coming
+    pub fn is_abandoned(&self, now: time::OffsetDateTime) -> bool {
+        self.status == UploadStatus::Processing
+            && self
+                .processing_started_at
+                .is_some_and(|started_at| now - started_at > PROCESSING_ABANDONED_AFTER)
+    }
+
+    pub fn sort_key_for(upload_id: Uuid) -> String {
+        format!("{}{}", Self::SORT_KEY_PREFIX, upload_id)
+    }
+}
+
+impl TryFrom<&HashMap<String, AttributeValue>> for ImageUpload {
+    type Error = ModelError;
+    fn try_from(item: &HashMap<String, AttributeValue>) -> Result<Self, Self::Error> {
+        let sort_key: String = get_field(item, columns::SORTING_KEY_COLUMN)?;
+        let upload_id = sort_key
+            .strip_prefix(Self::SORT_KEY_PREFIX)
+            .and_then(|upload_id| upload_id.parse().ok())
+            .ok_or_else(|| ModelError::InvalidData(format!("Invalid upload key {sort_key}")))?;
+        Ok(Self {
+            event_id: get_field(item, columns::PARTITION_KEY_COLUMN)?,
+            upload_id,
+            status: get_field(item, columns::UPLOAD_STATUS_COLUMN)?,
+            requested_at: get_datetime(item, columns::REQUESTED_AT_COLUMN)?,
+            image_id: get_optional_field(item, columns::IMAGE_COLUMN, ScalarAttributeType::S)?,
+            error_code: get_optional_field(
+                item,
+                columns::UPLOAD_ERROR_COLUMN,
+                ScalarAttributeType::S,
+            )?,
+            framing: get_optional_nested_object(item, columns::IMAGE_FRAMING_COLUMN)?
+                .unwrap_or_default(),
+            processing_started_at: get_optional_field::<i64>(
+                item,
+                columns::PROCESSING_STARTED_AT_COLUMN,
+                ScalarAttributeType::N,
+            )?
+            .map(|timestamp| {
+                time::OffsetDateTime::from_unix_timestamp(timestamp).map_err(|_| {
+                    ModelError::InvalidData(format!("Invalid processing start {timestamp}"))
+                })
+            })
+            .transpose()?,
+        })
+    }
+}
+
+impl TryFrom<&ImageUpload> for HashMap<String, AttributeValue> {
+    type Error = ModelError;
+    fn try_from(upload: &ImageUpload) -> Result<Self, Self::Error> {
+        let expires_at = upload.requested_at + UPLOAD_RETENTION;
+        let mut item = HashMap::from([
+            (
+                columns::PARTITION_KEY_COLUMN.to_owned(),
+                AttributeValue::S(upload.event_id.to_string()),
+            ),
+            (
+                columns::SORTING_KEY_COLUMN.to_owned(),
+                AttributeValue::S(ImageUpload::sort_key_for(upload.upload_id)),
+            ),
+            (
+                columns::UPLOAD_STATUS_COLUMN.to_owned(),
+                AttributeValue::S(upload.status.to_string()),
+            ),
+            (
+                columns::REQUESTED_AT_COLUMN.to_owned(),
+                datetime_attribute(columns::REQUESTED_AT_COLUMN, &upload.requested_at)?,
+            ),
+            // Read by the time to live of the table, which expects seconds since the epoch
+            (
+                columns::EXPIRES_AT_COLUMN.to_owned(),
+                AttributeValue::N(expires_at.unix_timestamp().to_string()),
+            ),
+        ]);
+        if let Some(image_id) = upload.image_id {
+            item.insert(
+                columns::IMAGE_COLUMN.to_owned(),
+                AttributeValue::S(image_id.to_string()),
+            );
+        }
+        if let Some(error_code) = &upload.error_code {
+            item.insert(
+                columns::UPLOAD_ERROR_COLUMN.to_owned(),
+                AttributeValue::S(error_code.clone()),
+            );
+        }
+        if let Some(started_at) = upload.processing_started_at {
+            item.insert(
+                columns::PROCESSING_STARTED_AT_COLUMN.to_owned(),
+                AttributeValue::N(started_at.unix_timestamp().to_string()),
+            );
+        }
+        if upload.framing != ImageFraming::default() {
+            item.insert(
+                columns::IMAGE_FRAMING_COLUMN.to_owned(),
+                nested_object_attribute(columns::IMAGE_FRAMING_COLUMN, &upload.framing)?,
+            );
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::types::AttributeValue;
+    use rstest::rstest;
+    use time::macros::datetime;
+    use uuid::Uuid;
+
+    use crate::images::framing::{FocalPoint, ImageFraming};
+
+    use super::{ImageUpload, UploadStatus};
+
+    #[test]
+    fn test_round_trip() {
+        let mut upload = ImageUpload::new(Uuid::new_v4());
+        upload.status = UploadStatus::Failed;
+        upload.error_code = Some("IMAGE_TOO_SMALL".to_owned());
+        upload.framing = ImageFraming {
+            crop: None,
+            focal_point: Some(FocalPoint { x: 0.5, y: 0.25 }),
+        };
+        upload.processing_started_at = Some(datetime!(2025-03-01 12:00 UTC));
+
+        let item: HashMap<String, AttributeValue> = (&upload).try_into().unwrap();
+        let parsed = ImageUpload::try_from(&item).unwrap();
+
+        assert_eq!(parsed.upload_id, upload.upload_id);
+        assert_eq!(parsed.status, UploadStatus::Failed);
+        assert_eq!(parsed.error_code, upload.error_code);
+        assert_eq!(parsed.image_id, None);
+        assert_eq!(parsed.framing, upload.framing);
+        assert_eq!(parsed.processing_started_at, upload.processing_started_at);
+    }
+
+    #[rstest]
+    #[case(UploadStatus::Processing, Some(datetime!(2025-03-01 11:59 UTC)), false)]
+    #[case(UploadStatus::Processing, Some(datetime!(2025-03-01 11:00 UTC)), true)]
+    #[case(UploadStatus::Processing, None, false)]
+    #[case(UploadStatus::Complete, Some(datetime!(2025-03-01 11:00 UTC)), false)]
+    fn test_is_abandoned(
+        #[case] status: UploadStatus,
+        #[case] processing_started_at: Option<time::OffsetDateTime>,
+        #[case] abandoned: bool,
+    ) {
+        let upload = ImageUpload {
+            status,
+            processing_started_at,
+            ..ImageUpload::new(Uuid::new_v4())
+        };
+        assert_eq!(
+            upload.is_abandoned(datetime!(2025-03-01 12:00 UTC)),
+            abandoned
+        );
+    }
+}
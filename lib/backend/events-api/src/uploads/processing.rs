@@ -0,0 +1,310 @@
+//! Images uploaded straight to the bucket, with a presigned link, wait under
+//! `EVENT_IMAGE_UPLOADS_PREFIX` until S3 notifies the image processing Lambda. It stores them
+//! in every variant as the cover image of the event, and records how that went on the upload.
+
+use aws_lambda_events::event::s3::S3Event;
+use aws_sdk_s3::presigning::{PresignedRequest, PresigningConfig};
+use lambda_runtime::{Error, LambdaEvent};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    api::error::RestError,
+    configuration::{EVENT_IMAGES_BUCKET_NAME, EVENT_IMAGE_UPLOADS_PREFIX},
+    events::queries::DynamodbQueries,
+    images::{
+        conform_image, decode_image, delete_image, discard_cover_image, errors::ImageUploadError,
+        formats::UploadFormat, is_image_too_small, upload_image, MAX_IMAGE_SIZE,
+    },
+};
+
+use super::{errors::UploadError, models::ImageUpload};
+
+/// How long a client has to start uploading once it has a link
+const UPLOAD_LINK_VALIDITY: std::time::Duration = std::time::Duration::from_secs(15 * 60);
+
+/// Where the image of an upload is put, `{prefix}/{event}/{upload}`
+pub fn upload_key(event_id: Uuid, upload_id: Uuid) -> String {
+    format!(
+        "{prefix}/{event_id}/{upload_id}",
+        prefix = &*EVENT_IMAGE_UPLOADS_PREFIX
+    )
+}
+
+/// The event and upload of an uploaded object. S3 events give keys URL encoded, which leaves
+/// these keys as they are.
+fn parse_upload_key(key: &str) -> Option<(Uuid, Uuid)> {
+    let (event_id, upload_id) = key
+        .strip_prefix(EVENT_IMAGE_UPLOADS_PREFIX.as_str())?
+        .strip_prefix('/')?
+        .split_once('/')?;
+    Some((event_id.parse().ok()?, upload_id.parse().ok()?))
+}
+
+/// A link the client can put the image of the upload to. The content type is part of the
+/// signature, so the client must send the same one.
+pub async fn presign_upload(
+    s3: &aws_sdk_s3::Client,
+    upload: &ImageUpload,
+    content_type: &str,
+) -> Result<PresignedRequest, UploadError> {
+    let config = PresigningConfig::expires_in(UPLOAD_LINK_VALIDITY).map_err(|e| {
+        error!("Invalid presigning configuration: {e:?}");
+        UploadError::PresigningFailed
+    })?;
+    s3.put_object()
+        .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+        .key(upload_key(upload.event_id, upload.upload_id))
+        .content_type(content_type)
+        .presigned(config)
+        .await
+        .map_err(|e| {
+            error!("Failed to presign upload: {e:?}");
+            sentry::capture_error(&e);
+            UploadError::PresigningFailed
+        })
+}
+
+/// Stores the uploaded image as the cover image of the event
+async fn store_upload(
+    s3: &aws_sdk_s3::Client,
+    dynamodb: &DynamodbQueries,
+    upload: &ImageUpload,
+    key: &str,
+) -> Result<Uuid, RestError> {
+    let event_id = upload.event_id;
+    let object = s3
+        .get_object()
+        .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+        .key(key)
+        .send()
+        .await
+        .map_err(|e| {
+            error!("Failed to read uploaded image: {e:?}");
+            sentry::capture_error(&e);
+            ImageUploadError::StorageError
+        })?;
+    if object
+        .content_length()
+        .is_some_and(|length| length > MAX_IMAGE_SIZE as i64)
+    {
+        return Err(ImageUploadError::ImageTooLarge.into());
+    }
+    let format = UploadFormat::from_content_type(
+        object
+            .content_type()
+            .ok_or(ImageUploadError::UnsupportedImageFormat)?,
+    )?;
+    let body = object
+        .body
+        .collect()
+        .await
+        .map_err(|e| {
+            error!("Failed to read uploaded image: {e:?}");
+            ImageUploadError::StorageError
+        })?
+        .into_bytes();
+
+    let image = decode_image(body, format, upload.framing).await?;
+    if is_image_too_small(&image) {
+        return Err(ImageUploadError::ImageTooSmall.into());
+    }
+    let rendered = conform_image(image, upload.framing.focal_point).await?;
+    let (image_id, variants) = upload_image(s3, event_id, rendered.variants).await?;
+    let replaced = match dynamodb
+        .set_event_image(event_id, image_id, &variants, rendered.focal_point)
+        .await
+    {
+        Ok(replaced) => replaced,
+        Err(e) => {
+            // Nothing refers to the image, e.g. because the event was deleted meanwhile
+            if let Err(e) = delete_image(s3, event_id, image_id, &variants).await {
+                error!("Failed to delete files of unused image {image_id}: {e:?}");
+            }
+            return Err(e.into());
+        }
+    };
+    if let Some((old_image, old_variants)) = replaced {
+        discard_cover_image(s3, dynamodb, event_id, old_image, &old_variants).await;
+    }
+
+    Ok(image_id)
+}
+
+/// Processes the upload the object with `key` belongs to, unless it is already processed
+pub async fn process_upload(
+    s3: &aws_sdk_s3::Client,
+    dynamodb: &DynamodbQueries,
+    key: &str,
+) -> Result<(), UploadError> {
+    let Some((event_id, upload_id)) = parse_upload_key(key) else {
+        error!("Ignoring upload {key}, which belongs to no upload");
+        return Ok(());
+    };
+    let Some(upload) = dynamodb
+        .start_upload_processing(event_id, upload_id, time::OffsetDateTime::now_utc())
+        .await?
+    else {
+        tracing::debug!("Upload {upload_id} of {event_id} is already processed");
+        return Ok(());
+    };
+
+    let outcome = store_upload(s3, dynamodb, &upload, key)
+        .await
+        .map_err(|e| e.error_code);
+    if let Err(error_code) = &outcome {
+        tracing::debug!("Upload {upload_id} of {event_id} failed: {error_code}");
+    }
+
+    // The upload is either stored as variants or failed, so the original is of no more use
+    if let Err(e) = s3
+        .delete_object()
+        .bucket(&*EVENT_IMAGES_BUCKET_NAME)
+        .key(key)
+        .send()
+        .await
+    {
+        error!("Failed to delete uploaded image {key}: {e:?}");
+        sentry::capture_error(&e);
+    }
+
+    dynamodb.finish_upload(event_id, upload_id, outcome).await
+}
+
+/// Handles the notifications S3 sends when images are uploaded
+pub async fn handle_s3_event(
+    s3: &aws_sdk_s3::Client,
+    dynamodb: &DynamodbQueries,
+    event: LambdaEvent<S3Event>,
+) -> Result<(), Error> {
+    for record in event.payload.records {
+        let Some(key) = record.s3.object.key else {
+            continue;
+        };
+        process_upload(s3, dynamodb, &key).await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        events::queries::DynamodbQueries,
+        images::{
+            framing::{CropRectangle, FocalPoint, ImageFraming},
+            variants::ImageSize,
+        },
+        test_fixtures::{
+            encoded_image, init_dynamodb, init_s3, insert_test_event, TEST_IMAGES_BUCKET,
+        },
+        uploads::models::{ImageUpload, UploadStatus},
+    };
+
+    use super::{presign_upload, process_upload, upload_key};
+
+    /// Uploads `image` through a presigned link, as a client would
+    async fn upload(s3: &aws_sdk_s3::Client, upload: &ImageUpload, image: &[u8]) {
+        let request = presign_upload(s3, upload, "image/png")
+            .await
+            .expect("Failed to presign upload");
+        let mut put = ureq::put(request.uri());
+        for (name, value) in request.headers() {
+            put = put.set(name, value);
+        }
+        put.send_bytes(image).expect("Failed to upload image");
+    }
+
+    #[tokio::test]
+    async fn test_process_upload() {
+        let (_dynamodb_container, dynamodb) = init_dynamodb().await;
+        let (_s3_container, s3) = init_s3().await;
+        let event_id = insert_test_event(&dynamodb).await;
+        let queries = DynamodbQueries::new(dynamodb, "events");
+        let framing = ImageFraming {
+            crop: Some(CropRectangle {
+                x: 0.25,
+                y: 0.0,
+                width: 0.5,
+                height: 1.0,
+            }),
+            focal_point: Some(FocalPoint { x: 0.5, y: 0.25 }),
+        };
+        let new_upload = ImageUpload {
+            framing,
+            ..ImageUpload::new(event_id)
+        };
+        queries.put_upload(&new_upload).await.unwrap();
+        upload(
+            &s3,
+            &new_upload,
+            &encoded_image(
+                &image::DynamicImage::new_rgb8(1600, 800),
+                image::ImageFormat::Png,
+            ),
+        )
+        .await;
+
+        let key = upload_key(event_id, new_upload.upload_id);
+        process_upload(&s3, &queries, &key)
+            .await
+            .expect("Failed to process upload");
+        // S3 may notify about the same upload again
+        process_upload(&s3, &queries, &key)
+            .await
+            .expect("Failed to process upload again");
+
+        let upload = queries
+            .get_upload(event_id, new_upload.upload_id)
+            .await
+            .unwrap();
+        assert_eq!(upload.status, UploadStatus::Complete);
+        let event = queries.get_event(event_id).await.unwrap();
+        assert_eq!(event.image, upload.image_id);
+        assert_eq!(event.image_focal_point, framing.focal_point);
+        let full = event
+            .image_variants
+            .iter()
+            .find(|variant| variant.size == ImageSize::Full)
+            .unwrap();
+        assert_eq!((full.width, full.height), (800, 800));
+        assert!(s3
+            .head_object()
+            .bucket(TEST_IMAGES_BUCKET)
+            .key(&key)
+            .send()
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_process_invalid_upload() {
+        let (_dynamodb_container, dynamodb) = init_dynamodb().await;
+        let (_s3_container, s3) = init_s3().await;
+        let event_id = insert_test_event(&dynamodb).await;
+        let queries = DynamodbQueries::new(dynamodb, "events");
+        let cover = queries.get_event(event_id).await.unwrap().image;
+        let new_upload = ImageUpload::new(event_id);
+        queries.put_upload(&new_upload).await.unwrap();
+        upload(
+            &s3,
+            &new_upload,
+            &encoded_image(
+                &image::DynamicImage::new_rgb8(100, 100),
+                image::ImageFormat::Png,
+            ),
+        )
+        .await;
+
+        process_upload(&s3, &queries, &upload_key(event_id, new_upload.upload_id))
+            .await
+            .expect("Failed to process upload");
+
+        let upload = queries
+            .get_upload(event_id, new_upload.upload_id)
+            .await
+            .unwrap();
+        assert_eq!(upload.status, UploadStatus::Failed);
+        assert_eq!(upload.error_code.as_deref(), Some("IMAGE_TOO_SMALL"));
+        assert_eq!(queries.get_event(event_id).await.unwrap().image, cover);
+    }
+}
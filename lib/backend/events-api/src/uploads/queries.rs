@@ -0,0 +1,218 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::types::{AttributeValue, ReturnValue};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::{
+    database::errors::DatabaseQueryFailed,
+    events::{models::columns, queries::DynamodbQueries},
+};
+
+use super::{
+    errors::UploadError,
+    models::{ImageUpload, UploadStatus, PROCESSING_LEASE},
+};
+
+impl DynamodbQueries {
+    fn upload_key(event_id: Uuid, upload_id: Uuid) -> HashMap<String, AttributeValue> {
+        HashMap::from([
+            ("PK".to_owned(), AttributeValue::S(event_id.to_string())),
+            (
+                "SK".to_owned(),
+                AttributeValue::S(ImageUpload::sort_key_for(upload_id)),
+            ),
+        ])
+    }
+
+    pub async fn put_upload(&self, upload: &ImageUpload) -> Result<(), UploadError> {
+        self.client
+            .put_item()
+            .table_name(self.table_name)
+            .set_item(Some(upload.try_into()?))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UploadError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+
+    pub async fn get_upload(
+        &self,
+        event_id: Uuid,
+        upload_id: Uuid,
+    ) -> Result<ImageUpload, UploadError> {
+        let res = self
+            .client
+            .get_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::upload_key(event_id, upload_id)))
+            .send()
+            .await
+            .map_err(|s| {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UploadError::from(DatabaseQueryFailed)
+            })?;
+
+        let item = res.item.ok_or(UploadError::NotFound)?;
+        ImageUpload::try_from(&item).map_err(|e| {
+            error!("Failed to parse image upload: {e:?}");
+            sentry::capture_error(&e);
+            UploadError::from(e)
+        })
+    }
+
+    /// Marks a pending upload as being processed. S3 may report an upload more than once, so
+    /// only the first attempt to start gets the upload. An attempt which was stopped before
+    /// finishing, e.g. by a timeout, is taken over once its lease runs out.
+    pub async fn start_upload_processing(
+        &self,
+        event_id: Uuid,
+        upload_id: Uuid,
+        now: time::OffsetDateTime,
+    ) -> Result<Option<ImageUpload>, UploadError> {
+        let res = self
+            .client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::upload_key(event_id, upload_id)))
+            .update_expression("SET #S = :processing, #T = :now")
+            .condition_expression("#S = :pending OR (#S = :processing AND #T < :expired)")
+            .expression_attribute_names("#S", columns::UPLOAD_STATUS_COLUMN)
+            .expression_attribute_names("#T", columns::PROCESSING_STARTED_AT_COLUMN)
+            .expression_attribute_values(
+                ":now",
+                AttributeValue::N(now.unix_timestamp().to_string()),
+            )
+            .expression_attribute_values(
+                ":expired",
+                AttributeValue::N((now - PROCESSING_LEASE).unix_timestamp().to_string()),
+            )
+            .expression_attribute_values(
+                ":processing",
+                AttributeValue::S(UploadStatus::Processing.to_string()),
+            )
+            .expression_attribute_values(
+                ":pending",
+                AttributeValue::S(UploadStatus::Pending.to_string()),
+            )
+            .return_values(ReturnValue::AllNew)
+            .send()
+            .await;
+
+        match res {
+            Ok(res) => {
+                let item = res.attributes.unwrap_or_default();
+                ImageUpload::try_from(&item).map(Some).map_err(|e| {
+                    error!("Failed to parse image upload: {e:?}");
+                    sentry::capture_error(&e);
+                    UploadError::from(e)
+                })
+            }
+            Err(s)
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception()) =>
+            {
+                Ok(None)
+            }
+            Err(s) => {
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                Err(UploadError::from(DatabaseQueryFailed))
+            }
+        }
+    }
+
+    /// Records the outcome of processing an upload: the stored image, or the error code of why
+    /// it failed
+    pub async fn finish_upload(
+        &self,
+        event_id: Uuid,
+        upload_id: Uuid,
+        outcome: Result<Uuid, String>,
+    ) -> Result<(), UploadError> {
+        let (status, column, value) = match outcome {
+            Ok(image_id) => (
+                UploadStatus::Complete,
+                columns::IMAGE_COLUMN,
+                image_id.to_string(),
+            ),
+            Err(error_code) => (
+                UploadStatus::Failed,
+                columns::UPLOAD_ERROR_COLUMN,
+                error_code,
+            ),
+        };
+        self.client
+            .update_item()
+            .table_name(self.table_name)
+            .set_key(Some(Self::upload_key(event_id, upload_id)))
+            .update_expression("SET #S = :status, #O = :outcome")
+            .condition_expression("attribute_exists(PK)")
+            .expression_attribute_names("#S", columns::UPLOAD_STATUS_COLUMN)
+            .expression_attribute_names("#O", column)
+            .expression_attribute_values(":status", AttributeValue::S(status.to_string()))
+            .expression_attribute_values(":outcome", AttributeValue::S(value))
+            .send()
+            .await
+            .map_err(|s| {
+                if s.as_service_error()
+                    .is_some_and(|e| e.is_conditional_check_failed_exception())
+                {
+                    return UploadError::NotFound;
+                }
+                error!("Failed to query database: {s:?}");
+                sentry::capture_error(&s);
+                UploadError::from(DatabaseQueryFailed)
+            })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::OffsetDateTime;
+
+    use crate::{
+        events::queries::DynamodbQueries,
+        test_fixtures::{init_dynamodb, insert_test_event},
+        uploads::models::{ImageUpload, UploadStatus, PROCESSING_LEASE},
+    };
+
+    #[tokio::test]
+    async fn test_take_over_stopped_processing() {
+        let (_container, client) = init_dynamodb().await;
+        let event_id = insert_test_event(&client).await;
+        let queries = DynamodbQueries::new(client, "events");
+        let upload = ImageUpload::new(event_id);
+        queries.put_upload(&upload).await.unwrap();
+        let started_at = OffsetDateTime::now_utc();
+
+        let first = queries
+            .start_upload_processing(event_id, upload.upload_id, started_at)
+            .await
+            .expect("Failed to start processing");
+        let while_running = queries
+            .start_upload_processing(event_id, upload.upload_id, started_at)
+            .await
+            .expect("Failed to start processing");
+        let after_lease = queries
+            .start_upload_processing(
+                event_id,
+                upload.upload_id,
+                started_at + PROCESSING_LEASE + time::Duration::seconds(1),
+            )
+            .await
+            .expect("Failed to start processing");
+
+        assert_eq!(first.unwrap().status, UploadStatus::Processing);
+        assert!(while_running.is_none());
+        assert!(after_lease.is_some());
+    }
+}
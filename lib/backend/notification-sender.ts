@@ -0,0 +1,25 @@
+import * as route53 from "aws-cdk-lib/aws-route53";
+import * as ses from "aws-cdk-lib/aws-ses";
+import { Construct } from "constructs";
+import { Domain } from "../domain";
+
+/** Sends the emails participants get when their event is cancelled or deleted */
+export class NotificationSender extends ses.EmailIdentity {
+  static readonly SENDER = `no-reply@${Domain.EVENTS_DOMAIN}`;
+
+  constructor(scope: Construct, domain: Domain) {
+    super(scope, "NotificationSender", {
+      identity: ses.Identity.domain(Domain.EVENTS_DOMAIN),
+    });
+
+    // Verifies the domain with SES, and lets receivers check the emails came from it
+    this.dkimRecords.forEach(
+      (record, index) =>
+        new route53.CnameRecord(this, `DkimRecord${index}`, {
+          zone: domain.zone,
+          recordName: record.name,
+          domainName: record.value,
+        }),
+    );
+  }
+}
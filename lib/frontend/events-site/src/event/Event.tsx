@@ -1,6 +1,5 @@
 import { JSX, useEffect, useState } from "react";
 import { useParams } from "react-router";
-import { Translation } from "../languages/common";
 import PublicViewHeader from "../common/PublicViewHeader";
 import { useTranslation} from "react-i18next";
 import { i18n } from "i18next";
@@ -27,51 +26,81 @@ interface Location {
     link: string;
 }
 
+interface ImageSource {
+    type: string;
+    srcset: string;
+}
+
+// Fractions of the width and height of the image, from its top left corner
+interface FocalPoint {
+    x: number;
+    y: number;
+}
+
+interface ImageSet {
+    sources: ImageSource[];
+    src: string;
+    width: number;
+    height: number;
+    focalPoint: FocalPoint | null;
+}
+
 interface Event {
     id: string;
-    title: Translation,
+    // Language the title and description are in
+    language: string;
+    languages: string[];
+    title: string;
     signupEndDate: string;
     eventDate: string;
     location: Location;
     contact: Contact;
-    description: Translation;
+    description: string;
     limit: number | null;
     image: string | null;
+    imageSet: ImageSet | null;
     visible: boolean;
 }
 
+// Event images are shown as a banner of this shape, cropped around their focal point
+const BANNER_ASPECT_RATIO = "16 / 9";
+
 function renderEventImage(event: Event): JSX.Element{
-    if (event.image === null) {
+    if (event.imageSet === null) {
         return <></>;
     }
 
-    let url = `/static/events/${event.id}/${event.image}.avif`;
-    if (window.location.hostname === "localhost") {
-        url = `https://events.jonsen.se${url}`;
-    }
-    return <img src={url} alt="Picture from event location" style={{ maxWidth: "100%" }} />;
+    const host = window.location.hostname === "localhost" ? "https://events.jonsen.se" : "";
+    const srcset = (srcset: string) => srcset.split(", ").map((source) => `${host}${source}`).join(", ");
+    const { sources, src, width, height, focalPoint } = event.imageSet;
+    // Keeps the subject in view wherever the banner crops the image
+    const objectPosition = focalPoint ? `${focalPoint.x * 100}% ${focalPoint.y * 100}%` : undefined;
+    return (
+        <picture style={{ display: "block", width: "100%", aspectRatio: BANNER_ASPECT_RATIO }}>
+            {sources.map((source) => <source key={source.type} type={source.type} srcSet={srcset(source.srcset)} />)}
+            <img
+                src={`${host}${src}`}
+                width={width || undefined}
+                height={height || undefined}
+                alt="Picture from event location"
+                style={{ display: "block", width: "100%", height: "100%", objectFit: "cover", objectPosition }}
+            />
+        </picture>
+    );
 
 }
 
 function renderEvent(event: Event, translator: i18n): JSX.Element {
-    // Clone translator so we don't pollute the global resources with temporary resources
-    const eventTranslator = translator.cloneInstance();
-
-    Object.keys(event.title).forEach((key) => {
-        eventTranslator.addResource(key, "translation", "title", event.title[key]);
-        eventTranslator.addResource(key, "translation", "description", event.description[key]);
-    });
-
-    console.log(eventTranslator.getResource("en", "translation", "location"))
-    // Intl.DateTimeFormat("sv-SE", { year: "numeric" }).format(event.eventDate);
+    // Texts around the event are in the same language as the event itself
+    const eventTranslator = translator.cloneInstance({ lng: event.language });
 
     return (
         <Grid2 direction="column" container spacing={2} marginTop="1rem" display="flex" alignItems="center">
             <Grid2 display="flex" justifyContent="center" size={{ xs: 12, xl: 6, lg: 10, }}>{renderEventImage(event)}</Grid2>
             <Grid2 size={{ xs: 12, md: 6 }}>
                 <Paper style={{ paddingLeft: "2rem", paddingRight: "2rem" }} elevation={12}>
-                    <h1>{eventTranslator.t("title")}</h1>
-                    <p>{eventTranslator.t("description")}</p>
+                    <h1 lang={event.language}>{event.title}</h1>
+                    <p lang={event.language}>{event.description}</p>
                     <List>
                         <ListItem>
                             <ListItemIcon><EventIcon /></ListItemIcon> 
@@ -112,14 +141,14 @@ export default function Event() {
     const {t, i18n } = useTranslation();
 
     useEffect(() => {
-        axios.default.get<Event>(`/api/public/event/${id}`)
+        axios.default.get<Event>(`/api/public/event/${id}`, { params: { lang: i18n.resolvedLanguage } })
             .then((response) => {
                 setEvent(response.data);
             })
             .catch((error) => {
                 handleRequestError(error, setErrorCode)
         });
-    }, [id])
+    }, [id, i18n.resolvedLanguage])
 
     let inner: JSX.Element;
     if (event !== null) {
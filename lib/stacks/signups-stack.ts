@@ -39,6 +39,7 @@ export class SignupsStack extends cdk.Stack {
       sentry,
       authentication,
       database: eventTable,
+      domain: zone,
     });
   }
 }